@@ -754,6 +754,48 @@ where
         self.0.set_byte_headers(headers);
     }
 
+    /// Rename some of the header names, without changing the data.
+    ///
+    /// `mapping` is a list of (old, new) pairs. Every header named like the
+    /// first element of a pair is renamed to its second element, and other
+    /// headers are kept. The header row is read first if necessary.
+    ///
+    /// Afterwards, `headers` returns the new names, and records are
+    /// deserialized by name using them. Like `set_headers`, this disables
+    /// any automatic detection of headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use serde::Deserialize;
+    /// use csv_async::AsyncDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize, Eq, PartialEq)]
+    /// struct Row {
+    ///     city: String,
+    ///     population: u64,
+    /// }
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,pop
+    /// Boston,4628910
+    /// ";
+    ///     let mut rdr = AsyncDeserializer::from_reader(data.as_bytes());
+    ///     rdr.with_renamed_headers(&[("pop", "population")]).await?;
+    ///     let row: Row = rdr.deserialize().next().await.unwrap()?;
+    ///     assert_eq!(row, Row { city: "Boston".to_string(), population: 4628910 });
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn with_renamed_headers(&mut self, mapping: &[(&str, &str)]) -> Result<()> {
+        self.0.with_renamed_headers(mapping).await
+    }
+
     /// Read a single row into the given record. Returns false when no more
     /// records could be read.
     ///
@@ -985,7 +1027,7 @@ mod tests {
     use crate::byte_record::ByteRecord;
     use crate::error::ErrorKind;
     use crate::string_record::StringRecord;
-    use crate::Trim;
+    use crate::{HeaderScheme, Trim};
 
     use super::{Position, AsyncReaderBuilder, AsyncDeserializer};
 
@@ -1428,6 +1470,36 @@ mod tests {
         });
     }
 
+    // Test that records can be deserialized into dynamic containers
+    // (maps keyed by header names and positional vectors).
+    #[test]
+    fn deserialize_into_hashmap() {
+        use std::collections::HashMap;
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,42695");
+            let mut rdr = AsyncReaderBuilder::new().create_deserializer(data);
+            let rows = rdr.deserialize::<HashMap<String, String>>().collect::<Result<Vec<_>, _>>().await.unwrap();
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0]["city"], "Boston");
+            assert_eq!(rows[0]["pop"], "4628910");
+            assert_eq!(rows[1]["city"], "Concord");
+            assert_eq!(rows[1]["pop"], "42695");
+        });
+    }
+
+    #[test]
+    fn deserialize_into_vec() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,42695");
+            let mut rdr = AsyncReaderBuilder::new().create_deserializer(data);
+            let rows = rdr.deserialize::<Vec<String>>().collect::<Result<Vec<_>, _>>().await.unwrap();
+            assert_eq!(rows, vec![
+                vec!["Boston".to_string(), "4628910".to_string()],
+                vec!["Concord".to_string(), "42695".to_string()],
+            ]);
+        });
+    }
+
     #[test]
     fn no_infinite_loop_on_io_errors() {
         struct FailingRead;
@@ -1454,4 +1526,147 @@ mod tests {
             assert!(record_results.next().await.is_none());
         });
     }
+
+    #[test]
+    fn deserialize_duplicate_headers() {
+        #[derive(Debug, Deserialize)]
+        struct Row {
+            #[allow(dead_code)]
+            id: u32,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new()
+                .error_on_duplicate_headers(true)
+                .create_deserializer(b("id,name,id\n1,foo,2\n"));
+            let mut rows = rdr.deserialize::<Row>();
+            let err = rows.next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::DuplicateHeader { ref name, .. } => assert_eq!(name, "id"),
+                ref wrong => panic!("expected DuplicateHeader but got {:?}", wrong),
+            }
+        });
+    }
+
+    #[test]
+    fn deserialize_generated_headers() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            col2: String,
+            col1: u32,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .generate_headers(Some(HeaderScheme::Numbered))
+                .create_deserializer(b("1,foo\n2,bar\n"));
+            let rows: Vec<Row> = rdr.deserialize().map(Result::unwrap).collect().await;
+            assert_eq!(rows, vec![
+                Row { col2: "foo".to_string(), col1: 1 },
+                Row { col2: "bar".to_string(), col1: 2 },
+            ]);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["col1", "col2"]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .generate_headers(Some(HeaderScheme::Prefixed("f".to_string())))
+                .create_deserializer(b("1,2\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["f1", "f2"]);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", "2"]);
+        });
+    }
+
+    #[test]
+    fn serialize_with_headers_round_trip() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            pop: u64,
+            city: String,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let headers = StringRecord::from(vec!["city", "pop"]);
+            let mut ser = crate::AsyncSerializer::from_writer(vec![]);
+            for row in &[["Boston", "4628910"], ["Concord", "42695"]] {
+                let row: Vec<String> = row.iter().map(|field| field.to_string()).collect();
+                ser.serialize_with_headers(&headers, row).await.unwrap();
+            }
+            let data = ser.into_inner().await.unwrap();
+            assert_eq!(s(&data), "city,pop\nBoston,4628910\nConcord,42695\n");
+
+            let mut rdr = AsyncDeserializer::from_reader(&data[..]);
+            let rows: Vec<Row> = rdr.deserialize().map(|row| row.unwrap()).collect().await;
+            assert_eq!(rows, vec![
+                Row { pop: 4628910, city: "Boston".to_string() },
+                Row { pop: 42695, city: "Concord".to_string() },
+            ]);
+        });
+    }
+
+    #[test]
+    fn with_renamed_headers() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            city: String,
+            population: u64,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,42695\n");
+            let mut rdr = AsyncDeserializer::from_reader(data);
+            rdr.with_renamed_headers(&[("pop", "population"), ("area", "size")]).await.unwrap();
+            let headers = rdr.headers().await.unwrap().clone();
+            assert_eq!(headers, vec!["city", "population"]);
+            assert_eq!(headers.position().unwrap().line(), 1);
+            assert_eq!(rdr.byte_headers().await.unwrap(), vec!["city", "population"]);
+
+            let rows: Vec<Row> = rdr.deserialize().map(Result::unwrap).collect().await;
+            assert_eq!(rows, vec![
+                Row { city: "Boston".to_string(), population: 4628910 },
+                Row { city: "Concord".to_string(), population: 42695 },
+            ]);
+
+            // The old name no longer matches.
+            let mut rdr = AsyncDeserializer::from_reader(data);
+            rdr.with_renamed_headers(&[("city", "town")]).await.unwrap();
+            let err = rdr.deserialize::<Row>().next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::Deserialize { .. } => {}
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+        });
+    }
+
+    #[test]
+    fn deserialize_error_location() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            city: String,
+            pop: u64,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,many\n");
+            let mut rdr = AsyncDeserializer::from_reader(data);
+            let mut rows = rdr.deserialize::<Row>();
+            assert!(rows.next().await.unwrap().is_ok());
+            let err = rows.next().await.unwrap().unwrap_err();
+            assert_eq!(err.kind().position(), Some(&newpos(24, 3, 2)));
+            match *err.kind() {
+                ErrorKind::Deserialize { ref err, .. } => {
+                    assert_eq!(err.field(), Some(1));
+                    assert_eq!(err.header(), Some("pop"));
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+            assert_eq!(
+                err.to_string(),
+                "CSV deserialize error: record 2 (line: 3, byte: 24): \
+                 field 1 (\"pop\"): invalid digit found in string"
+            );
+        });
+    }
 }
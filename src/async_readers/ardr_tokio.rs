@@ -1,4 +1,11 @@
-use tokio::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use tokio::io::{self, AsyncReadExt};
+use tokio_stream::Stream;
+#[cfg(feature = "with_serde")]
+use serde::de::DeserializeOwned;
 
 use crate::AsyncReaderBuilder;
 use crate::byte_record::{ByteRecord, Position};
@@ -6,9 +13,13 @@ use crate::error::Result;
 use crate::string_record::StringRecord;
 use super::{
     AsyncReaderImpl,
-    StringRecordsStream, StringRecordsIntoStream,
-    ByteRecordsStream, ByteRecordsIntoStream,
+    StringRecordsStream, StringRecordsIntoStream, StringRecordsWithRawStream,
+    StringRecordsWithHeaderStream,
+    ByteRecordsStream, ByteRecordsIntoStream, ColumnSummary, InferredType, ParserState, ParseTiming, SectionEvent,
+    TableReader, Warning,
 };
+#[cfg(feature = "with_serde")]
+use super::{DeserializeRecordsStream, DeserializeRecordsIntoStream};
 
 impl AsyncReaderBuilder {
     /// Build a CSV reader from this configuration that reads data from `rdr`.
@@ -41,7 +52,129 @@ impl AsyncReaderBuilder {
     pub fn create_reader<R: io::AsyncRead + std::marker::Unpin>(&self, rdr: R) -> AsyncReader<R> {
         AsyncReader::new(self, rdr)
     }
+
+    /// Build a CSV reader from this configuration that first reads the bytes
+    /// in `prefix` and then continues with data from `rdr`.
+    ///
+    /// This is useful when some bytes were already consumed from a source
+    /// that can not be seeked back, e.g. to sniff the format of the data.
+    /// The prefix is parsed as if it was still at the front of `rdr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio1::io::AsyncReadExt;
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut data = "city;pop\nBoston;4628910\n".as_bytes();
+    ///     let mut prefix = vec![0; 8];
+    ///     data.read_exact(&mut prefix).await?;
+    ///     let delimiter = if prefix.contains(&b';') { b';' } else { b',' };
+    ///
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .delimiter(delimiter)
+    ///         .create_reader_with_prefix(prefix, data);
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "pop"]);
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn create_reader_with_prefix<R: io::AsyncRead + std::marker::Unpin>(
+        &self,
+        prefix: Vec<u8>,
+        rdr: R,
+    ) -> AsyncReader<io::Chain<std::io::Cursor<Vec<u8>>, R>> {
+        AsyncReader::new(self, std::io::Cursor::new(prefix).chain(rdr))
+    }
     
+    /// Build a CSV reader from this configuration that resumes parsing from
+    /// a state captured by `AsyncReader::parser_state`, continuing with
+    /// data from `rdr`.
+    ///
+    /// `rdr` has to deliver the data following the bytes consumed by the
+    /// reader the state was captured from. A record that was cut off, even
+    /// within a quoted field, is completed with data from `rdr`. Headers and
+    /// positions carry on from the captured state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, ParserState, Position};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     // A state persisted while in the middle of a quoted field.
+    ///     let state = ParserState::new(
+    ///         b"Boston,\"Massachusetts,\n".to_vec(),
+    ///         Position::new(),
+    ///         Some(vec!["city", "region"].into()),
+    ///     );
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .create_reader_with_state(state, "United States\"\n".as_bytes());
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["Boston", "Massachusetts,\nUnited States"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn create_reader_with_state<R: io::AsyncRead + std::marker::Unpin>(
+        &self,
+        state: ParserState,
+        rdr: R,
+    ) -> AsyncReader<io::Chain<std::io::Cursor<Vec<u8>>, R>> {
+        let mut rdr = AsyncReader::new(self, std::io::Cursor::new(state.pending().to_vec()).chain(rdr));
+        rdr.0.restore_state(state);
+        rdr
+    }
+
+    /// Build a CSV reader from this configuration that reads at most `limit`
+    /// bytes from `rdr`.
+    ///
+    /// The end of the CSV data is reached after `limit` bytes, even if `rdr`
+    /// has more data. Since no more than `limit` bytes are ever read from
+    /// `rdr`, not even into the internal buffer, `rdr` is positioned right
+    /// after the CSV data once it is recovered with `into_inner`. This is
+    /// useful to parse CSV data embedded in a larger stream, e.g. as the
+    /// payload of a length-prefixed frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio1::io::AsyncReadExt;
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut data = "city,pop\nBoston,4628910\nEND".as_bytes();
+    ///     let mut rdr = AsyncReaderBuilder::new().create_reader_limited(&mut data, 24);
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     assert!(records.next().await.is_none());
+    ///     drop(records);
+    ///     drop(rdr);
+    ///
+    ///     let mut rest = String::new();
+    ///     data.read_to_string(&mut rest).await?;
+    ///     assert_eq!(rest, "END");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn create_reader_limited<R: io::AsyncRead + std::marker::Unpin>(
+        &self,
+        rdr: R,
+        limit: u64,
+    ) -> AsyncReader<io::Take<R>> {
+        AsyncReader::new(self, rdr.take(limit))
+    }
+
     /// Build a CSV parser from this configuration that reads data from `rdr`.
     #[deprecated(
         since = "1.0.1",
@@ -203,14 +336,16 @@ where
         StringRecordsStream::new(&mut self.0)
     }
 
-    /// Returns an owned iterator over all records as strings.
+    /// Returns a borrowed iterator over all records as strings, each paired
+    /// with its raw text.
     ///
-    /// Each item yielded by this iterator is a `Result<StringRecord, Error>`.
-    /// Therefore, in order to access the record, callers must handle the
-    /// possibility of error (typically with `try!` or `?`).
+    /// Each item yielded by this iterator is a `Result<(String, StringRecord), Error>`.
+    /// The `String` is the text of the record exactly as it appears in the
+    /// source data (including quotes and whitespace), without its record
+    /// terminator. This is useful for logging records that fail validation.
     ///
-    /// This is mostly useful when you want to return a CSV iterator or store
-    /// it somewhere.
+    /// If the raw text is not valid UTF-8, an `ErrorKind::Utf8` error is
+    /// returned.
     ///
     /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
     /// default), then this does not include the first record.
@@ -226,36 +361,39 @@ where
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
     /// city,country,pop
-    /// Boston,United States,4628910
+    /// Boston,\"United States\", 4628910
     /// ";
-    ///     let rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     let mut records = rdr.into_records();
-    ///     while let Some(record) = records.next().await {
-    ///         println!("{:?}", record?);
-    ///     }
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut records = rdr.records_with_raw();
+    ///     let (raw, record) = records.next().await.unwrap()?;
+    ///     assert_eq!(raw, "Boston,\"United States\", 4628910");
+    ///     assert_eq!(record, vec!["Boston", "United States", " 4628910"]);
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn into_records(self) -> StringRecordsIntoStream<'r, R> {
-        StringRecordsIntoStream::new(self.0)
+    pub fn records_with_raw(&mut self) -> StringRecordsWithRawStream<R> {
+        StringRecordsWithRawStream::new(&mut self.0)
     }
 
-    /// Returns a borrowed iterator over all records as raw bytes.
+    /// Returns a borrowed iterator yielding the header row, followed by all
+    /// records as strings.
     ///
-    /// Each item yielded by this iterator is a `Result<ByteRecord, Error>`.
-    /// Therefore, in order to access the record, callers must handle the
-    /// possibility of error (typically with `try!` or `?`).
+    /// The first item is always `RecordOrHeader::Header`, holding the same
+    /// record as `headers` returns, and all following items are
+    /// `RecordOrHeader::Record`. This delivers the header row in-band to
+    /// consumers that need it before any data, e.g. to create a table.
     ///
-    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
-    /// default), then this does not include the first record.
+    /// If `has_headers` is disabled, the header item is the first record
+    /// (or the generated headers, see `AsyncReaderBuilder::generate_headers`),
+    /// and the first record is also yielded as a record.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::AsyncReader;
     /// use tokio_stream::StreamExt;
+    /// use csv_async::{AsyncReader, RecordOrHeader};
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
@@ -264,73 +402,87 @@ where
     /// Boston,United States,4628910
     /// ";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     let mut iter = rdr.byte_records();
-    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
-    ///     assert!(iter.next().await.is_none());
+    ///     let mut items = rdr.records_with_leading_header();
+    ///     while let Some(item) = items.next().await {
+    ///         match item? {
+    ///             RecordOrHeader::Header(headers) => println!("columns: {:?}", headers),
+    ///             RecordOrHeader::Record(record) => println!("{:?}", record),
+    ///         }
+    ///     }
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn byte_records(&mut self) -> ByteRecordsStream<R> {
-        ByteRecordsStream::new(&mut self.0)
+    pub fn records_with_leading_header(&mut self) -> StringRecordsWithHeaderStream<R> {
+        StringRecordsWithHeaderStream::new(&mut self.0)
     }
 
-    /// Returns an owned iterator over all records as raw bytes.
-    ///
-    /// Each item yielded by this iterator is a `Result<ByteRecord, Error>`.
-    /// Therefore, in order to access the record, callers must handle the
-    /// possibility of error (typically with `try!` or `?`).
-    ///
-    /// This is mostly useful when you want to return a CSV iterator or store
-    /// it somewhere.
+    /// Returns a reader over the next table of a multi-table document, or
+    /// `None` when there are no more tables.
     ///
-    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
-    /// default), then this does not include the first record.
+    /// Tables are separated by blank lines when `multi_table` is enabled on
+    /// the builder; otherwise the whole input is a single table. Any records
+    /// left unread in the current table are skipped. Each table starts with
+    /// its own header row.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::AsyncReader;
     /// use tokio_stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, StringRecord};
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
-    /// city,country,pop
-    /// Boston,United States,4628910
+    /// city,pop
+    /// Boston,4628910
+    /// Concord,42695
+    ///
+    /// name,age
+    /// Alice,30
     /// ";
-    ///     let rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     let mut iter = rdr.into_byte_records();
-    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
-    ///     assert!(iter.next().await.is_none());
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .multi_table(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut headers = vec![];
+    ///     while let Some(mut table) = rdr.next_table().await? {
+    ///         headers.push(table.headers().await?.clone());
+    ///         let records = table.records().collect::<Vec<_>>().await;
+    ///         assert!(!records.is_empty());
+    ///     }
+    ///     assert_eq!(headers, vec![
+    ///         StringRecord::from(vec!["city", "pop"]),
+    ///         StringRecord::from(vec!["name", "age"]),
+    ///     ]);
     ///     Ok(())
     /// }
     /// ```
-    #[inline]
-    pub fn into_byte_records(self) -> ByteRecordsIntoStream<'r, R> {
-        ByteRecordsIntoStream::new(self.0)
+    pub async fn next_table(&mut self) -> Result<Option<TableReader<'_, R>>> {
+        if self.0.next_table().await? {
+            Ok(Some(TableReader::new(&mut self.0)))
+        } else {
+            Ok(None)
+        }
     }
 
-    /// Returns a reference to the first row read by this parser.
-    ///
-    /// If no row has been read yet, then this will force parsing of the first
-    /// row.
+    /// Returns a borrowed iterator over runs of consecutive records sharing
+    /// the same value in column `col`.
     ///
-    /// If there was a problem parsing the row or if it wasn't valid UTF-8,
-    /// then this returns an error.
+    /// Each item yielded by this iterator is a `Result<(String, Vec<StringRecord>), Error>`
+    /// holding the value of the key column and the records of the run, in
+    /// input order. Records are grouped only while they are consecutive, so
+    /// the input should be sorted by the key column to get one group per key.
+    /// Records that do not have column `col` are grouped under an empty key.
     ///
-    /// If the underlying reader emits EOF before any data, then this returns
-    /// an empty record.
+    /// An error reading a record is yielded as soon as it occurs. The group
+    /// being collected at that time is continued afterwards.
     ///
-    /// Note that this method may be used regardless of whether `has_headers`
-    /// was enabled (but it is enabled by default).
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this does not include the first record.
     ///
     /// # Example
     ///
-    /// This example shows how to get the header row of CSV data. Notice that
-    /// the header row does not appear as a record in the iterator!
-    ///
     /// ```
     /// use std::error::Error;
     /// use csv_async::AsyncReader;
@@ -339,828 +491,3670 @@ where
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
-    /// city,country,pop
-    /// Boston,United States,4628910
+    /// country,city
+    /// Poland,Warsaw
+    /// Poland,Krakow
+    /// United States,Boston
     /// ";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///
-    ///     // We can read the headers before iterating.
-    ///     {
-    ///     // `headers` borrows from the reader, so we put this in its
-    ///     // own scope. That way, the borrow ends before we try iterating
-    ///     // below. Alternatively, we could clone the headers.
-    ///     let headers = rdr.headers().await?;
-    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
-    ///     }
-    ///
-    ///     {
-    ///     let mut records = rdr.records();
-    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
-    ///     assert!(records.next().await.is_none());
-    ///     }
-    ///
-    ///     // We can also read the headers after iterating.
-    ///     let headers = rdr.headers().await?;
-    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
+    ///     let mut groups = rdr.group_by_column(0);
+    ///     let (country, cities) = groups.next().await.unwrap()?;
+    ///     assert_eq!(country, "Poland");
+    ///     assert_eq!(cities.len(), 2);
+    ///     let (country, cities) = groups.next().await.unwrap()?;
+    ///     assert_eq!(country, "United States");
+    ///     assert_eq!(cities[0], vec!["United States", "Boston"]);
+    ///     assert!(groups.next().await.is_none());
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub async fn headers(&mut self) -> Result<&StringRecord> {
-        self.0.headers().await
+    pub fn group_by_column(
+        &mut self,
+        col: usize,
+    ) -> impl Stream<Item = Result<(String, Vec<StringRecord>)>> + Unpin + '_ {
+        super::group_by_column(&mut self.0, col)
     }
 
-    /// Returns a reference to the first row read by this parser as raw bytes.
+    /// Returns a borrowed stream over the records with at least `min` and at
+    /// most `max` fields, as strings.
     ///
-    /// If no row has been read yet, then this will force parsing of the first
-    /// row.
-    ///
-    /// If there was a problem parsing the row then this returns an error.
-    ///
-    /// If the underlying reader emits EOF before any data, then this returns
-    /// an empty record.
-    ///
-    /// Note that this method may be used regardless of whether `has_headers`
-    /// was enabled (but it is enabled by default).
+    /// Records with any other number of fields are skipped silently. This
+    /// is meant for cleaning data with stray malformed rows, so the reader
+    /// should be `flexible`: otherwise, records whose length differs from
+    /// the first record are reported as errors before they can be skipped.
+    /// Errors are passed through.
     ///
     /// # Example
     ///
-    /// This example shows how to get the header row of CSV data. Notice that
-    /// the header row does not appear as a record in the iterator!
-    ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::AsyncReader;
     /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
     /// city,country,pop
     /// Boston,United States,4628910
+    /// oops
+    /// Concord,United States,42695
     /// ";
-    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///
-    ///     // We can read the headers before iterating.
-    ///     {
-    ///     // `headers` borrows from the reader, so we put this in its
-    ///     // own scope. That way, the borrow ends before we try iterating
-    ///     // below. Alternatively, we could clone the headers.
-    ///     let headers = rdr.byte_headers().await?;
-    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
-    ///     }
-    ///
-    ///     {
-    ///     let mut records = rdr.byte_records();
-    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .flexible(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records_with_arity(3, 3);
+    ///     assert_eq!(records.next().await.unwrap()?[0], *"Boston");
+    ///     assert_eq!(records.next().await.unwrap()?[0], *"Concord");
     ///     assert!(records.next().await.is_none());
-    ///     }
-    ///
-    ///     // We can also read the headers after iterating.
-    ///     let headers = rdr.byte_headers().await?;
-    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub async fn byte_headers(&mut self) -> Result<&ByteRecord> {
-        self.0.byte_headers().await
+    pub fn records_with_arity(
+        &mut self,
+        min: usize,
+        max: usize,
+    ) -> impl Stream<Item = Result<StringRecord>> + Unpin + '_ {
+        super::records_with_arity(&mut self.0, min, max)
     }
 
-    /// Set the headers of this CSV parser manually.
+    /// Returns a borrowed stream over the records as strings, each holding
+    /// only the fields of the columns with the given header names, in the
+    /// given order.
     ///
-    /// This overrides any other setting (including `set_byte_headers`). Any
-    /// automatic detection of headers is disabled. This may be called at any
-    /// time.
+    /// Names are looked up in the header row, which is read first if it was
+    /// not read yet. If the reader has no header row, they are looked up in
+    /// the first row, or in the generated names of `generate_headers`. A
+    /// name missing from the header row results in a single `UnknownHeader`
+    /// error, which ends the stream. Records too short to have a column get
+    /// an empty field in its place.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::{AsyncReader, StringRecord};
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReader;
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
     /// city,country,pop
     /// Boston,United States,4628910
+    /// Concord,United States,42695
     /// ";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///
-    ///     assert_eq!(rdr.headers().await?, vec!["city", "country", "pop"]);
-    ///     rdr.set_headers(StringRecord::from(vec!["a", "b", "c"]));
-    ///     assert_eq!(rdr.headers().await?, vec!["a", "b", "c"]);
-    ///
+    ///     let mut records = rdr.project(&["pop", "city"]);
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["4628910", "Boston"]);
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["42695", "Concord"]);
+    ///     assert!(records.next().await.is_none());
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn set_headers(&mut self, headers: StringRecord) {
-        self.0.set_headers(headers);
+    pub fn project(
+        &mut self,
+        names: &[&str],
+    ) -> impl Stream<Item = Result<StringRecord>> + Unpin + '_ {
+        super::records_projected(&mut self.0, names)
     }
 
-    /// Set the headers of this CSV parser manually as raw bytes.
+    /// Returns a borrowed stream over the values `f` maps the records to,
+    /// skipping the records it maps to `None`.
     ///
-    /// This overrides any other setting (including `set_headers`). Any
-    /// automatic detection of headers is disabled. This may be called at any
-    /// time.
+    /// `f` sees every record as a byte record, before any conversion to
+    /// strings, and a single record is reused for reading all of them: only
+    /// the values kept cost an allocation. An error returned by `f` is
+    /// yielded like a read error, and the stream continues after it.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::{AsyncReader, ByteRecord};
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReader;
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
-    /// city,country,pop
-    /// Boston,United States,4628910
+    /// city,pop
+    /// Boston,4628910
+    /// Concord,unknown
+    /// Springfield,155929
     /// ";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///
-    ///     assert_eq!(rdr.byte_headers().await?, vec!["city", "country", "pop"]);
-    ///     rdr.set_byte_headers(ByteRecord::from(vec!["a", "b", "c"]));
-    ///     assert_eq!(rdr.byte_headers().await?, vec!["a", "b", "c"]);
-    ///
+    ///     let mut pops = rdr.byte_records_filter_map(|record| {
+    ///         let pop = std::str::from_utf8(&record[1]).ok().and_then(|s| s.parse::<u64>().ok());
+    ///         Ok(pop)
+    ///     });
+    ///     assert_eq!(pops.next().await.unwrap()?, 4628910);
+    ///     assert_eq!(pops.next().await.unwrap()?, 155929);
+    ///     assert!(pops.next().await.is_none());
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn set_byte_headers(&mut self, headers: ByteRecord) {
-        self.0.set_byte_headers(headers);
+    pub fn byte_records_filter_map<'s, F, T>(
+        &'s mut self,
+        f: F,
+    ) -> impl Stream<Item = Result<T>> + Unpin + 's
+    where
+        F: FnMut(&ByteRecord) -> Result<Option<T>> + 's,
+        T: 's,
+    {
+        super::byte_records_filter_map(&mut self.0, f)
     }
 
-    /// Read a single row into the given record. Returns false when no more
-    /// records could be read.
-    ///
-    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
-    /// default), then this will never read the first record.
-    ///
-    /// This method is useful when you want to read records as fast as
-    /// as possible. It's less ergonomic than an iterator, but it permits the
-    /// caller to reuse the `StringRecord` allocation, which usually results
-    /// in higher throughput.
+    /// Returns a borrowed stream over the values `f` maps the records to,
+    /// while threading a state through all of them.
     ///
-    /// Records read via this method are guaranteed to have a position set
-    /// on them, even if the reader is at EOF or if an error is returned.
+    /// This is the analog of `Iterator::scan` for CSV records: `f` is called
+    /// with a mutable reference to the state, starting as `init`, and each
+    /// record in turn, so it can compute e.g. running totals. Read errors are
+    /// yielded without calling `f`. An error returned by `f` is yielded like
+    /// a read error, and in both cases the stream continues after it.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use csv_async::{AsyncReader, StringRecord};
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReader;
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,pop
-    /// Boston,United States,4628910
-    /// ";
+    ///     let data = "day,sales\nmon,3\ntue,5\nwed,2\n";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     let mut record = StringRecord::new();
-    ///
-    ///     if rdr.read_record(&mut record).await? {
-    ///         assert_eq!(record, vec!["Boston", "United States", "4628910"]);
-    ///         Ok(())
-    ///     } else {
-    ///         Err(From::from("expected at least one record but got none"))
-    ///     }
+    ///     let totals: Vec<u64> = rdr
+    ///         .scan_records(0, |total, record| {
+    ///             *total += record[1].parse::<u64>().unwrap_or(0);
+    ///             Ok(*total)
+    ///         })
+    ///         .map(Result::unwrap)
+    ///         .collect()
+    ///         .await;
+    ///     assert_eq!(totals, vec![3, 8, 10]);
+    ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub async fn read_record(&mut self, record: &mut StringRecord) -> Result<bool> {
-        self.0.read_record(record).await
+    pub fn scan_records<'s, S, T, F>(
+        &'s mut self,
+        init: S,
+        f: F,
+    ) -> impl Stream<Item = Result<T>> + Unpin + 's
+    where
+        S: 's,
+        T: 's,
+        F: FnMut(&mut S, StringRecord) -> Result<T> + 's,
+    {
+        super::scan_records(&mut self.0, init, f)
     }
 
-    /// Read a single row into the given byte record. Returns false when no
-    /// more records could be read.
-    ///
-    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
-    /// default), then this will never read the first record.
-    ///
-    /// This method is useful when you want to read records as fast as
-    /// as possible. It's less ergonomic than an iterator, but it permits the
-    /// caller to reuse the `ByteRecord` allocation, which usually results
-    /// in higher throughput.
+    /// Returns a borrowed stream over the records as strings, each paired
+    /// with a hash of its fields.
     ///
-    /// Records read via this method are guaranteed to have a position set
-    /// on them, even if the reader is at EOF or if an error is returned.
+    /// The hash is meant for deduplicating records across a whole input,
+    /// by keeping a set of the hashes seen instead of the records. It is a
+    /// 64-bit FNV-1a hash of the field contents, which is stable across runs
+    /// and platforms; equal records always have equal hashes, while distinct
+    /// records have distinct hashes with high probability only. The header
+    /// row and record positions do not contribute to the hash.
     ///
     /// # Example
     ///
     /// ```
+    /// use std::collections::HashSet;
     /// use std::error::Error;
-    /// use csv_async::{ByteRecord, AsyncReader};
-    ///
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReader;
+    ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,pop
-    /// Boston,United States,4628910
-    /// ";
+    ///     let data = "city,country\nBoston,US\nParis,FR\nBoston,US\n";
     ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     let mut record = ByteRecord::new();
-    ///
-    ///     if rdr.read_byte_record(&mut record).await? {
-    ///         assert_eq!(record, vec!["Boston", "United States", "4628910"]);
-    ///         Ok(())
-    ///     } else {
-    ///         Err(From::from("expected at least one record but got none"))
+    ///     let mut seen = HashSet::new();
+    ///     let mut unique = vec![];
+    ///     let mut records = rdr.records_hashed();
+    ///     while let Some(item) = records.next().await {
+    ///         let (hash, record) = item?;
+    ///         if seen.insert(hash) {
+    ///             unique.push(record);
+    ///         }
     ///     }
+    ///     assert_eq!(unique, vec![vec!["Boston", "US"], vec!["Paris", "FR"]]);
+    ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub async fn read_byte_record(&mut self, record: &mut ByteRecord) -> Result<bool> {
-        self.0.read_byte_record(record).await
+    pub fn records_hashed<'s>(
+        &'s mut self,
+    ) -> impl Stream<Item = Result<(u64, StringRecord)>> + Unpin + 's {
+        super::records_hashed(&mut self.0)
     }
 
-    /// Return the current position of this CSV reader.
+    /// Returns a borrowed stream over the records as strings, which ends
+    /// once `deadline` has passed.
     ///
-    /// The byte offset in the position returned can be used to `seek` this
-    /// reader. In particular, seeking to a position returned here on the same
-    /// data will result in parsing the same subsequent record.
+    /// This is meant for processing a large input in time-boxed steps. The
+    /// deadline is checked before reading each record, so a record already
+    /// being read when the deadline passes is still yielded, and no record
+    /// is lost when the stream ends early: the reader is left positioned at
+    /// the next record, and a later call continues from there.
     ///
-    /// # Example: reading the position
+    /// # Example
     ///
     /// ```
     /// use std::error::Error;
-    /// use std::io;
-    /// use csv_async::{AsyncReader, Position};
+    /// use std::time::{Duration, Instant};
     /// use tokio_stream::StreamExt;
+    /// use csv_async::AsyncReader;
     ///
     /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
     ///     let data = "\
-    /// city,country,popcount
+    /// city,country,pop
     /// Boston,United States,4628910
     /// Concord,United States,42695
     /// ";
-    ///     let rdr = AsyncReader::from_reader(io::Cursor::new(data));
-    ///     let mut iter = rdr.into_records();
-    ///     let mut pos = Position::new();
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut count = 0;
     ///     loop {
-    ///         let next = iter.next().await;
-    ///         if let Some(next) = next {
-    ///             pos = next?.position().expect("Cursor should be at some valid position").clone();
-    ///         } else {
+    ///         let deadline = Instant::now() + Duration::from_millis(10);
+    ///         let mut records = rdr.records_until(deadline);
+    ///         while let Some(record) = records.next().await {
+    ///             record?;
+    ///             count += 1;
+    ///         }
+    ///         drop(records);
+    ///         if rdr.is_done() {
     ///             break;
     ///         }
     ///     }
-    ///
-    ///     // `pos` should now be the position immediately before the last
-    ///     // record.
-    ///     assert_eq!(pos.byte(), 51);
-    ///     assert_eq!(pos.line(), 3);
-    ///     assert_eq!(pos.record(), 2);
+    ///     assert_eq!(count, 2);
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn position(&self) -> &Position {
-        self.0.position()
+    pub fn records_until(
+        &mut self,
+        deadline: Instant,
+    ) -> impl Stream<Item = Result<StringRecord>> + Unpin + '_ {
+        super::records_until(&mut self.0, deadline)
     }
 
-    /// Returns true if and only if this reader has been exhausted.
+    /// Returns a borrowed stream over the records as strings, split into
+    /// sections of records with the same number of fields.
     ///
-    /// When this returns true, no more records can be read from this reader.
+    /// A `SectionEvent::NewSection` holding the number of fields is yielded
+    /// before the first record and whenever a record has a different number
+    /// of fields than the one before it, followed by the records as
+    /// `SectionEvent::Record`. This is meant for headerless data made up of
+    /// several concatenated parts with different schemas: records are never
+    /// checked against the length of the record before, even if the reader
+    /// is not `flexible`.
     ///
     /// # Example
     ///
     /// ```
-    /// # use tokio1 as tokio;
     /// use std::error::Error;
-    /// use tokio::io;
     /// use tokio_stream::StreamExt;
-    /// use csv_async::{AsyncReader, Position};
+    /// use csv_async::{AsyncReaderBuilder, SectionEvent};
     ///
-    /// # fn main() { tokio::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,popcount
-    /// Boston,United States,4628910
-    /// Concord,United States,42695
-    /// ";
-    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
-    ///     assert!(!rdr.is_done());
-    ///     {
-    ///         let mut records = rdr.records();
-    ///         while let Some(record) = records.next().await {
-    ///             let _ = record?;
-    ///         }
-    ///     }
-    ///     assert!(rdr.is_done());
+    ///     let data = "Boston,4628910\nConcord,42695\nBoston,MA,United States\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .has_headers(false)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut events = rdr.records_sectioned();
+    ///     assert_eq!(events.next().await.unwrap()?, SectionEvent::NewSection { field_count: 2 });
+    ///     assert!(matches!(events.next().await.unwrap()?, SectionEvent::Record(_)));
+    ///     assert!(matches!(events.next().await.unwrap()?, SectionEvent::Record(_)));
+    ///     assert_eq!(events.next().await.unwrap()?, SectionEvent::NewSection { field_count: 3 });
+    ///     assert!(matches!(events.next().await.unwrap()?, SectionEvent::Record(_)));
+    ///     assert!(events.next().await.is_none());
     ///     Ok(())
     /// }
     /// ```
     #[inline]
-    pub fn is_done(&self) -> bool {
-        self.0.is_done()
+    pub fn records_sectioned(&mut self) -> impl Stream<Item = Result<SectionEvent>> + Unpin + '_ {
+        super::records_sectioned(&mut self.0)
     }
 
-    /// Returns true if and only if this reader has been configured to
-    /// interpret the first record as a header record.
+    /// Returns a borrowed stream over deserialized records.
+    ///
+    /// Each item yielded by this stream is a `Result<D, Error>`.
+    /// Therefore, in order to access the record, callers must handle the
+    /// possibility of error (typically with `?`).
+    ///
+    /// This works like `AsyncDeserializer::deserialize`, so that records can
+    /// be deserialized without building a separate `AsyncDeserializer`. If
+    /// `has_headers` is enabled (which is the default), records are matched
+    /// to struct fields by header name. Otherwise they are deserialized by
+    /// position.
+    ///
+    /// This is only available with the `with_serde` feature (enabled by
+    /// default).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use serde::Deserialize;
+    /// use csv_async::AsyncReader;
+    ///
+    /// #[derive(Debug, Deserialize, PartialEq)]
+    /// struct Row {
+    ///     city: String,
+    ///     pop: u64,
+    /// }
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\n";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut rows = rdr.deserialize::<Row>();
+    ///     let row = rows.next().await.unwrap()?;
+    ///     assert_eq!(row, Row { city: "Boston".to_string(), pop: 4628910 });
+    ///     let row = rows.next().await.unwrap()?;
+    ///     assert_eq!(row, Row { city: "Concord".to_string(), pop: 42695 });
+    ///     assert!(rows.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "with_serde")]
     #[inline]
-    pub fn has_headers(&self) -> bool {
-        self.0.has_headers()
+    pub fn deserialize<D>(&'r mut self) -> DeserializeRecordsStream<'r, R, D>
+    where
+        D: DeserializeOwned + 'r,
+    {
+        DeserializeRecordsStream::new(&mut self.0)
     }
 
-    /// Returns a reference to the underlying reader.
+    /// Returns an owned stream over deserialized records.
+    ///
+    /// This is the same as `deserialize`, except the reader is consumed, so
+    /// the stream can be returned from functions or stored in structs.
+    ///
+    /// This is only available with the `with_serde` feature (enabled by
+    /// default).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::{Stream, StreamExt};
+    /// use serde::Deserialize;
+    /// use csv_async::AsyncReader;
+    ///
+    /// #[derive(Debug, Deserialize, PartialEq)]
+    /// struct Row {
+    ///     city: String,
+    ///     pop: u64,
+    /// }
+    ///
+    /// fn rows(data: &'static str) -> impl Stream<Item = csv_async::Result<Row>> + Unpin {
+    ///     AsyncReader::from_reader(data.as_bytes()).into_deserialize()
+    /// }
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut rows = rows("city,pop\nBoston,4628910\n");
+    ///     let row = rows.next().await.unwrap()?;
+    ///     assert_eq!(row, Row { city: "Boston".to_string(), pop: 4628910 });
+    ///     assert!(rows.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "with_serde")]
     #[inline]
-    pub fn get_ref(&self) -> &R {
-        self.0.get_ref()
+    pub fn into_deserialize<D>(self) -> DeserializeRecordsIntoStream<'r, R, D>
+    where
+        D: DeserializeOwned + 'r,
+    {
+        DeserializeRecordsIntoStream::new(self.0)
     }
 
-    /// Returns a mutable reference to the underlying reader.
+    /// Returns a borrowed stream of batches of deserialized records.
+    ///
+    /// Up to `batch` records are read at a time and then deserialized in
+    /// parallel on the [rayon](https://docs.rs/rayon) thread pool, which
+    /// pays off when deserializing is much more expensive than reading.
+    /// Records are deserialized as by `StringRecord::deserialize`, using the
+    /// headers if the reader has them. Each batch keeps the order of the
+    /// records it holds, and fails as a whole if any of them fails to
+    /// deserialize. An error while reading ends the batch early and is
+    /// yielded after it.
+    ///
+    /// This is only available with the `rayon` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use serde::Deserialize;
+    /// use csv_async::AsyncReader;
+    ///
+    /// #[derive(Debug, Deserialize, PartialEq)]
+    /// struct Row {
+    ///     city: String,
+    ///     pop: u64,
+    /// }
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\nDover,32741\n";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut batches = rdr.par_deserialize::<Row>(2);
+    ///     let batch = batches.next().await.unwrap()?;
+    ///     assert_eq!(batch[0], Row { city: "Boston".to_string(), pop: 4628910 });
+    ///     assert_eq!(batch[1], Row { city: "Concord".to_string(), pop: 42695 });
+    ///     assert_eq!(batches.next().await.unwrap()?.len(), 1);
+    ///     assert!(batches.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(all(feature = "with_serde", feature = "rayon"))]
     #[inline]
-    pub fn get_mut(&mut self) -> &mut R {
-        self.0.get_mut()
+    pub fn par_deserialize<D>(
+        &mut self,
+        batch: usize,
+    ) -> impl Stream<Item = Result<Vec<D>>> + Unpin + '_
+    where
+        D: DeserializeOwned + Send + 'static,
+    {
+        super::par_deserialize(&mut self.0, batch)
     }
 
-    /// Unwraps this CSV reader, returning the underlying reader.
+    /// Returns a borrowed stream of Arrow record batches.
     ///
-    /// Note that any leftover data inside this reader's internal buffer is
-    /// lost.
+    /// Up to `batch` records are read at a time and converted into a
+    /// [`RecordBatch`](https://docs.rs/arrow-array/latest/arrow_array/struct.RecordBatch.html)
+    /// of the given schema. The fields of each record map to the columns of
+    /// the schema by position: extra fields are ignored and missing fields
+    /// are treated as empty. Fields are cast to the type of their column,
+    /// and an empty field is null if the column is nullable. Booleans,
+    /// integers, floats, strings and binary data are supported.
+    ///
+    /// A field that can not be cast fails its whole batch with an `Arrow`
+    /// error naming the record and field. An error while reading ends the
+    /// batch early and is yielded after it. The header row is not part of
+    /// any batch.
+    ///
+    /// This is only available with the `arrow` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use arrow_array::{Array, Int64Array, StringArray};
+    /// use arrow_schema::{DataType, Field, Schema};
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,\nDover,32741\n";
+    ///     let schema = Schema::new(vec![
+    ///         Field::new("city", DataType::Utf8, false),
+    ///         Field::new("pop", DataType::Int64, true),
+    ///     ]);
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut batches = rdr.into_record_batches(schema, 2);
+    ///
+    ///     let batch = batches.next().await.unwrap()?;
+    ///     assert_eq!(batch.num_rows(), 2);
+    ///     let pops = batch.column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+    ///     assert_eq!(pops.value(0), 4628910);
+    ///     assert!(pops.is_null(1));
+    ///     let batch = batches.next().await.unwrap()?;
+    ///     let cities = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    ///     assert_eq!(cities.value(0), "Dover");
+    ///     assert!(batches.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "arrow")]
     #[inline]
-    pub fn into_inner(self) -> R {
-        self.0.into_inner()
+    pub fn into_record_batches(
+        &mut self,
+        schema: arrow_schema::Schema,
+        batch: usize,
+    ) -> impl Stream<Item = Result<arrow_array::RecordBatch>> + Unpin + '_ {
+        super::record_batches(&mut self.0, schema, batch)
     }
-}
-
-
-#[cfg(test)]
-mod tests {
-    use std::pin::Pin;
-    use std::task::{Context, Poll};
-
-    use tokio::io;
-    use tokio_stream::StreamExt;
-    use tokio::runtime::Runtime;
-
-    use crate::byte_record::ByteRecord;
-    use crate::error::ErrorKind;
-    use crate::string_record::StringRecord;
-    use crate::Trim;
 
-    use super::{Position, AsyncReaderBuilder, AsyncReader};
-
-    fn b(s: &str) -> &[u8] {
-        s.as_bytes()
-    }
-    fn s(b: &[u8]) -> &str {
-        ::std::str::from_utf8(b).unwrap()
+    /// Returns an owned iterator over all records as strings.
+    ///
+    /// Each item yielded by this iterator is a `Result<StringRecord, Error>`.
+    /// Therefore, in order to access the record, callers must handle the
+    /// possibility of error (typically with `try!` or `?`).
+    ///
+    /// This is mostly useful when you want to return a CSV iterator or store
+    /// it somewhere.
+    ///
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this does not include the first record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut records = rdr.into_records();
+    ///     while let Some(record) = records.next().await {
+    ///         println!("{:?}", record?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn into_records(self) -> StringRecordsIntoStream<'r, R> {
+        StringRecordsIntoStream::new(self.0)
     }
 
-    fn newpos(byte: u64, line: u64, record: u64) -> Position {
-        let mut p = Position::new();
-        p.set_byte(byte).set_line(line).set_record(record);
-        p
+    /// Returns a borrowed iterator over all records as raw bytes.
+    ///
+    /// Each item yielded by this iterator is a `Result<ByteRecord, Error>`.
+    /// Therefore, in order to access the record, callers must handle the
+    /// possibility of error (typically with `try!` or `?`).
+    ///
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this does not include the first record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut iter = rdr.byte_records();
+    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(iter.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn byte_records(&mut self) -> ByteRecordsStream<R> {
+        ByteRecordsStream::new(&mut self.0)
     }
 
-    async fn count(stream: impl StreamExt) -> usize {
+    /// Returns an owned iterator over all records as raw bytes.
+    ///
+    /// Each item yielded by this iterator is a `Result<ByteRecord, Error>`.
+    /// Therefore, in order to access the record, callers must handle the
+    /// possibility of error (typically with `try!` or `?`).
+    ///
+    /// This is mostly useful when you want to return a CSV iterator or store
+    /// it somewhere.
+    ///
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this does not include the first record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut iter = rdr.into_byte_records();
+    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(iter.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn into_byte_records(self) -> ByteRecordsIntoStream<'r, R> {
+        ByteRecordsIntoStream::new(self.0)
+    }
+
+    /// Returns a reference to the first row read by this parser.
+    ///
+    /// If no row has been read yet, then this will force parsing of the first
+    /// row.
+    ///
+    /// If there was a problem parsing the row or if it wasn't valid UTF-8,
+    /// then this returns an error.
+    ///
+    /// If the underlying reader emits EOF before any data, then this returns
+    /// an empty record.
+    ///
+    /// Note that this method may be used regardless of whether `has_headers`
+    /// was enabled (but it is enabled by default).
+    ///
+    /// # Example
+    ///
+    /// This example shows how to get the header row of CSV data. Notice that
+    /// the header row does not appear as a record in the iterator!
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///
+    ///     // We can read the headers before iterating.
+    ///     {
+    ///     // `headers` borrows from the reader, so we put this in its
+    ///     // own scope. That way, the borrow ends before we try iterating
+    ///     // below. Alternatively, we could clone the headers.
+    ///     let headers = rdr.headers().await?;
+    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
+    ///     }
+    ///
+    ///     {
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(records.next().await.is_none());
+    ///     }
+    ///
+    ///     // We can also read the headers after iterating.
+    ///     let headers = rdr.headers().await?;
+    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn headers(&mut self) -> Result<&StringRecord> {
+        self.0.headers().await
+    }
+
+    /// Returns a reference to the first row read by this parser as raw bytes.
+    ///
+    /// If no row has been read yet, then this will force parsing of the first
+    /// row.
+    ///
+    /// If there was a problem parsing the row then this returns an error.
+    ///
+    /// If the underlying reader emits EOF before any data, then this returns
+    /// an empty record.
+    ///
+    /// Note that this method may be used regardless of whether `has_headers`
+    /// was enabled (but it is enabled by default).
+    ///
+    /// # Example
+    ///
+    /// This example shows how to get the header row of CSV data. Notice that
+    /// the header row does not appear as a record in the iterator!
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///
+    ///     // We can read the headers before iterating.
+    ///     {
+    ///     // `headers` borrows from the reader, so we put this in its
+    ///     // own scope. That way, the borrow ends before we try iterating
+    ///     // below. Alternatively, we could clone the headers.
+    ///     let headers = rdr.byte_headers().await?;
+    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
+    ///     }
+    ///
+    ///     {
+    ///     let mut records = rdr.byte_records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(records.next().await.is_none());
+    ///     }
+    ///
+    ///     // We can also read the headers after iterating.
+    ///     let headers = rdr.byte_headers().await?;
+    ///     assert_eq!(headers, vec!["city", "country", "pop"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn byte_headers(&mut self) -> Result<&ByteRecord> {
+        self.0.byte_headers().await
+    }
+
+    /// Returns every header name that appears more than once, along with
+    /// the indices of its columns.
+    ///
+    /// Names are listed in order of their first appearance. When fields are
+    /// deserialized by name, only one of the columns sharing a name is used,
+    /// so duplicates usually indicate data that would be silently lost. See
+    /// also `AsyncReaderBuilder::error_on_duplicate_headers`.
+    ///
+    /// If the headers have not been read yet, the first record is read
+    /// first, as with `headers`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "id,name,id\n1,foo,2\n";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let dups = rdr.check_duplicate_headers().await?;
+    ///     assert_eq!(dups, vec![("id".to_string(), vec![0, 2])]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn check_duplicate_headers(&mut self) -> Result<Vec<(String, Vec<usize>)>> {
+        self.0.check_duplicate_headers().await
+    }
+
+    /// Set the headers of this CSV parser manually.
+    ///
+    /// This overrides any other setting (including `set_byte_headers`). Any
+    /// automatic detection of headers is disabled. This may be called at any
+    /// time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReader, StringRecord};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "country", "pop"]);
+    ///     rdr.set_headers(StringRecord::from(vec!["a", "b", "c"]));
+    ///     assert_eq!(rdr.headers().await?, vec!["a", "b", "c"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_headers(&mut self, headers: StringRecord) {
+        self.0.set_headers(headers);
+    }
+
+    /// Set the headers of this CSV parser manually as raw bytes.
+    ///
+    /// This overrides any other setting (including `set_headers`). Any
+    /// automatic detection of headers is disabled. This may be called at any
+    /// time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReader, ByteRecord};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///
+    ///     assert_eq!(rdr.byte_headers().await?, vec!["city", "country", "pop"]);
+    ///     rdr.set_byte_headers(ByteRecord::from(vec!["a", "b", "c"]));
+    ///     assert_eq!(rdr.byte_headers().await?, vec!["a", "b", "c"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_byte_headers(&mut self, headers: ByteRecord) {
+        self.0.set_byte_headers(headers);
+    }
+
+    /// Rename some of the header names, without changing the data.
+    ///
+    /// `mapping` is a list of (old, new) pairs. Every header named like the
+    /// first element of a pair is renamed to its second element, and other
+    /// headers are kept. The header row is read first if necessary.
+    ///
+    /// Afterwards, `headers` returns the new names, and records are
+    /// deserialized by name using them. Like `set_headers`, this disables
+    /// any automatic detection of headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     rdr.with_renamed_headers(&[("pop", "population")]).await?;
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "country", "population"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn with_renamed_headers(&mut self, mapping: &[(&str, &str)]) -> Result<()> {
+        self.0.with_renamed_headers(mapping).await
+    }
+
+    /// Read a single row into the given record. Returns false when no more
+    /// records could be read.
+    ///
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this will never read the first record.
+    ///
+    /// This method is useful when you want to read records as fast as
+    /// as possible. It's less ergonomic than an iterator, but it permits the
+    /// caller to reuse the `StringRecord` allocation, which usually results
+    /// in higher throughput.
+    ///
+    /// Records read via this method are guaranteed to have a position set
+    /// on them, even if the reader is at EOF or if an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReader, StringRecord};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut record = StringRecord::new();
+    ///
+    ///     if rdr.read_record(&mut record).await? {
+    ///         assert_eq!(record, vec!["Boston", "United States", "4628910"]);
+    ///         Ok(())
+    ///     } else {
+    ///         Err(From::from("expected at least one record but got none"))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub async fn read_record(&mut self, record: &mut StringRecord) -> Result<bool> {
+        self.0.read_record(record).await
+    }
+
+    /// Read a single row into the given byte record. Returns false when no
+    /// more records could be read.
+    ///
+    /// If `has_headers` was enabled via a `ReaderBuilder` (which is the
+    /// default), then this will never read the first record.
+    ///
+    /// This method is useful when you want to read records as fast as
+    /// as possible. It's less ergonomic than an iterator, but it permits the
+    /// caller to reuse the `ByteRecord` allocation, which usually results
+    /// in higher throughput.
+    ///
+    /// Records read via this method are guaranteed to have a position set
+    /// on them, even if the reader is at EOF or if an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{ByteRecord, AsyncReader};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut record = ByteRecord::new();
+    ///
+    ///     if rdr.read_byte_record(&mut record).await? {
+    ///         assert_eq!(record, vec!["Boston", "United States", "4628910"]);
+    ///         Ok(())
+    ///     } else {
+    ///         Err(From::from("expected at least one record but got none"))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub async fn read_byte_record(&mut self, record: &mut ByteRecord) -> Result<bool> {
+        self.0.read_byte_record(record).await
+    }
+
+    /// Read a single row into the given byte record, and the span of each of
+    /// its fields in the source data into `spans`.
+    ///
+    /// Each span is a pair of the byte offset of the field, counted from the
+    /// start of the data, and its length in bytes. This allows indexing a
+    /// file and slicing fields out of it later, e.g. through a memory map.
+    /// Spans cover fields as they appear in the data, not as they are
+    /// returned: a quoted field's span includes its quotes and any escaped
+    /// quotes, so slicing it yields text that still needs unquoting, and
+    /// trimming does not shrink spans. `spans` is cleared first and holds one
+    /// span per field of `record`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{ByteRecord, AsyncReader};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,\"United States\",4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut record = ByteRecord::new();
+    ///     let mut spans = vec![];
+    ///
+    ///     assert!(rdr.read_byte_record_with_spans(&mut record, &mut spans).await?);
+    ///     assert_eq!(spans, vec![(17, 6), (24, 15), (40, 7)]);
+    ///     let (offset, len) = spans[1];
+    ///     assert_eq!(&data[offset as usize..][..len as usize], "\"United States\"");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn read_byte_record_with_spans(
+        &mut self,
+        record: &mut ByteRecord,
+        spans: &mut Vec<(u64, u64)>,
+    ) -> Result<bool> {
+        self.0.read_byte_record_with_spans(record, spans).await
+    }
+
+    /// Attempt to read a single row into the given byte record, without
+    /// creating a future.
+    ///
+    /// This is the building block of `read_byte_record` and of the record
+    /// streams, for use in hand-written `Future` or `Stream` implementations.
+    /// When no data is ready, `Poll::Pending` is returned and the waker in
+    /// `cx` is scheduled to be woken once the underlying reader is ready.
+    /// The partially read record is kept by the reader, so `record` is only
+    /// written once this returns `Poll::Ready`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use std::pin::Pin;
+    /// use futures::future::poll_fn;
+    /// use csv_async::{ByteRecord, AsyncReader};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut record = ByteRecord::new();
+    ///
+    ///     let ok = poll_fn(|cx| Pin::new(&mut rdr).poll_read_byte_record(cx, &mut record)).await?;
+    ///     assert!(ok);
+    ///     assert_eq!(record, vec!["Boston", "United States", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn poll_read_byte_record(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        self.get_mut().0.poll_read_byte_record(cx, record)
+    }
+
+    /// Converts all remaining records to JSON objects and writes them to `out`,
+    /// one object per line (NDJSON).
+    ///
+    /// Object keys are taken from the header row, in its order. If
+    /// `has_headers` is disabled, or a record has more fields than the header
+    /// row, the index of the field is used as its key instead. A header row
+    /// with repeated names results in an `ErrorKind::DuplicateHeader` error.
+    ///
+    /// When `numbers` is enabled, fields holding an integer or a finite
+    /// floating point number, written exactly as JSON writes it, are written
+    /// as JSON numbers. Other fields, like `007` or `1e3`, are written as
+    /// JSON strings, so no data is lost. When `numbers` is disabled, all
+    /// fields are written as JSON strings.
+    ///
+    /// `out` is flushed once all records are written.
+    ///
+    /// This method is available only with the `serde_json` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,pop
+    /// Boston,4628910
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut out = vec![];
+    ///     rdr.to_ndjson(&mut out, true).await?;
+    ///     assert_eq!(String::from_utf8(out)?, "{\"city\":\"Boston\",\"pop\":4628910}\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub async fn to_ndjson<W: io::AsyncWrite + std::marker::Unpin>(
+        &mut self,
+        out: W,
+        numbers: bool,
+    ) -> Result<()> {
+        self.0.write_ndjson(out, numbers).await
+    }
+
+    /// Infer the type of every column from a sample of up to `sample_rows`
+    /// records.
+    ///
+    /// For every column, this finds the narrowest of `ColumnType::Bool`,
+    /// `Int`, `Float` and `Text` that fits all non-empty sampled values.
+    /// Integers and floats mix into `Float`, while any other mix of types
+    /// results in `Text`. A column is nullable if it has empty values, or is
+    /// missing from some records. A column without any non-empty values is
+    /// reported as nullable `Text`.
+    ///
+    /// The header row is never sampled. If `has_headers` is disabled, the
+    /// first record is sampled like any other.
+    ///
+    /// The sampled records are kept by the reader and yielded again, so
+    /// reading can go on from the first sampled record afterwards, without
+    /// the need to seek.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::{AsyncReader, ColumnType};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,pop,area
+    /// Boston,4628910,
+    /// Concord,42695,25.4
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let schema = rdr.infer_schema(100).await?;
+    ///     assert_eq!(schema[0].column_type(), ColumnType::Text);
+    ///     assert_eq!(schema[1].column_type(), ColumnType::Int);
+    ///     assert_eq!(schema[2].column_type(), ColumnType::Float);
+    ///     assert!(schema[2].is_nullable());
+    ///
+    ///     // The sampled records can still be read.
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["Boston", "4628910", ""]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn infer_schema(&mut self, sample_rows: usize) -> Result<Vec<InferredType>> {
+        self.0.infer_schema(sample_rows).await
+    }
+
+    /// Compute statistics about the given numeric columns in one pass over
+    /// all remaining records, without keeping the records in memory.
+    ///
+    /// For every column index in `cols`, the returned `ColumnSummary` holds
+    /// the count, minimum, maximum, sum and mean of the values that parse as
+    /// `f64`. Empty fields and fields missing from short records are counted
+    /// as nulls. Other values that are not numbers are counted as nulls too,
+    /// unless the `error_on_non_numeric` option of `AsyncReaderBuilder` is
+    /// enabled, in which case an error of kind `ErrorKind::NonNumeric` is
+    /// returned.
+    ///
+    /// The header row is never summarized. The reader is exhausted
+    /// afterwards, unless an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,pop,area
+    /// Boston,4628910,
+    /// Concord,42695,25.4
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let summary = rdr.summarize(&[1, 2]).await?;
+    ///     assert_eq!(summary[0].count(), 2);
+    ///     assert_eq!(summary[0].max(), Some(4628910.0));
+    ///     assert_eq!(summary[1].count(), 1);
+    ///     assert_eq!(summary[1].null_count(), 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn summarize(&mut self, cols: &[usize]) -> Result<Vec<ColumnSummary>> {
+        self.0.summarize(cols).await
+    }
+
+    /// Returns whether the data has a header row, but no data records after
+    /// it.
+    ///
+    /// A file holding only a header row reads like any other: `headers`
+    /// returns the header row, and reading records yields nothing. This
+    /// tells such a file apart up front, by reading ahead one record. That
+    /// record is kept by the reader and yielded again by the next read, so
+    /// no record is lost. Once records have been read, this tells whether
+    /// there are no records left.
+    ///
+    /// This returns false for empty data, which has no header row, and
+    /// whenever `has_headers` is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncReader;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut rdr = AsyncReader::from_reader("city,country,pop\n".as_bytes());
+    ///     assert!(rdr.is_header_only().await?);
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "country", "pop"]);
+    ///
+    ///     let data = "city,country,pop\nBoston,United States,4628910\n";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     assert!(!rdr.is_header_only().await?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn is_header_only(&mut self) -> Result<bool> {
+        self.0.is_header_only().await
+    }
+
+    /// Return the parsing throughput measured so far.
+    ///
+    /// This is only measured if the `track_timing` option of
+    /// `AsyncReaderBuilder` is enabled. Otherwise all numbers are zero.
+    #[inline]
+    pub fn timing(&self) -> ParseTiming {
+        self.0.timing()
+    }
+
+    /// Return the warnings collected so far about non-fatal anomalies in
+    /// the data, in the order they were found.
+    ///
+    /// Warnings are only collected if the `collect_warnings` option of
+    /// `AsyncReaderBuilder` is enabled. Otherwise this is always empty.
+    #[inline]
+    pub fn warnings(&self) -> &[Warning] {
+        self.0.warnings()
+    }
+
+    /// Capture the state of this reader, from which parsing can be resumed
+    /// by a reader created with `AsyncReaderBuilder::create_reader_with_state`.
+    ///
+    /// This is meant for data delivered by a connection that may be cut off
+    /// anywhere, including in the middle of a quoted field: once the
+    /// connection fails, the state captured here and the data delivered by a
+    /// new connection continue the same CSV data.
+    ///
+    /// The state holds the bytes of the record being parsed only if the
+    /// `resumable` option of `AsyncReaderBuilder` is enabled. Otherwise the
+    /// state is only complete between records.
+    #[inline]
+    pub fn parser_state(&self) -> ParserState {
+        self.0.parser_state()
+    }
+
+    /// Return the current position of this CSV reader.
+    ///
+    /// The byte offset in the position returned can be used to `seek` this
+    /// reader. In particular, seeking to a position returned here on the same
+    /// data will result in parsing the same subsequent record.
+    ///
+    /// # Example: reading the position
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use std::io;
+    /// use csv_async::{AsyncReader, Position};
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,popcount
+    /// Boston,United States,4628910
+    /// Concord,United States,42695
+    /// ";
+    ///     let rdr = AsyncReader::from_reader(io::Cursor::new(data));
+    ///     let mut iter = rdr.into_records();
+    ///     let mut pos = Position::new();
+    ///     loop {
+    ///         let next = iter.next().await;
+    ///         if let Some(next) = next {
+    ///             pos = next?.position().expect("Cursor should be at some valid position").clone();
+    ///         } else {
+    ///             break;
+    ///         }
+    ///     }
+    ///
+    ///     // `pos` should now be the position immediately before the last
+    ///     // record.
+    ///     assert_eq!(pos.byte(), 51);
+    ///     assert_eq!(pos.line(), 3);
+    ///     assert_eq!(pos.record(), 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn position(&self) -> &Position {
+        self.0.position()
+    }
+
+    /// Returns true if and only if this reader has been exhausted.
+    ///
+    /// When this returns true, no more records can be read from this reader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use tokio1 as tokio;
+    /// use std::error::Error;
+    /// use tokio::io;
+    /// use tokio_stream::StreamExt;
+    /// use csv_async::{AsyncReader, Position};
+    ///
+    /// # fn main() { tokio::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,popcount
+    /// Boston,United States,4628910
+    /// Concord,United States,42695
+    /// ";
+    ///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     assert!(!rdr.is_done());
+    ///     {
+    ///         let mut records = rdr.records();
+    ///         while let Some(record) = records.next().await {
+    ///             let _ = record?;
+    ///         }
+    ///     }
+    ///     assert!(rdr.is_done());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+
+    /// Returns true if and only if this reader has been configured to
+    /// interpret the first record as a header record.
+    #[inline]
+    pub fn has_headers(&self) -> bool {
+        self.0.has_headers()
+    }
+
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        self.0.get_ref()
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        self.0.get_mut()
+    }
+
+    /// Unwraps this CSV reader, returning the underlying reader.
+    ///
+    /// Note that any leftover data inside this reader's internal buffer is
+    /// lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.0.into_inner()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::time::{Duration, Instant};
+
+    use tokio::io::{self, AsyncReadExt};
+    use tokio_stream::StreamExt;
+    use tokio::runtime::Runtime;
+
+    use crate::byte_record::ByteRecord;
+    use crate::error::ErrorKind;
+    use crate::string_record::StringRecord;
+    use crate::{
+        FooterSpec, InvalidUtf8Policy, RecordOrHeader, RecordPool, SectionEvent, Terminator, Trim,
+    };
+
+    use super::{Position, AsyncReaderBuilder, AsyncReader};
+
+    fn b(s: &str) -> &[u8] {
+        s.as_bytes()
+    }
+    fn s(b: &[u8]) -> &str {
+        ::std::str::from_utf8(b).unwrap()
+    }
+
+    fn newpos(byte: u64, line: u64, record: u64) -> Position {
+        let mut p = Position::new();
+        p.set_byte(byte).set_line(line).set_record(record);
+        p
+    }
+
+    async fn count(stream: impl StreamExt) -> usize {
         stream.fold(0, |acc, _| acc + 1 ).await
     }
 
     #[test]
-    fn read_byte_record() {
+    fn read_byte_record() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,\"b,ar\",baz\nabc,mno,xyz");
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let mut rec = ByteRecord::new();
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("foo", s(&rec[0]));
+            assert_eq!("b,ar", s(&rec[1]));
+            assert_eq!("baz", s(&rec[2]));
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("abc", s(&rec[0]));
+            assert_eq!("mno", s(&rec[1]));
+            assert_eq!("xyz", s(&rec[2]));
+
+            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn read_trimmed_records_and_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(true)
+                .trim(Trim::All)
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!("1", s(&rec[0]));
+            assert_eq!("2", s(&rec[1]));
+            assert_eq!("3", s(&rec[2]));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!("1", &rec[0]);
+            assert_eq!("", &rec[1]);
+            assert_eq!("3", &rec[2]);
+            {
+                let headers = rdr.headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!("foo", &headers[0]);
+                assert_eq!("bar", &headers[1]);
+                assert_eq!("baz", &headers[2]);
+            }
+        });
+    }
+
+    #[test]
+    fn read_trimmed_header() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(true)
+                .trim(Trim::Headers)
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!("  1", s(&rec[0]));
+            assert_eq!("  2", s(&rec[1]));
+            assert_eq!("  3", s(&rec[2]));
+            {
+                let headers = rdr.headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!("foo", &headers[0]);
+                assert_eq!("bar", &headers[1]);
+                assert_eq!("baz", &headers[2]);
+            }
+        });
+    }
+
+    #[test]
+    fn read_trimed_header_invalid_utf8() {
+        Runtime::new().unwrap().block_on(async {
+            let data = &b"foo,  b\xFFar,\tbaz\na,b,c\nd,e,f"[..];
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(true)
+                .trim(Trim::Headers)
+                .create_reader(data);
+            let mut rec = StringRecord::new();
+
+            // force the headers to be read
+            let _ = rdr.read_record(&mut rec).await;
+            // Check the byte headers are trimmed
+            {
+                let headers = rdr.byte_headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!(b"foo", &headers[0]);
+                assert_eq!(b"b\xFFar", &headers[1]);
+                assert_eq!(b"baz", &headers[2]);
+            }
+            match *rdr.headers().await.unwrap_err().kind() {
+                ErrorKind::Utf8 { pos: Some(ref pos), ref err } => {
+                    assert_eq!(pos, &newpos(0, 1, 0));
+                    assert_eq!(err.field(), 1);
+                    assert_eq!(err.valid_up_to(), 3);
+                }
+                ref err => panic!("match failed, got {:?}", err),
+            }
+        });
+    }
+
+    #[test]
+    fn read_trimmed_records() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(true)
+                .trim(Trim::Fields)
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!("1", s(&rec[0]));
+            assert_eq!("2", s(&rec[1]));
+            assert_eq!("3", s(&rec[2]));
+            {
+                let headers = rdr.headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!("foo", &headers[0]);
+                assert_eq!("  bar", &headers[1]);
+                assert_eq!("\tbaz", &headers[2]);
+            }
+        });
+    }
+
+    #[test]
+    fn read_record_unequal_fails() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo\nbar,baz");
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let mut rec = ByteRecord::new();
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(1, rec.len());
+            assert_eq!("foo", s(&rec[0]));
+
+            match rdr.read_byte_record(&mut rec).await {
+                Err(err) => match *err.kind() {
+                    ErrorKind::UnequalLengths {
+                        expected_len: 1,
+                        ref pos,
+                        len: 2,
+                    } => {
+                        assert_eq!(pos, &Some(newpos(4, 2, 1)));
+                    }
+                    ref wrong => panic!("match failed, got {:?}", wrong),
+                },
+                wrong => panic!("match failed, got {:?}", wrong),
+            }
+        });
+    }
+
+    #[test]
+    fn read_record_unequal_ok() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo\nbar,baz");
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(1, rec.len());
+            assert_eq!("foo", s(&rec[0]));
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(2, rec.len());
+            assert_eq!("bar", s(&rec[0]));
+            assert_eq!("baz", s(&rec[1]));
+
+            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+        });
+    }
+
+    // This tests that even if we get a CSV error, we can continue reading
+    // if we want.
+    #[test]
+    fn read_record_unequal_continue() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo\nbar,baz\nquux");
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let mut rec = ByteRecord::new();
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(1, rec.len());
+            assert_eq!("foo", s(&rec[0]));
+
+            match rdr.read_byte_record(&mut rec).await {
+                Err(err) => match err.kind() {
+                    &ErrorKind::UnequalLengths {
+                        expected_len: 1,
+                        ref pos,
+                        len: 2,
+                    } => {
+                        assert_eq!(pos, &Some(newpos(4, 2, 1)));
+                    }
+                    wrong => panic!("match failed, got {:?}", wrong),
+                },
+                wrong => panic!("match failed, got {:?}", wrong),
+            }
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(1, rec.len());
+            assert_eq!("quux", s(&rec[0]));
+
+            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn read_record_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,bar,baz\na,b,c\nd,e,f");
+            let mut rdr = AsyncReaderBuilder::new().has_headers(true).create_reader(data);
+            let mut rec = StringRecord::new();
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("a", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("d", &rec[0]);
+
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            {
+                let headers = rdr.byte_headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!(b"foo", &headers[0]);
+                assert_eq!(b"bar", &headers[1]);
+                assert_eq!(b"baz", &headers[2]);
+            }
+            {
+                let headers = rdr.headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!("foo", &headers[0]);
+                assert_eq!("bar", &headers[1]);
+                assert_eq!("baz", &headers[2]);
+            }
+        });
+    }
+
+    #[test]
+    fn read_record_headers_invalid_utf8() {
+        Runtime::new().unwrap().block_on(async {
+            let data = &b"foo,b\xFFar,baz\na,b,c\nd,e,f"[..];
+            let mut rdr = AsyncReaderBuilder::new().has_headers(true).create_reader(data);
+            let mut rec = StringRecord::new();
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("a", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("d", &rec[0]);
+
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            // Check that we can read the headers as raw bytes, but that
+            // if we read them as strings, we get an appropriate UTF-8 error.
+            {
+                let headers = rdr.byte_headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!(b"foo", &headers[0]);
+                assert_eq!(b"b\xFFar", &headers[1]);
+                assert_eq!(b"baz", &headers[2]);
+            }
+            match *rdr.headers().await.unwrap_err().kind() {
+                ErrorKind::Utf8 { pos: Some(ref pos), ref err } => {
+                    assert_eq!(pos, &newpos(0, 1, 0));
+                    assert_eq!(err.field(), 1);
+                    assert_eq!(err.valid_up_to(), 1);
+                }
+                ref err => panic!("match failed, got {:?}", err),
+            }
+        });
+    }
+
+    #[test]
+    fn read_record_no_headers_before() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,bar,baz\na,b,c\nd,e,f");
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let mut rec = StringRecord::new();
+
+            {
+                let headers = rdr.headers().await.unwrap();
+                assert_eq!(3, headers.len());
+                assert_eq!("foo", &headers[0]);
+                assert_eq!("bar", &headers[1]);
+                assert_eq!("baz", &headers[2]);
+            }
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("foo", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("a", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("d", &rec[0]);
+
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn read_record_no_headers_after() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("foo,bar,baz\na,b,c\nd,e,f");
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let mut rec = StringRecord::new();
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("foo", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("a", &rec[0]);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(3, rec.len());
+            assert_eq!("d", &rec[0]);
+
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            let headers = rdr.headers().await.unwrap();
+            assert_eq!(3, headers.len());
+            assert_eq!("foo", &headers[0]);
+            assert_eq!("bar", &headers[1]);
+            assert_eq!("baz", &headers[2]);
+        });
+    }
+
+
+    // Test that position info is reported correctly in absence of headers.
+    #[test]
+    fn positions_no_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader("a,b,c\nx,y,z".as_bytes())
+                .into_records();
+
+            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
+            assert_eq!(pos.byte(), 0);
+            assert_eq!(pos.line(), 1);
+            assert_eq!(pos.record(), 0);
+
+            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
+            assert_eq!(pos.byte(), 6);
+            assert_eq!(pos.line(), 2);
+            assert_eq!(pos.record(), 1);
+        });
+    }
+
+    // Test that position info is reported correctly with headers.
+    #[test]
+    fn positions_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(true)
+                .create_reader("a,b,c\nx,y,z".as_bytes())
+                .into_records();
+
+            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
+            assert_eq!(pos.byte(), 6);
+            assert_eq!(pos.line(), 2);
+            assert_eq!(pos.record(), 1);
+        });
+    }
+
+    // Test that reading headers on empty data yields an empty record.
+    #[test]
+    fn headers_on_empty_data() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new().create_reader("".as_bytes());
+            let r = rdr.byte_headers().await.unwrap();
+            assert_eq!(r.len(), 0);
+        });
+    }
+
+    // Test that reading the first record on empty data works.
+    #[test]
+    fn no_headers_on_empty_data() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr =
+            AsyncReaderBuilder::new().has_headers(false).create_reader("".as_bytes());
+            assert_eq!(count(rdr.records()).await, 0);
+        });
+    }
+
+    // Test that reading the first record on empty data works, even if
+    // we've tried to read headers before hand.
+    #[test]
+    fn no_headers_on_empty_data_after_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr =
+                AsyncReaderBuilder::new().has_headers(false).create_reader("".as_bytes());
+            assert_eq!(rdr.headers().await.unwrap().len(), 0);
+            assert_eq!(count(rdr.records()).await, 0);
+        });
+    }
+
+    #[test]
+    fn no_infinite_loop_on_io_errors() {
+        struct FailingRead;
+        impl io::AsyncRead for FailingRead {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _cx: &mut Context,
+                _buf: &mut tokio::io::ReadBuf
+            ) -> Poll<Result<(), io::Error>> {
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "Broken reader")))
+            }
+        }
+        impl std::marker::Unpin for FailingRead {}
+    
+        Runtime::new().unwrap().block_on(async {
+            let mut record_results = AsyncReader::from_reader(FailingRead).into_records();
+            let first_result = record_results.next().await;
+            assert!(
+                matches!(&first_result, Some(Err(e)) if matches!(e.kind(), crate::ErrorKind::Io(_)))
+            );
+            assert!(record_results.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn read_fixed_width() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("name  qty\nfoo   12\n\nbarbaz7\nqux\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .fixed_width(Some(&[6, 3]))
+                .trim(Trim::All)
+                .create_reader(data);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["name", "qty"]);
+
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["foo", "12"]);
+            assert_eq!(rec.position().unwrap(), &newpos(10, 2, 1));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["barbaz", "7"]);
+            assert_eq!(rec.position().unwrap(), &newpos(20, 4, 2));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["qux", ""]);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn records_with_raw() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "h1,h2,h3\r\n\"a, b\" ,\"c\"\"d\",  e  \r\nf,g,h";
+            let mut rdr = AsyncReader::from_reader(data.as_bytes());
+            let mut records = rdr.records_with_raw();
+
+            let (raw, rec) = records.next().await.unwrap().unwrap();
+            assert_eq!(raw, "\"a, b\" ,\"c\"\"d\",  e  ");
+            assert_eq!(&rec[0], "a, b ");
+            assert_eq!(&rec[1], "c\"d");
+            assert_eq!(&rec[2], "  e  ");
+
+            let (raw, rec) = records.next().await.unwrap().unwrap();
+            assert_eq!(raw, "f,g,h");
+            assert_eq!(rec, vec!["f", "g", "h"]);
+
+            assert!(records.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn create_reader_with_prefix() {
+        Runtime::new().unwrap().block_on(async {
+            let mut data = b("a;b;c\nx;y;z\n1;2;3\n");
+            let mut prefix = vec![0; 7];
+            data.read_exact(&mut prefix).await.unwrap();
+            let delimiter = if prefix.iter().filter(|&&c| c == b';').count() > 1 { b';' } else { b',' };
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .delimiter(delimiter)
+                .create_reader_with_prefix(prefix, data);
+            let records = rdr.records().collect::<Vec<_>>().await;
+            assert_eq!(records.len(), 3);
+            assert_eq!(records[0].as_ref().unwrap(), &vec!["a", "b", "c"]);
+            assert_eq!(records[1].as_ref().unwrap(), &vec!["x", "y", "z"]);
+            assert_eq!(records[2].as_ref().unwrap(), &vec!["1", "2", "3"]);
+            assert_eq!(rdr.position().byte(), 18);
+        });
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn to_ndjson() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "name,age,score\nalice,30,1.5\n\"bob, jr\",x,\n";
+            let mut rdr = AsyncReader::from_reader(data.as_bytes());
+            let mut out = vec![];
+            rdr.to_ndjson(&mut out, true).await.unwrap();
+
+            let out = String::from_utf8(out).unwrap();
+            let lines: Vec<serde_json::Value> = out
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            assert_eq!(lines, vec![
+                serde_json::json!({"name": "alice", "age": 30, "score": 1.5}),
+                serde_json::json!({"name": "bob, jr", "age": "x", "score": ""}),
+            ]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader(data.as_bytes());
+            let mut out = vec![];
+            rdr.to_ndjson(&mut out, false).await.unwrap();
+            let first: serde_json::Value =
+                serde_json::from_str(s(&out).lines().nth(1).unwrap()).unwrap();
+            assert_eq!(first, serde_json::json!({"0": "alice", "1": "30", "2": "1.5"}));
+        });
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn to_ndjson_lossless() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "b,a,c\n007,18446744073709551615,-0\n-12,1.5,1e3\n";
+            let mut rdr = AsyncReader::from_reader(data.as_bytes());
+            let mut out = vec![];
+            rdr.to_ndjson(&mut out, true).await.unwrap();
+            assert_eq!(
+                s(&out),
+                "{\"b\":\"007\",\"a\":18446744073709551615,\"c\":\"-0\"}\n\
+                 {\"b\":-12,\"a\":1.5,\"c\":\"1e3\"}\n"
+            );
+
+            let mut rdr = AsyncReader::from_reader("a,b,a\n1,2,3\n".as_bytes());
+            let err = rdr.to_ndjson(vec![], true).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::DuplicateHeader { ref name, ref fields, .. } => {
+                    assert_eq!(name, "a");
+                    assert_eq!(fields, &vec![0, 2]);
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+        });
+    }
+
+    #[test]
+    fn read_collapse_whitespace() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("h1 \t h2,h3,h4\n\"a   b\tc\",  d  ,e\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .collapse_whitespace(true)
+                .create_reader(data);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["h1 \t h2", "h3", "h4"]);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a b c", " d ", "e"]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .collapse_whitespace(true)
+                .trim(Trim::Fields)
+                .has_headers(false)
+                .create_reader(data);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["h1 h2", "h3", "h4"]);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a b c", "d", "e"]);
+        });
+    }
+
+    #[test]
+    fn group_by_column() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("k,v\na,1\na,2\nb,3\nc,4\nc,5\nc,6\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let groups: Vec<(String, Vec<StringRecord>)> = rdr
+                .group_by_column(0)
+                .map(|group| group.unwrap())
+                .collect()
+                .await;
+            let got: Vec<(&str, Vec<&str>)> = groups
+                .iter()
+                .map(|(key, recs)| (key.as_str(), recs.iter().map(|r| &r[1]).collect()))
+                .collect();
+            assert_eq!(got, vec![
+                ("a", vec!["1", "2"]),
+                ("b", vec!["3"]),
+                ("c", vec!["4", "5", "6"]),
+            ]);
+        });
+    }
+
+    #[test]
+    fn read_record_header_shorter_than_data() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("a,b\n1,2,\n3,4,\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let mut rec = StringRecord::new();
+
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::HeaderLengthMismatch { ref pos, header_len: 2, len: 3 } => {
+                    assert_eq!(pos, &Some(newpos(4, 2, 1)));
+                }
+                ref wrong => panic!("match failed, got {:?}", wrong),
+            }
+            assert_eq!(
+                err.to_string(),
+                "CSV error: record 1 (line: 2, byte: 4): found record with 3 fields, \
+                 but the header row has 2 fields"
+            );
+
+            // Later records are compared as before.
+            match *rdr.read_record(&mut rec).await.unwrap_err().kind() {
+                ErrorKind::UnequalLengths { expected_len: 2, len: 3, .. } => {}
+                ref wrong => panic!("match failed, got {:?}", wrong),
+            }
+        });
+    }
+
+    #[test]
+    fn poll_read_byte_record_manually() {
+        // Hands out one byte per read, answering every other poll with
+        // `Pending`.
+        struct Trickle(&'static [u8], bool);
+        impl io::AsyncRead for Trickle {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context,
+                buf: &mut tokio::io::ReadBuf
+            ) -> Poll<Result<(), io::Error>> {
+                self.1 = !self.1;
+                if self.1 {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                let n = self.0.len().min(1).min(buf.remaining());
+                buf.put_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn read(
+            rdr: &mut AsyncReader<Trickle>,
+            cx: &mut Context,
+            rec: &mut ByteRecord,
+        ) -> (bool, usize) {
+            let mut pending = 0;
+            loop {
+                match Pin::new(&mut *rdr).poll_read_byte_record(cx, rec) {
+                    Poll::Ready(result) => return (result.unwrap(), pending),
+                    Poll::Pending => pending += 1,
+                }
+            }
+        }
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut rdr = AsyncReader::from_reader(Trickle(b"h1,h2\na,\"b\nc\"\nd,e\n", false));
+        let mut rec = ByteRecord::new();
+        let (ok, pending) = read(&mut rdr, &mut cx, &mut rec);
+        assert!(ok);
+        assert!(pending > 0);
+        assert_eq!(rec, vec!["a", "b\nc"]);
+        // A read left pending may be resumed with another record.
+        let mut other = ByteRecord::new();
+        assert!(Pin::new(&mut rdr).poll_read_byte_record(&mut cx, &mut rec).is_pending());
+        assert!(read(&mut rdr, &mut cx, &mut other).0);
+        assert_eq!(other, vec!["d", "e"]);
+        assert!(!read(&mut rdr, &mut cx, &mut rec).0);
+        assert!(!read(&mut rdr, &mut cx, &mut rec).0);
+    }
+
+    #[test]
+    fn multi_table() {
+        Runtime::new().unwrap().block_on(async {
+            for data in &[
+                "a,b\n1,2\n3,4\n\nx,y,z\n5,6,7\n",
+                "a,b\r\n1,2\r\n3,4\r\n\r\n\r\nx,y,z\r\n5,6,7",
+            ] {
+                let mut rdr = AsyncReaderBuilder::new()
+                    .multi_table(true)
+                    .create_reader(data.as_bytes());
+
+                let mut table = rdr.next_table().await.unwrap().unwrap();
+                assert_eq!(table.headers().await.unwrap(), vec!["a", "b"]);
+                let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+                assert_eq!(records, vec![vec!["1", "2"], vec!["3", "4"]]);
+
+                // Tables differ in length, which is not an error.
+                let mut table = rdr.next_table().await.unwrap().unwrap();
+                assert_eq!(table.headers().await.unwrap(), vec!["x", "y", "z"]);
+                let mut rec = StringRecord::new();
+                assert!(table.read_record(&mut rec).await.unwrap());
+                assert_eq!(rec, vec!["5", "6", "7"]);
+                assert!(!table.read_record(&mut rec).await.unwrap());
+
+                assert!(rdr.next_table().await.unwrap().is_none());
+            }
+        });
+    }
+
+    #[test]
+    fn multi_table_skips_unread_records() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "a\n1\n2\n\nb\n3\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .multi_table(true)
+                .create_reader(data.as_bytes());
+            rdr.next_table().await.unwrap().unwrap();
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            assert_eq!(table.headers().await.unwrap(), vec!["b"]);
+            assert_eq!(table.records().next().await.unwrap().unwrap(), vec!["3"]);
+            assert!(rdr.next_table().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn read_transform_field() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("NAME,City\nALICE,Boston\nBob,NEW YORK\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .transform_field(Box::new(|i, field: &[u8]| match i {
+                    0 => std::borrow::Cow::Owned(field.to_ascii_lowercase()),
+                    _ => std::borrow::Cow::Borrowed(field),
+                }))
+                .create_reader(data);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["NAME", "City"]);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["alice", "Boston"]);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["bob", "NEW YORK"]);
+            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn duplicate_headers() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("id,name,id,tag,name\n1,foo,2,x,bar\n");
+            let mut rdr = AsyncReaderBuilder::new().create_reader(data);
+            assert_eq!(rdr.check_duplicate_headers().await.unwrap(), vec![
+                ("id".to_string(), vec![0, 2]),
+                ("name".to_string(), vec![1, 4]),
+            ]);
+            assert_eq!(rdr.headers().await.unwrap().len(), 5);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .error_on_duplicate_headers(true)
+                .create_reader(data);
+            match *rdr.byte_headers().await.unwrap_err().kind() {
+                ErrorKind::DuplicateHeader { ref pos, ref name, ref fields } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 1);
+                    assert_eq!(name, "id");
+                    assert_eq!(fields, &[0, 2]);
+                }
+                ref wrong => panic!("expected DuplicateHeader but got {:?}", wrong),
+            }
+            // The records themselves can still be read.
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", "foo", "2", "x", "bar"]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .error_on_duplicate_headers(true)
+                .create_reader(b("id,name\n1,foo\n"));
+            assert!(rdr.check_duplicate_headers().await.unwrap().is_empty());
+            assert_eq!(rdr.headers().await.unwrap(), vec!["id", "name"]);
+        });
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn read_base64_columns() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("id,blob,name\n1,AAH/gA==,foo\n2,,bar\n3,not base64,baz\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .base64_columns(&[1])
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec![&b"1"[..], &[0x00, 0x01, 0xff, 0x80][..], &b"foo"[..]]);
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["2", "", "bar"]);
+            assert_eq!(rdr.byte_headers().await.unwrap(), vec!["id", "blob", "name"]);
+
+            let err = rdr.read_byte_record(&mut rec).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::Base64 { ref pos, field, .. } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 4);
+                    assert_eq!(field, 1);
+                }
+                ref wrong => panic!("expected Base64 error but got {:?}", wrong),
+            }
+            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn records_with_leading_header() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReaderBuilder::new()
+                .create_reader(b("city,pop\nBoston,4628910\nConcord,42695\n"));
+            let items: Vec<RecordOrHeader> = rdr
+                .records_with_leading_header()
+                .map(Result::unwrap)
+                .collect()
+                .await;
+            assert_eq!(items, vec![
+                RecordOrHeader::Header(vec!["city", "pop"].into()),
+                RecordOrHeader::Record(vec!["Boston", "4628910"].into()),
+                RecordOrHeader::Record(vec!["Concord", "42695"].into()),
+            ]);
+
+            // The header is delivered even if it has been read before, and
+            // for data without records.
+            let mut rdr = AsyncReaderBuilder::new().create_reader(b("city,pop\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["city", "pop"]);
+            let mut items = rdr.records_with_leading_header();
+            assert_eq!(
+                items.next().await.unwrap().unwrap(),
+                RecordOrHeader::Header(vec!["city", "pop"].into())
+            );
+            assert!(items.next().await.is_none());
+        });
+    }
+
+    #[cfg(feature = "timeout")]
+    #[test]
+    fn idle_timeout() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        // Serves whatever was pushed into `data`, then goes silent.
+        struct SilentRead(Arc<Mutex<Vec<u8>>>);
+        impl io::AsyncRead for SilentRead {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _cx: &mut Context,
+                buf: &mut tokio::io::ReadBuf
+            ) -> Poll<Result<(), io::Error>> {
+                let mut data = self.0.lock().unwrap();
+                if data.is_empty() {
+                    return Poll::Pending;
+                }
+                let n = std::cmp::min(buf.remaining(), data.len());
+                buf.put_slice(&data[..n]);
+                data.drain(..n);
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let data = Arc::new(Mutex::new(b("a,b\n1,2\n3,").to_vec()));
+            let mut rdr = AsyncReaderBuilder::new()
+                .idle_timeout(Some(Duration::from_millis(50)))
+                .create_reader(SilentRead(data.clone()));
+            let mut rec = StringRecord::new();
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", "2"]);
+
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::IdleTimeout));
+
+            data.lock().unwrap().extend_from_slice(b("4\n"));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["3", "4"]);
+        });
+    }
+
+    #[test]
+    fn follow() {
+        use std::sync::{Arc, Mutex};
+
+        // Serves whatever was pushed into `data`, reporting the end of the
+        // input whenever it runs out.
+        struct Growing(Arc<Mutex<Vec<u8>>>);
+        impl io::AsyncRead for Growing {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _cx: &mut Context,
+                buf: &mut tokio::io::ReadBuf
+            ) -> Poll<Result<(), io::Error>> {
+                let mut data = self.0.lock().unwrap();
+                let n = std::cmp::min(buf.remaining(), data.len());
+                buf.put_slice(&data[..n]);
+                data.drain(..n);
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let data = Arc::new(Mutex::new(b("h1,h2\na,b\nc,").to_vec()));
+        let mut rdr = AsyncReaderBuilder::new()
+            .follow(true)
+            .create_reader(Growing(data.clone()));
+        let mut rec = ByteRecord::new();
+        let mut poll = |rdr: &mut AsyncReader<Growing>, rec: &mut ByteRecord| {
+            match Pin::new(rdr).poll_read_byte_record(&mut cx, rec) {
+                Poll::Ready(result) => Some(result.unwrap()),
+                Poll::Pending => None,
+            }
+        };
+
+        assert_eq!(poll(&mut rdr, &mut rec), Some(true));
+        assert_eq!(rec, vec!["a", "b"]);
+        assert_eq!(poll(&mut rdr, &mut rec), None);
+        assert_eq!(poll(&mut rdr, &mut rec), None);
+
+        data.lock().unwrap().extend_from_slice(b("d\ne,f\n"));
+        assert_eq!(poll(&mut rdr, &mut rec), Some(true));
+        assert_eq!(rec, vec!["c", "d"]);
+        assert_eq!(poll(&mut rdr, &mut rec), Some(true));
+        assert_eq!(rec, vec!["e", "f"]);
+        assert_eq!(poll(&mut rdr, &mut rec), None);
+        assert!(!rdr.is_done());
+    }
+
+    #[test]
+    fn infer_schema() {
+        use crate::{ColumnType, InferredType};
+
+        Runtime::new().unwrap().block_on(async {
+            let data = "id,price,name,active\n1,2.5,foo,true\n2,3,bar,\n3,4,7,false\n4,x,baz,true\n";
+            let mut rdr = AsyncReaderBuilder::new().create_reader(data.as_bytes());
+            let schema = rdr.infer_schema(3).await.unwrap();
+            assert_eq!(schema, vec![
+                InferredType::new(ColumnType::Int, false),
+                InferredType::new(ColumnType::Float, false),
+                InferredType::new(ColumnType::Text, false),
+                InferredType::new(ColumnType::Bool, true),
+            ]);
+
+            // Sampling again covers the same records, and all records can
+            // still be read in order.
+            assert_eq!(rdr.infer_schema(4).await.unwrap()[1].column_type(), ColumnType::Text);
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records.len(), 4);
+            assert_eq!(records[0], vec!["1", "2.5", "foo", "true"]);
+            assert_eq!(records[3], vec!["4", "x", "baz", "true"]);
+            assert!(rdr.is_done());
+        });
+    }
+
+    #[test]
+    fn resume_from_parser_state() {
+        // Delivers its data, then fails like a dropped connection.
+        struct Cut(&'static [u8]);
+        impl io::AsyncRead for Cut {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context,
+                buf: &mut tokio::io::ReadBuf
+            ) -> Poll<Result<(), io::Error>> {
+                if self.0.is_empty() {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "cut")));
+                }
+                let n = std::cmp::min(buf.remaining(), self.0.len());
+                buf.put_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let mut builder = AsyncReaderBuilder::new();
+            builder.resumable(true);
+            let mut rdr = builder.create_reader(Cut(b("a,b\n0,w\n1,\"x\ny")));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["0", "w"]);
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::Io(_)));
+
+            let state = rdr.parser_state();
+            assert_eq!(state.pending(), b("1,\"x\ny"));
+            assert_eq!(state.position(), &newpos(8, 3, 2));
+
+            let mut rdr = builder.create_reader_with_state(state, b("z\"\n3,4\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["a", "b"]);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", "x\nyz"]);
+            assert_eq!(rec.position(), Some(&newpos(8, 3, 2)));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["3", "4"]);
+            assert_eq!(rec.position(), Some(&newpos(17, 5, 3)));
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn normalize_newlines() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "h1,h2\r\n\"a\r\nb\rc\",\"d\ne\"\r\nf,g\r\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .normalize_newlines(true)
+                .create_reader(data.as_bytes());
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![
+                StringRecord::from(vec!["a\nb\nc", "d\ne"]),
+                StringRecord::from(vec!["f", "g"]),
+            ]);
+
+            let mut rdr = AsyncReaderBuilder::new().create_reader(data.as_bytes());
+            let record = rdr.records().next().await.unwrap().unwrap();
+            assert_eq!(record, vec!["a\r\nb\rc", "d\ne"]);
+        });
+    }
+
+    #[test]
+    fn records_with_arity() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "a,b,c\n1,2,3\n4\n5,6\n7,8,9,10\n11,12,13,14,15\n16,17,18\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .create_reader(data.as_bytes());
+            let records = rdr.records_with_arity(2, 4)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await;
+            assert_eq!(records, vec![
+                StringRecord::from(vec!["1", "2", "3"]),
+                StringRecord::from(vec!["5", "6"]),
+                StringRecord::from(vec!["7", "8", "9", "10"]),
+                StringRecord::from(vec!["16", "17", "18"]),
+            ]);
+        });
+    }
+
+    #[test]
+    fn track_timing() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "a,b\n1,2\n3,4\n5,6\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .track_timing(true)
+                .create_reader(data.as_bytes());
+            assert_eq!(count(rdr.records()).await, 3);
+            let timing = rdr.timing();
+            assert_eq!(timing.bytes, data.len() as u64);
+            assert_eq!(timing.records, 3);
+            assert!(timing.elapsed > std::time::Duration::from_secs(0));
+
+            let mut rdr = AsyncReaderBuilder::new().create_reader(data.as_bytes());
+            assert_eq!(count(rdr.records()).await, 3);
+            assert_eq!(rdr.timing(), crate::ParseTiming::default());
+        });
+    }
+
+    #[test]
+    fn expect_footer() {
+        Runtime::new().unwrap().block_on(async {
+            let spec = FooterSpec { label: Some("END".to_string()), checksum: true };
+            let data = "a,b\r\n1,2\r\n3,4\r\nEND,2,16360257\r\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .expect_footer(spec.clone())
+                .create_reader(data.as_bytes());
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["1", "2"], vec!["3", "4"]]);
+
+            let data = "a,b\n1,2\n3,4\n3\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .expect_footer(FooterSpec::default())
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["1", "2"]);
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["3", "4"]);
+            match *records.next().await.unwrap().unwrap_err().kind() {
+                ErrorKind::FooterMismatch { ref pos, ref msg } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 4);
+                    assert_eq!(msg, "declares 3 records, but 2 were read");
+                }
+                ref err => panic!("expected footer mismatch, got {:?}", err),
+            }
+            assert!(records.next().await.is_none());
+
+            let data = "a,b\n1,2\n3,5\nEND,2,10860230\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .expect_footer(spec)
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            records.next().await.unwrap().unwrap();
+            records.next().await.unwrap().unwrap();
+            let err = records.next().await.unwrap().unwrap_err();
+            assert!(err.to_string().contains("declares checksum 10860230"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn max_field_size() {
+        Runtime::new().unwrap().block_on(async {
+            let data = format!("a,b\n1,\"{}\n{}\"\n2,3\n", "x".repeat(500), "x,".repeat(250));
+            let mut rdr = AsyncReaderBuilder::new()
+                .max_field_size(Some(100))
+                .buffer_capacity(64)
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            let err = records.next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::FieldTooLarge { ref pos, field_index, size, limit } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 2);
+                    assert_eq!(field_index, 1);
+                    assert!(size > 100 && size < 1000, "{}", size);
+                    assert_eq!(limit, 100);
+                }
+                ref err => panic!("expected field size error, got {:?}", err),
+            }
+            // The error is reported once, and the rest of the record is
+            // discarded.
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["2", "3"]);
+            assert!(records.next().await.is_none());
+
+            let data = "a,b\n1,2\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .max_field_size(Some(1))
+                .create_reader(data.as_bytes());
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["1", "2"]]);
+        });
+    }
+
+    #[test]
+    fn max_record_size() {
+        Runtime::new().unwrap().block_on(async {
+            let data = format!("a,b,c\n1,2,3\n{}\n4,5,6\n", vec!["x".repeat(40); 3].join(","));
+            let mut rdr = AsyncReaderBuilder::new()
+                .max_field_size(Some(50))
+                .max_record_size(Some(100))
+                .buffer_capacity(64)
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["1", "2", "3"]);
+            let err = records.next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::RecordTooLarge { ref pos, size, limit } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 3);
+                    assert!(size > 100 && size <= 120, "{}", size);
+                    assert_eq!(limit, 100);
+                }
+                ref err => panic!("expected record size error, got {:?}", err),
+            }
+            // The rest of the record is discarded.
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["4", "5", "6"]);
+            assert!(records.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn create_reader_limited() {
+        Runtime::new().unwrap().block_on(async {
+            let csv = "id,name\n1,alpha\n2,bravo\n3,charlie\n4,delta\n5,hotel\n";
+            assert_eq!(csv.len(), 50);
+            let input = format!("{}\x00\x01trailer", csv);
+            let mut data = input.as_bytes();
+            let mut rdr = AsyncReaderBuilder::new()
+                .buffer_capacity(16)
+                .create_reader_limited(&mut data, 50);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 5);
+            assert_eq!(records[4], vec!["5", "hotel"]);
+            let inner = rdr.into_inner().into_inner();
+            let mut rest = vec![];
+            inner.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b"\x00\x01trailer");
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_deserialize() {
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            id: u32,
+            name: String,
+            score: Option<f64>,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let mut data = String::from("id,name,score\n");
+            for i in 0..100 {
+                data.push_str(&format!("{},name{},{}\n", i, i, if i % 3 == 0 { "".to_string() } else { format!("{}.5", i) }));
+            }
+            let mut des = crate::AsyncDeserializer::from_reader(data.as_bytes());
+            let serial: Vec<Row> = des.deserialize().map(Result::unwrap).collect().await;
+
+            let mut rdr = AsyncReader::from_reader(data.as_bytes());
+            let batches: Vec<Vec<Row>> =
+                rdr.par_deserialize(7).map(Result::unwrap).collect().await;
+            assert_eq!(batches.len(), 15);
+            assert!(batches[..14].iter().all(|batch| batch.len() == 7));
+            let parallel: Vec<Row> = batches.into_iter().flatten().collect();
+            assert_eq!(parallel, serial);
+
+            let mut rdr = AsyncReader::from_reader("id,name,score\n1,a,x\n".as_bytes());
+            let mut batches = rdr.par_deserialize::<Row>(7);
+            assert!(batches.next().await.unwrap().is_err());
+            assert!(batches.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn records_sectioned() {
+        Runtime::new().unwrap().block_on(async {
+            let data = "a,1\nb,2\nc,3,x\nd,4,y\ne,5\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader(data.as_bytes());
+            let events: Vec<SectionEvent> =
+                rdr.records_sectioned().map(Result::unwrap).collect().await;
+            let record = |fields: &[&str]| SectionEvent::Record(StringRecord::from(fields.to_vec()));
+            assert_eq!(events, vec![
+                SectionEvent::NewSection { field_count: 2 },
+                record(&["a", "1"]),
+                record(&["b", "2"]),
+                SectionEvent::NewSection { field_count: 3 },
+                record(&["c", "3", "x"]),
+                record(&["d", "4", "y"]),
+                SectionEvent::NewSection { field_count: 2 },
+                record(&["e", "5"]),
+            ]);
+        });
+    }
+
+    #[test]
+    fn record_pool() {
+        Runtime::new().unwrap().block_on(async {
+            let pool = Arc::new(RecordPool::new(4));
+            let mut rdr = AsyncReaderBuilder::new()
+                .record_pool(pool.clone())
+                .create_reader("a,b\nlong field,x\ny,z\n".as_bytes());
+            let mut held = vec![];
+            let mut records = rdr.byte_records();
+            while let Some(record) = records.next().await {
+                held.push(record.unwrap());
+            }
+            assert_eq!(held, vec![vec!["long field", "x"], vec!["y", "z"]]);
+            assert_eq!(pool.reused(), 0);
+            for record in held {
+                pool.put(record);
+            }
+            assert_eq!(pool.idle(), 2);
+
+            // A second reader sharing the pool takes its records from it.
+            let mut rdr = AsyncReaderBuilder::new()
+                .record_pool(pool.clone())
+                .create_reader("c\n1\n2\n".as_bytes());
+            let mut records = rdr.records();
+            let first = records.next().await.unwrap().unwrap();
+            let second = records.next().await.unwrap().unwrap();
+            assert!(records.next().await.is_none());
+            assert_eq!((first, second), (StringRecord::from(vec!["1"]), StringRecord::from(vec!["2"])));
+            assert_eq!(pool.reused(), 2);
+            assert_eq!(pool.idle(), 0);
+        });
+    }
+
+    #[test]
+    fn records_until() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("a,b\n1,2\n3,4\n5,6\n");
+            let mut rdr = AsyncReader::from_reader(data);
+
+            // A deadline already passed ends the stream before any record.
+            let past = Instant::now();
+            let got: Vec<_> = rdr.records_until(past).collect().await;
+            assert!(got.is_empty());
+            assert!(!rdr.is_done());
+
+            let far = Instant::now() + Duration::from_secs(3600);
+            let mut records = rdr.records_until(far);
+            assert_eq!(records.next().await.unwrap().unwrap(), vec!["1", "2"]);
+            drop(records);
+
+            let got: Vec<_> = rdr.records_until(past).collect().await;
+            assert!(got.is_empty());
+
+            // The reader continues after the records already yielded.
+            let got: Vec<StringRecord> = rdr
+                .records_until(far)
+                .map(|record| record.unwrap())
+                .collect()
+                .await;
+            assert_eq!(got, vec![vec!["3", "4"], vec!["5", "6"]]);
+            assert!(rdr.is_done());
+        });
+    }
+
+    #[test]
+    fn read_byte_record_with_spans() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo,\"b,ar\",baz\nabc,mno,xyz");
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let data = "a,b,c\nfoo,,barbaz\n\n#note\n\"q,\"\"x\"\"\",y,\r\nlast,z";
+            let mut rdr = AsyncReaderBuilder::new()
+                .comment(Some(b'#'))
+                .flexible(true)
+                .create_reader(data.as_bytes());
             let mut rec = ByteRecord::new();
+            let mut spans = vec![];
+            let slices = |spans: &[(u64, u64)]| -> Vec<&str> {
+                spans.iter().map(|&(o, l)| &data[o as usize..(o + l) as usize]).collect()
+            };
 
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("foo", s(&rec[0]));
-            assert_eq!("b,ar", s(&rec[1]));
-            assert_eq!("baz", s(&rec[2]));
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(rec, vec!["foo", "", "barbaz"]);
+            assert_eq!(spans, vec![(6, 3), (10, 0), (11, 6)]);
+            assert_eq!(slices(&spans), vec!["foo", "", "barbaz"]);
 
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("abc", s(&rec[0]));
-            assert_eq!("mno", s(&rec[1]));
-            assert_eq!("xyz", s(&rec[2]));
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(rec, vec!["q,\"x\"", "y", ""]);
+            assert_eq!(slices(&spans), vec!["\"q,\"\"x\"\"\"", "y", ""]);
 
-            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(rec, vec!["last", "z"]);
+            assert_eq!(slices(&spans), vec!["last", "z"]);
+
+            assert!(!rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert!(spans.is_empty());
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .fixed_width(Some(&[3, 4]))
+                .create_reader("ab cdef\n\nxyz1\n".as_bytes());
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(spans, vec![(0, 3), (3, 4)]);
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(spans, vec![(9, 3), (12, 1)]);
         });
     }
 
     #[test]
-    fn read_trimmed_records_and_headers() {
+    fn collapse_delimiters() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
             let mut rdr = AsyncReaderBuilder::new()
-                .has_headers(true)
-                .trim(Trim::All)
-                .create_reader(data);
-            let mut rec = ByteRecord::new();
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!("1", s(&rec[0]));
-            assert_eq!("2", s(&rec[1]));
-            assert_eq!("3", s(&rec[2]));
+                .has_headers(false)
+                .create_reader("a,,b\n".as_bytes());
             let mut rec = StringRecord::new();
             assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!("1", &rec[0]);
-            assert_eq!("", &rec[1]);
-            assert_eq!("3", &rec[2]);
-            {
-                let headers = rdr.headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!("foo", &headers[0]);
-                assert_eq!("bar", &headers[1]);
-                assert_eq!("baz", &headers[2]);
-            }
+            assert_eq!(rec, vec!["a", "", "b"]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .delimiter(b' ')
+                .collapse_delimiters(true)
+                .create_reader("a   b\n  c d  \n   \n\"e\"  \"\" f\n".as_bytes());
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a", "b"]);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["c", "d"]);
+            assert_eq!(rec.position().unwrap().line(), 2);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["e", "f"]);
+            assert_eq!(rec.position().unwrap().line(), 4);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
         });
     }
 
     #[test]
-    fn read_trimmed_header() {
+    fn byte_records_filter_map() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
+            let data = b("n\n1\nx\n22\n\"\"\n333\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let got: Vec<u64> = rdr
+                .byte_records_filter_map(|record| {
+                    let n = std::str::from_utf8(&record[0]).unwrap().parse::<u64>().ok();
+                    Ok(n)
+                })
+                .map(|n| n.unwrap())
+                .collect()
+                .await;
+            assert_eq!(got, vec![1, 22, 333]);
+
+            // Errors from the closure are yielded, and reading goes on.
+            let mut rdr = AsyncReader::from_reader(data);
+            let mut values = rdr.byte_records_filter_map(|record| {
+                if &record[0] == b"x" {
+                    Err(crate::Error::from(io::Error::from(io::ErrorKind::InvalidData)))
+                } else {
+                    Ok(Some(record.len()))
+                }
+            });
+            assert_eq!(values.next().await.unwrap().unwrap(), 1);
+            assert!(values.next().await.unwrap().is_err());
+            assert_eq!(values.next().await.unwrap().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn is_header_only() {
+        Runtime::new().unwrap().block_on(async {
+            let mut rdr = AsyncReader::from_reader(b("a,b\n"));
+            assert!(rdr.is_header_only().await.unwrap());
+            assert_eq!(rdr.headers().await.unwrap(), vec!["a", "b"]);
+            let mut rec = StringRecord::new();
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            let mut rdr = AsyncReader::from_reader(b(""));
+            assert!(!rdr.is_header_only().await.unwrap());
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            let mut rdr = AsyncReader::from_reader(b("a,b\n1,2\n"));
+            assert!(!rdr.is_header_only().await.unwrap());
+            assert!(!rdr.is_header_only().await.unwrap());
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", "2"]);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
             let mut rdr = AsyncReaderBuilder::new()
-                .has_headers(true)
-                .trim(Trim::Headers)
-                .create_reader(data);
-            let mut rec = ByteRecord::new();
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!("  1", s(&rec[0]));
-            assert_eq!("  2", s(&rec[1]));
-            assert_eq!("  3", s(&rec[2]));
-            {
-                let headers = rdr.headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!("foo", &headers[0]);
-                assert_eq!("bar", &headers[1]);
-                assert_eq!("baz", &headers[2]);
+                .has_headers(false)
+                .create_reader(b("a,b\n"));
+            assert!(!rdr.is_header_only().await.unwrap());
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a", "b"]);
+        });
+    }
+
+    #[test]
+    fn strict() {
+        Runtime::new().unwrap().block_on(async {
+            let cases = [
+                ("a,b\n", "record terminated by a bare \\n"),
+                ("a,b\r", "record terminated by a bare \\r"),
+                ("a,b\rc,d\r\n", "record terminated by a bare \\r"),
+                ("a, \"b\"\r\n", "whitespace before a quoted field"),
+                ("a,\"b\" \r\n", "whitespace after a quoted field"),
+                ("a,\"b\"c\"\r\n", "quote not doubled within a quoted field"),
+                ("a,b\"c\r\n", "quote within an unquoted field"),
+            ];
+            for &(data, reason) in cases.iter() {
+                let mut rdr = AsyncReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .strict(true)
+                    .create_reader(b(data));
+                let mut rec = StringRecord::new();
+                let err = rdr.read_record(&mut rec).await.unwrap_err();
+                match *err.kind() {
+                    ErrorKind::Strict { ref pos, reason: ref got } => {
+                        assert_eq!(got, reason, "{:?}", data);
+                        assert!(pos.is_some());
+                    }
+                    ref kind => panic!("unexpected error kind {:?} for {:?}", kind, data),
+                }
+
+                let mut rdr = AsyncReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .create_reader(b(data));
+                let mut count = 0;
+                while rdr.read_record(&mut rec).await.unwrap() {
+                    count += 1;
+                }
+                assert!(count > 0, "{:?}", data);
             }
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .strict(true)
+                .create_reader(b("a,\"b\"\"c\",\"d\r\ne\"\r\n1, 2 ,\r\n"));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a", "b\"c", "d\r\ne"]);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["1", " 2 ", ""]);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .strict(true)
+                .buffer_capacity(4)
+                .create_reader(b("a,b\r\nc,d\rx,y\r\n"));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["a", "b"]);
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            assert_eq!(err.position().unwrap().record(), 1);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["x", "y"]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .strict(true)
+                .terminator(Terminator::Any(b'\n'))
+                .create_reader(b("a,b\nc,d"));
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["c", "d"]);
         });
     }
 
+    #[cfg(feature = "regex")]
     #[test]
-    fn read_trimed_header_invalid_utf8() {
+    fn column_patterns() {
         Runtime::new().unwrap().block_on(async {
-            let data = &b"foo,  b\xFFar,\tbaz\na,b,c\nd,e,f"[..];
+            let email = regex::Regex::new(r"^[^@\s]+@[^@\s]+\.[a-z]+$").unwrap();
+            let data = b("email,name\nann@example.com,Ann\nbob.example.com,Bob\ncid@example.org,Cid\n");
             let mut rdr = AsyncReaderBuilder::new()
-                .has_headers(true)
-                .trim(Trim::Headers)
+                .column_patterns(vec![(0, email)])
                 .create_reader(data);
             let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["ann@example.com", "Ann"]);
 
-            // force the headers to be read
-            let _ = rdr.read_record(&mut rec).await;
-            // Check the byte headers are trimmed
-            {
-                let headers = rdr.byte_headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!(b"foo", &headers[0]);
-                assert_eq!(b"b\xFFar", &headers[1]);
-                assert_eq!(b"baz", &headers[2]);
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::PatternMismatch { ref pos, field_index, ref value } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 3);
+                    assert_eq!(field_index, 0);
+                    assert_eq!(value, "bob.example.com");
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
             }
-            match *rdr.headers().await.unwrap_err().kind() {
-                ErrorKind::Utf8 { pos: Some(ref pos), ref err } => {
-                    assert_eq!(pos, &newpos(0, 1, 0));
-                    assert_eq!(err.field(), 1);
-                    assert_eq!(err.valid_up_to(), 3);
+
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["cid@example.org", "Cid"]);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        });
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn into_record_batches() {
+        use arrow_array::{Array, Int64Array, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+
+        Runtime::new().unwrap().block_on(async {
+            let schema = Schema::new(vec![
+                Field::new("id", DataType::Int64, true),
+                Field::new("name", DataType::Utf8, false),
+            ]);
+            let data = b("id,name\n1,alpha\n2,bravo\n,charlie\n4,\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let batches: Vec<_> = rdr
+                .into_record_batches(schema.clone(), 3)
+                .map(Result::unwrap)
+                .collect()
+                .await;
+            assert_eq!(batches.len(), 2);
+            assert_eq!(batches[0].num_rows(), 3);
+            assert_eq!(batches[1].num_rows(), 1);
+            assert_eq!(batches[0].schema().as_ref(), &schema);
+
+            let ids = batches[0].column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(ids.len(), 3);
+            assert_eq!(ids.value(0), 1);
+            assert_eq!(ids.value(1), 2);
+            assert!(ids.is_null(2));
+            let names = batches[0].column(1).as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(
+                names.iter().collect::<Vec<_>>(),
+                vec![Some("alpha"), Some("bravo"), Some("charlie")]
+            );
+
+            let ids = batches[1].column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(ids.value(0), 4);
+            let names = batches[1].column(1).as_any().downcast_ref::<StringArray>().unwrap();
+            assert!(!names.is_null(0));
+            assert_eq!(names.value(0), "");
+
+            let mut rdr = AsyncReader::from_reader(b("id,name\n1,a\nx,b\n"));
+            let mut batches = rdr.into_record_batches(schema, 10);
+            let err = batches.next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::Arrow { ref pos, field, .. } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 3);
+                    assert_eq!(field, Some(0));
                 }
-                ref err => panic!("match failed, got {:?}", err),
+                ref kind => panic!("unexpected error kind: {:?}", kind),
             }
+            assert!(batches.next().await.is_none());
         });
     }
 
     #[test]
-    fn read_trimmed_records() {
+    fn invalid_utf8_policy() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo,  bar,\tbaz\n  1,  2,  3\n1\t,\t,3\t\t");
+            let data = &b"city,pop\nBoston,4628910\nConc\xffrd,42695\nDover,32741\n"[..];
+
+            let mut rdr = AsyncReader::from_reader(data);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            let err = rdr.read_record(&mut rec).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::Utf8 { ref pos, ref err } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 3);
+                    assert_eq!(err.field(), 0);
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["Dover", "32741"]);
+
             let mut rdr = AsyncReaderBuilder::new()
-                .has_headers(true)
-                .trim(Trim::Fields)
+                .invalid_utf8(InvalidUtf8Policy::ReplaceLossy)
                 .create_reader(data);
-            let mut rec = ByteRecord::new();
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!("1", s(&rec[0]));
-            assert_eq!("2", s(&rec[1]));
-            assert_eq!("3", s(&rec[2]));
-            {
-                let headers = rdr.headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!("foo", &headers[0]);
-                assert_eq!("  bar", &headers[1]);
-                assert_eq!("\tbaz", &headers[2]);
-            }
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 3);
+            assert_eq!(records[1], vec!["Conc\u{FFFD}rd", "42695"]);
+            assert_eq!(records[1].position().unwrap().line(), 3);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .invalid_utf8(InvalidUtf8Policy::Skip)
+                .create_reader(data);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["Boston", "4628910"], vec!["Dover", "32741"]]);
+
+            // Byte records are not affected.
+            let mut rdr = AsyncReaderBuilder::new()
+                .invalid_utf8(InvalidUtf8Policy::Skip)
+                .create_reader(data);
+            let records: Vec<ByteRecord> =
+                rdr.byte_records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 3);
+            assert_eq!(&records[1][0], &b"Conc\xffrd"[..]);
         });
     }
 
     #[test]
-    fn read_record_unequal_fails() {
+    fn field_wrappers() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo\nbar,baz");
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
-            let mut rec = ByteRecord::new();
+            let data = b("[id],[name],note\n[1],[alpha],plain\n2,[],\"[a,b]\"\n[3,[x]],]y[\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .field_wrappers(Some((b'[', b']')))
+                .create_reader(data);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["id", "name", "note"]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![
+                vec!["1", "alpha", "plain"],
+                vec!["2", "", "a,b"],
+                vec!["[3", "x]", "]y["],
+            ]);
+            assert_eq!(records[1].position().unwrap().line(), 3);
 
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(1, rec.len());
-            assert_eq!("foo", s(&rec[0]));
+            // Without wrappers configured, fields are left as is.
+            let mut rdr = AsyncReader::from_reader(b("[a],b\n[c],d\n"));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["[c]", "d"]);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["[a]", "b"]);
+        });
+    }
 
-            match rdr.read_byte_record(&mut rec).await {
-                Err(err) => match *err.kind() {
-                    ErrorKind::UnequalLengths {
-                        expected_len: 1,
-                        ref pos,
-                        len: 2,
-                    } => {
-                        assert_eq!(pos, &Some(newpos(4, 2, 1)));
-                    }
-                    ref wrong => panic!("match failed, got {:?}", wrong),
-                },
-                wrong => panic!("match failed, got {:?}", wrong),
+    #[test]
+    fn scan_records() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("day,sales\nmon,3\ntue,x\nwed,5\nthu,2\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let items: Vec<crate::Result<(String, u64)>> = rdr
+                .scan_records(0u64, |total, record| {
+                    let sales: u64 = record[1].parse().map_err(|_| {
+                        crate::Error::from(io::Error::from(io::ErrorKind::InvalidData))
+                    })?;
+                    *total += sales;
+                    Ok((record[0].to_string(), *total))
+                })
+                .collect()
+                .await;
+            assert_eq!(items.len(), 4);
+            assert_eq!(items[0].as_ref().unwrap(), &("mon".to_string(), 3));
+            assert!(items[1].is_err());
+            assert_eq!(items[2].as_ref().unwrap(), &("wed".to_string(), 8));
+            assert_eq!(items[3].as_ref().unwrap(), &("thu".to_string(), 10));
+        });
+    }
+
+    #[test]
+    fn respect_sep_directive() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("sep=;\r\na;b\r\n1;2,5\r\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .respect_sep_directive(true)
+                .create_reader(data);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["a", "b"]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["1", "2,5"]]);
+            let pos = records[0].position().unwrap();
+            assert_eq!((pos.byte(), pos.line(), pos.record()), (11, 2, 1));
+
+            // Without the option, the directive is read as data.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .create_reader(b("sep=;\na;b\n"));
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["sep=;"], vec!["a;b"]]);
+
+            // Data not starting with a directive is read as usual, even when
+            // looking for one spans several buffer fills.
+            let mut rdr = AsyncReaderBuilder::new()
+                .respect_sep_directive(true)
+                .buffer_capacity(2)
+                .create_reader(b("separate,x\nsep=|,y\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["separate", "x"]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["sep=|", "y"]]);
+            assert_eq!(records[0].position().unwrap().byte(), 11);
+
+            // Quoting and terminator settings carry over to the new parser.
+            let mut rdr = AsyncReaderBuilder::new()
+                .respect_sep_directive(true)
+                .quote(b'\'')
+                .create_reader(b("sep=\t\nname\tnote\nx\t'a\tb'\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["name", "note"]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["x", "a\tb"]]);
+        });
+    }
+
+    #[test]
+    fn records_hashed() {
+        Runtime::new().unwrap().block_on(async {
+            let data = b("a,b\nx,yz\nq,r\nx,yz\nxy,z\n\"x\",yz\n");
+            let mut rdr = AsyncReaderBuilder::new().has_headers(false).create_reader(data);
+            let items: Vec<(u64, StringRecord)> =
+                rdr.records_hashed().map(Result::unwrap).collect().await;
+            let hashes: Vec<u64> = items.iter().map(|&(hash, _)| hash).collect();
+            assert_eq!(items[1].1, vec!["x", "yz"]);
+            // Identical records, however they were quoted, hash the same.
+            assert_eq!(hashes[1], hashes[3]);
+            assert_eq!(hashes[1], hashes[5]);
+            // Distinct records differ, even with the same bytes overall.
+            assert_ne!(hashes[0], hashes[1]);
+            assert_ne!(hashes[1], hashes[2]);
+            assert_ne!(hashes[1], hashes[4]);
+            // The hash only depends on the fields.
+            let mut rdr = AsyncReader::from_reader(b("head,i\nx,yz\n"));
+            let mut other = rdr.records_hashed();
+            let other = other.next().await.unwrap().unwrap();
+            assert_eq!(other.0, hashes[1]);
+            assert_ne!(other.1.position(), items[1].1.position());
+        });
+    }
+
+    #[test]
+    fn max_total_bytes() {
+        Runtime::new().unwrap().block_on(async {
+            let mut data = String::from("a,b\n");
+            for i in 0..50 {
+                data.push_str(&format!("row{:02},xyz\n", i));
+            }
+            let mut rdr = AsyncReaderBuilder::new()
+                .max_total_bytes(Some(100))
+                .buffer_capacity(16)
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            for i in 0..9 {
+                let record = records.next().await.unwrap().unwrap();
+                assert_eq!(record[0], format!("row{:02}", i));
+            }
+            let err = records.next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::InputTooLarge { limit } => assert_eq!(limit, 100),
+                ref err => panic!("expected input size error, got {:?}", err),
+            }
+            // The limit stays exceeded.
+            assert!(records.next().await.unwrap().is_err());
+            drop(records);
+            assert!(rdr.position().byte() > 100);
+            assert!(rdr.position().byte() <= 116);
+
+            // An input of exactly the limit is fine.
+            let mut rdr = AsyncReaderBuilder::new()
+                .max_total_bytes(Some(8))
+                .create_reader(b("a,b\n1,2\n"));
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["1", "2"]]);
+
+            // The limit also applies within a line of fixed-width data.
+            let data = "x".repeat(1000);
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .fixed_width(Some(&[10]))
+                .max_total_bytes(Some(100))
+                .buffer_capacity(16)
+                .create_reader(data.as_bytes());
+            let mut records = rdr.records();
+            for _ in 0..10 {
+                let err = records.next().await.unwrap().unwrap_err();
+                assert!(matches!(*err.kind(), ErrorKind::InputTooLarge { limit: 100 }));
             }
         });
     }
 
     #[test]
-    fn read_record_unequal_ok() {
+    fn track_quoted() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo\nbar,baz");
+            let data = b("\"\",a\n,a\n\"x\"\"y\",  \"z\"\n");
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .track_quoted(true)
+                .buffer_capacity(3)
+                .create_reader(data);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["", "a"]);
+            assert_eq!((rec.was_quoted(0), rec.was_quoted(1)), (Some(true), Some(false)));
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["", "a"]);
+            assert_eq!((rec.was_quoted(0), rec.was_quoted(1)), (Some(false), Some(false)));
+            // Only a quote starting a field quotes it.
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["x\"y", "  \"z\""]);
+            assert_eq!((rec.was_quoted(0), rec.was_quoted(1)), (Some(true), Some(false)));
+            assert_eq!(rec.was_quoted(2), None);
+
+            // Flags survive field transformations and are cloned along.
+            let mut rdr = AsyncReaderBuilder::new()
+                .track_quoted(true)
+                .trim(Trim::All)
+                .transform_field(Box::new(|_, field: &[u8]| field.to_ascii_uppercase().into()))
+                .create_reader(b("h1,h2\n\" a \",b\n"));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            let rec = rec.clone();
+            assert_eq!(rec, vec!["A", "B"]);
+            assert_eq!(rec.as_byte_record().was_quoted(0), Some(true));
+            assert_eq!(rec.as_byte_record().was_quoted(1), Some(false));
+            assert_eq!(rdr.byte_headers().await.unwrap().was_quoted(0), Some(false));
+
+            // Dropped empty fields drop their flags.
             let mut rdr = AsyncReaderBuilder::new()
                 .has_headers(false)
-                .flexible(true)
-                .create_reader(data);
+                .track_quoted(true)
+                .collapse_delimiters(true)
+                .create_reader(b("\"\",,\"a\",b\n"));
             let mut rec = ByteRecord::new();
-
             assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(1, rec.len());
-            assert_eq!("foo", s(&rec[0]));
+            assert_eq!(rec, vec!["a", "b"]);
+            assert_eq!((rec.was_quoted(0), rec.was_quoted(1)), (Some(true), Some(false)));
 
+            // Without the option, nothing is known.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader(b("\"\",a\n"));
             assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(2, rec.len());
-            assert_eq!("bar", s(&rec[0]));
-            assert_eq!("baz", s(&rec[1]));
+            assert_eq!(rec.was_quoted(0), None);
 
-            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+            // Spans are still reported along with the flags.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .track_quoted(true)
+                .create_reader(b("a,\"b\"\nc,d\n"));
+            let mut spans = Vec::new();
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(spans, vec![(0, 1), (2, 3)]);
+            assert!(rdr.read_byte_record_with_spans(&mut rec, &mut spans).await.unwrap());
+            assert_eq!(spans, vec![(6, 1), (8, 1)]);
+            assert_eq!(rec.was_quoted(1), Some(false));
         });
     }
 
-    // This tests that even if we get a CSV error, we can continue reading
-    // if we want.
     #[test]
-    fn read_record_unequal_continue() {
+    fn multi_table_sep_directive() {
         Runtime::new().unwrap().block_on(async {
-            let data = b("foo\nbar,baz\nquux");
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
-            let mut rec = ByteRecord::new();
-
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(1, rec.len());
-            assert_eq!("foo", s(&rec[0]));
+            let data = "sep=,\na,b\n1,2\n\nsep=;\nx;y\n3;4,5\n\nc,d\n6,7\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .multi_table(true)
+                .respect_sep_directive(true)
+                .create_reader(data.as_bytes());
 
-            match rdr.read_byte_record(&mut rec).await {
-                Err(err) => match err.kind() {
-                    &ErrorKind::UnequalLengths {
-                        expected_len: 1,
-                        ref pos,
-                        len: 2,
-                    } => {
-                        assert_eq!(pos, &Some(newpos(4, 2, 1)));
-                    }
-                    wrong => panic!("match failed, got {:?}", wrong),
-                },
-                wrong => panic!("match failed, got {:?}", wrong),
-            }
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            assert_eq!(table.headers().await.unwrap(), vec!["a", "b"]);
+            let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["1", "2"]]);
 
-            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
-            assert_eq!(1, rec.len());
-            assert_eq!("quux", s(&rec[0]));
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            assert_eq!(table.headers().await.unwrap(), vec!["x", "y"]);
+            let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["3", "4,5"]]);
+            let pos = records[0].position().unwrap();
+            assert_eq!((pos.byte(), pos.line()), (25, 7));
 
-            assert!(!rdr.read_byte_record(&mut rec).await.unwrap());
+            // A table without a directive has the configured delimiter.
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            assert_eq!(table.headers().await.unwrap(), vec!["c", "d"]);
+            let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["6", "7"]]);
+            assert!(rdr.next_table().await.unwrap().is_none());
         });
     }
 
     #[test]
-    fn read_record_headers() {
-        Runtime::new().unwrap().block_on(async {
-            let data = b("foo,bar,baz\na,b,c\nd,e,f");
-            let mut rdr = AsyncReaderBuilder::new().has_headers(true).create_reader(data);
-            let mut rec = StringRecord::new();
+    fn collect_warnings() {
+        use crate::WarningKind;
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("a", &rec[0]);
-
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("d", &rec[0]);
+        Runtime::new().unwrap().block_on(async {
+            let data = b"a,b\n1,2\n3\n4,\xff\n5,6,7\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .collect_warnings(true)
+                .invalid_utf8(InvalidUtf8Policy::ReplaceLossy)
+                .create_reader(&data[..]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 4);
+            let warnings = rdr.warnings();
+            assert_eq!(warnings.len(), 3);
+            assert_eq!(warnings[0].kind, WarningKind::UnequalLengths { expected_len: 2, len: 1 });
+            assert_eq!(warnings[0].pos.as_ref().unwrap().line(), 3);
+            assert_eq!(warnings[1].kind, WarningKind::ReplacedInvalidUtf8 { field: 1 });
+            assert_eq!(warnings[1].pos.as_ref().unwrap().line(), 4);
+            assert_eq!(warnings[2].kind, WarningKind::UnequalLengths { expected_len: 2, len: 3 });
+            assert_eq!(warnings[2].pos.as_ref().unwrap().line(), 5);
 
-            assert!(!rdr.read_record(&mut rec).await.unwrap());
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .collect_warnings(true)
+                .invalid_utf8(InvalidUtf8Policy::Skip)
+                .create_reader(&data[..]);
+            let records: Vec<StringRecord> =
+                rdr.records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 3);
+            let kinds: Vec<&WarningKind> = rdr.warnings().iter().map(|w| &w.kind).collect();
+            assert_eq!(kinds, vec![
+                &WarningKind::UnequalLengths { expected_len: 2, len: 1 },
+                &WarningKind::SkippedInvalidUtf8 { field: 1 },
+                &WarningKind::UnequalLengths { expected_len: 2, len: 3 },
+            ]);
 
-            {
-                let headers = rdr.byte_headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!(b"foo", &headers[0]);
-                assert_eq!(b"bar", &headers[1]);
-                assert_eq!(b"baz", &headers[2]);
-            }
-            {
-                let headers = rdr.headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!("foo", &headers[0]);
-                assert_eq!("bar", &headers[1]);
-                assert_eq!("baz", &headers[2]);
-            }
+            // Nothing is collected unless asked for.
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .create_reader(&data[..]);
+            let records: Vec<ByteRecord> =
+                rdr.byte_records().map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 4);
+            assert!(rdr.warnings().is_empty());
         });
     }
 
     #[test]
-    fn read_record_headers_invalid_utf8() {
+    fn summarize() {
         Runtime::new().unwrap().block_on(async {
-            let data = &b"foo,b\xFFar,baz\na,b,c\nd,e,f"[..];
-            let mut rdr = AsyncReaderBuilder::new().has_headers(true).create_reader(data);
-            let mut rec = StringRecord::new();
-
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("a", &rec[0]);
+            let data = b("x,y\n1,10.5\n-2,\n3,abc\n4\n");
+            let mut rdr = AsyncReaderBuilder::new().flexible(true).create_reader(data);
+            let summary = rdr.summarize(&[0, 1]).await.unwrap();
+            assert_eq!(summary.len(), 2);
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("d", &rec[0]);
+            assert_eq!(summary[0].column(), 0);
+            assert_eq!(summary[0].count(), 4);
+            assert_eq!(summary[0].null_count(), 0);
+            assert_eq!(summary[0].min(), Some(-2.0));
+            assert_eq!(summary[0].max(), Some(4.0));
+            assert_eq!(summary[0].sum(), 6.0);
+            assert_eq!(summary[0].mean(), Some(1.5));
 
-            assert!(!rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(summary[1].column(), 1);
+            assert_eq!(summary[1].count(), 1);
+            assert_eq!(summary[1].null_count(), 3);
+            assert_eq!(summary[1].min(), Some(10.5));
+            assert_eq!(summary[1].max(), Some(10.5));
+            assert_eq!(summary[1].mean(), Some(10.5));
+            assert!(rdr.is_done());
 
-            // Check that we can read the headers as raw bytes, but that
-            // if we read them as strings, we get an appropriate UTF-8 error.
-            {
-                let headers = rdr.byte_headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!(b"foo", &headers[0]);
-                assert_eq!(b"b\xFFar", &headers[1]);
-                assert_eq!(b"baz", &headers[2]);
-            }
-            match *rdr.headers().await.unwrap_err().kind() {
-                ErrorKind::Utf8 { pos: Some(ref pos), ref err } => {
-                    assert_eq!(pos, &newpos(0, 1, 0));
-                    assert_eq!(err.field(), 1);
-                    assert_eq!(err.valid_up_to(), 1);
+            let mut rdr = AsyncReaderBuilder::new()
+                .flexible(true)
+                .error_on_non_numeric(true)
+                .create_reader(data);
+            let err = rdr.summarize(&[0, 1]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::NonNumeric { ref pos, field, ref value } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 4);
+                    assert_eq!(field, 1);
+                    assert_eq!(value, "abc");
                 }
-                ref err => panic!("match failed, got {:?}", err),
+                ref kind => panic!("unexpected error kind: {:?}", kind),
             }
+
+            let mut rdr = AsyncReaderBuilder::new().create_reader(b("x\n"));
+            let summary = rdr.summarize(&[0]).await.unwrap();
+            assert_eq!(summary[0].count(), 0);
+            assert_eq!(summary[0].mean(), None);
+            assert_eq!(summary[0].min(), None);
         });
     }
 
+    #[cfg(feature = "with_serde")]
     #[test]
-    fn read_record_no_headers_before() {
-        Runtime::new().unwrap().block_on(async {
-            let data = b("foo,bar,baz\na,b,c\nd,e,f");
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
-            let mut rec = StringRecord::new();
+    fn deserialize() {
+        use serde::Deserialize;
 
-            {
-                let headers = rdr.headers().await.unwrap();
-                assert_eq!(3, headers.len());
-                assert_eq!("foo", &headers[0]);
-                assert_eq!("bar", &headers[1]);
-                assert_eq!("baz", &headers[2]);
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            pop: u64,
+            city: String,
+        }
+
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,x\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let rows: Vec<_> = rdr.deserialize::<Row>().collect().await;
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0].as_ref().unwrap(), &Row { pop: 4628910, city: "Boston".to_string() });
+            match *rows[1].as_ref().unwrap_err().kind() {
+                ErrorKind::Deserialize { ref pos, .. } => {
+                    assert_eq!(pos.as_ref().unwrap().line(), 3);
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
             }
+            assert_eq!(rdr.headers().await.unwrap(), vec!["city", "pop"]);
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("foo", &rec[0]);
+            // Without headers, fields are deserialized by position.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader(b("Boston,4628910\n"));
+            let rows: Vec<(String, u64)> =
+                rdr.deserialize().map(Result::unwrap).collect().await;
+            assert_eq!(rows, vec![("Boston".to_string(), 4628910)]);
+        });
+    }
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("a", &rec[0]);
+    #[cfg(feature = "with_serde")]
+    #[test]
+    fn into_deserialize() {
+        use serde::Deserialize;
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("d", &rec[0]);
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            city: String,
+            pop: Option<u64>,
+        }
 
-            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        Runtime::new().unwrap().block_on(async {
+            let rdr = AsyncReaderBuilder::new()
+                .trim(Trim::All)
+                .create_reader(b("city, pop\nBoston, 4628910\nConcord,\n"));
+            let rows: Vec<Row> = rdr.into_deserialize().map(Result::unwrap).collect().await;
+            assert_eq!(rows, vec![
+                Row { city: "Boston".to_string(), pop: Some(4628910) },
+                Row { city: "Concord".to_string(), pop: None },
+            ]);
         });
     }
 
+    #[cfg(feature = "with_serde")]
     #[test]
-    fn read_record_no_headers_after() {
-        Runtime::new().unwrap().block_on(async {
-            let data = b("foo,bar,baz\na,b,c\nd,e,f");
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader(data);
-            let mut rec = StringRecord::new();
-
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("foo", &rec[0]);
+    fn deserialize_borrowed() {
+        use serde::Deserialize;
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("a", &rec[0]);
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row<'a> {
+            city: &'a str,
+            pop: u64,
+        }
 
-            assert!(rdr.read_record(&mut rec).await.unwrap());
-            assert_eq!(3, rec.len());
-            assert_eq!("d", &rec[0]);
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct RawRow<'a> {
+            #[serde(borrow)]
+            city: &'a [u8],
+            pop: u64,
+        }
 
-            assert!(!rdr.read_record(&mut rec).await.unwrap());
+        Runtime::new().unwrap().block_on(async {
+            let data = b("city,pop\nBoston,4628910\nConcord,42695\n");
+            let mut rdr = AsyncReader::from_reader(data);
+            let headers = rdr.headers().await.unwrap().clone();
+            let mut record = StringRecord::new();
+            let mut total = 0;
+            let mut cities = String::new();
+            while rdr.read_record(&mut record).await.unwrap() {
+                let row: Row = record.deserialize(Some(&headers)).unwrap();
+                total += row.pop;
+                cities.push_str(row.city);
+            }
+            assert_eq!(total, 4628910 + 42695);
+            assert_eq!(cities, "BostonConcord");
 
-            let headers = rdr.headers().await.unwrap();
-            assert_eq!(3, headers.len());
-            assert_eq!("foo", &headers[0]);
-            assert_eq!("bar", &headers[1]);
-            assert_eq!("baz", &headers[2]);
+            let mut rdr = AsyncReader::from_reader(data);
+            let headers = rdr.byte_headers().await.unwrap().clone();
+            let mut record = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut record).await.unwrap());
+            let row: RawRow = record.deserialize(Some(&headers)).unwrap();
+            assert_eq!(row, RawRow { city: b"Boston", pop: 4628910 });
         });
     }
 
-
-    // Test that position info is reported correctly in absence of headers.
     #[test]
-    fn positions_no_headers() {
+    fn select_columns() {
         Runtime::new().unwrap().block_on(async {
+            let data = "a,b,c\n1,2,3\n4,5,6\n";
+            let mut rdr = AsyncReaderBuilder::new()
+                .select_columns(Some(&[2, 0, 2]))
+                .track_quoted(true)
+                .create_reader(b(data));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["c", "a", "c"]);
+            let mut rec = ByteRecord::new();
+            assert!(rdr.read_byte_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["3", "1", "3"]);
+            assert_eq!(rec.position().unwrap().line(), 2);
+            assert_eq!(rec.was_quoted(1), Some(false));
+
+            // Without a header row, the first row is a record like any other.
             let mut rdr = AsyncReaderBuilder::new()
                 .has_headers(false)
-                .create_reader("a,b,c\nx,y,z".as_bytes())
-                .into_records();
+                .flexible(true)
+                .select_columns(Some(&[1]))
+                .create_reader(b("a,b\n1\n"));
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["b"], vec![""]]);
 
-            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
-            assert_eq!(pos.byte(), 0);
-            assert_eq!(pos.line(), 1);
-            assert_eq!(pos.record(), 0);
+            // Generated names follow the original column indices.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .generate_headers(Some(crate::HeaderScheme::Numbered))
+                .select_columns(Some(&[1]))
+                .create_reader(b("a,b\n1,2\n"));
+            assert_eq!(rdr.headers().await.unwrap(), vec!["col2"]);
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["b"], vec!["2"]]);
 
-            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
-            assert_eq!(pos.byte(), 6);
-            assert_eq!(pos.line(), 2);
-            assert_eq!(pos.record(), 1);
+            // Lengths are checked against all columns.
+            let mut rdr = AsyncReaderBuilder::new()
+                .select_columns(Some(&[0]))
+                .create_reader(b("a,b\n1\n"));
+            let err = rdr.records().next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::HeaderLengthMismatch { header_len: 2, len: 1, .. } => {}
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
         });
     }
 
-    // Test that position info is reported correctly with headers.
     #[test]
-    fn positions_headers() {
+    fn select_headers() {
         Runtime::new().unwrap().block_on(async {
+            let data = "a,b\n1,2\n\nb,c,a\n3,4,5\n";
             let mut rdr = AsyncReaderBuilder::new()
-                .has_headers(true)
-                .create_reader("a,b,c\nx,y,z".as_bytes())
-                .into_records();
+                .multi_table(true)
+                .select_headers(Some(&["a", "b"]))
+                .create_reader(b(data));
 
-            let pos = rdr.next().await.unwrap().unwrap().position().unwrap().clone();
-            assert_eq!(pos.byte(), 6);
-            assert_eq!(pos.line(), 2);
-            assert_eq!(pos.record(), 1);
-        });
-    }
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["1", "2"]]);
 
-    // Test that reading headers on empty data yields an empty record.
-    #[test]
-    fn headers_on_empty_data() {
-        Runtime::new().unwrap().block_on(async {
-            let mut rdr = AsyncReaderBuilder::new().create_reader("".as_bytes());
-            let r = rdr.byte_headers().await.unwrap();
-            assert_eq!(r.len(), 0);
-        });
-    }
+            // Names are looked up again for every table.
+            let mut table = rdr.next_table().await.unwrap().unwrap();
+            assert_eq!(table.headers().await.unwrap(), vec!["a", "b"]);
+            let records = table.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["5", "3"]]);
 
-    // Test that reading the first record on empty data works.
-    #[test]
-    fn no_headers_on_empty_data() {
-        Runtime::new().unwrap().block_on(async {
-            let mut rdr =
-            AsyncReaderBuilder::new().has_headers(false).create_reader("".as_bytes());
-            assert_eq!(count(rdr.records()).await, 0);
+            let mut rdr = AsyncReaderBuilder::new()
+                .select_headers(Some(&["a", "x"]))
+                .create_reader(b("a,b\n1,2\n"));
+            let err = rdr.records().next().await.unwrap().unwrap_err();
+            match *err.kind() {
+                ErrorKind::UnknownHeader { ref name, ref pos } => {
+                    assert_eq!(name, "x");
+                    assert_eq!(pos.as_ref().unwrap().line(), 1);
+                }
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
         });
     }
 
-    // Test that reading the first record on empty data works, even if
-    // we've tried to read headers before hand.
     #[test]
-    fn no_headers_on_empty_data_after_headers() {
+    fn project() {
         Runtime::new().unwrap().block_on(async {
-            let mut rdr =
-                AsyncReaderBuilder::new().has_headers(false).create_reader("".as_bytes());
-            assert_eq!(rdr.headers().await.unwrap().len(), 0);
-            assert_eq!(count(rdr.records()).await, 0);
+            let data = "a,b,c\n1,2,3\n4,5\n";
+            let mut rdr = AsyncReaderBuilder::new().flexible(true).create_reader(b(data));
+            let records = rdr.project(&["c", "a", "c"]).map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["3", "1", "3"], vec!["", "4", ""]]);
+            assert_eq!(records[1].position().unwrap().line(), 3);
+
+            // Records already read are not yielded again.
+            let mut rdr = AsyncReaderBuilder::new().create_reader(b("a,b\n1,2\n3,4\n"));
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            let records = rdr.project(&["b"]).map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["4"]]);
+
+            // Without a header row, names are looked up in the first row.
+            let mut rdr = AsyncReaderBuilder::new().has_headers(false).create_reader(b("a,b\n1,2\n"));
+            let records = rdr.project(&["b"]).map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["b"], vec!["2"]]);
+
+            let mut rdr = AsyncReaderBuilder::new().create_reader(b("a,b\n1,2\n"));
+            let results = rdr.project(&["a", "x"]).collect::<Vec<_>>().await;
+            assert_eq!(results.len(), 1);
+            match *results[0].as_ref().unwrap_err().kind() {
+                ErrorKind::UnknownHeader { ref name, .. } => assert_eq!(name, "x"),
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
         });
     }
 
     #[test]
-    fn no_infinite_loop_on_io_errors() {
-        struct FailingRead;
-        impl io::AsyncRead for FailingRead {
-            fn poll_read(
-                self: Pin<&mut Self>,
-                _cx: &mut Context,
-                _buf: &mut tokio::io::ReadBuf
-            ) -> Poll<Result<(), io::Error>> {
-                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "Broken reader")))
-            }
-        }
-        impl std::marker::Unpin for FailingRead {}
-    
+    fn skip_records() {
         Runtime::new().unwrap().block_on(async {
-            let mut record_results = AsyncReader::from_reader(FailingRead).into_records();
-            let first_result = record_results.next().await;
-            assert!(
-                matches!(&first_result, Some(Err(e)) if matches!(e.kind(), crate::ErrorKind::Io(_)))
-            );
-            assert!(record_results.next().await.is_none());
+            let data = "a,b,c\nexported\nby,someone\n1,2,3\n4,5,6\n";
+            let mut rdr = AsyncReaderBuilder::new().skip_records(2).create_reader(b(data));
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["1", "2", "3"], vec!["4", "5", "6"]]);
+            assert_eq!(records[0].position().unwrap().line(), 4);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["a", "b", "c"]);
+
+            // Without a header row, the first row is discarded too.
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .skip_records(3)
+                .create_reader(b(data));
+            let records = rdr.records().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(records, vec![vec!["1", "2", "3"], vec!["4", "5", "6"]]);
+
+            // Discarding more records than there are ends the data.
+            let mut rdr = AsyncReaderBuilder::new().skip_records(10).create_reader(b(data));
+            assert!(rdr.records().next().await.is_none());
         });
     }
 }
@@ -0,0 +1,122 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{
+    ArrayRef, ArrowPrimitiveType, BinaryArray, BooleanArray, LargeBinaryArray,
+    LargeStringArray, PrimitiveArray, RecordBatch, RecordBatchOptions, StringArray,
+};
+use arrow_schema::{DataType, Field, SchemaRef};
+
+use crate::byte_record::{ByteRecord, Position};
+use crate::error::{Error, ErrorKind, Result};
+
+/// Build a record batch of `schema` from `records`, whose fields map to the
+/// columns of the schema by position.
+pub(crate) fn record_batch(schema: &SchemaRef, records: &[ByteRecord]) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| column(i, field, records))
+        .collect::<Result<Vec<_>>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(records.len()));
+    RecordBatch::try_new_with_options(schema.clone(), columns, &options)
+        .map_err(|err| arrow_error(None, None, err.to_string()))
+}
+
+/// Build the array of column `i` from `records`.
+fn column(i: usize, field: &Field, records: &[ByteRecord]) -> Result<ArrayRef> {
+    let array: ArrayRef = match *field.data_type() {
+        DataType::Boolean => Arc::new(BooleanArray::from(parsed::<bool>(i, field, records)?)),
+        DataType::Int8 => primitive::<Int8Type>(i, field, records)?,
+        DataType::Int16 => primitive::<Int16Type>(i, field, records)?,
+        DataType::Int32 => primitive::<Int32Type>(i, field, records)?,
+        DataType::Int64 => primitive::<Int64Type>(i, field, records)?,
+        DataType::UInt8 => primitive::<UInt8Type>(i, field, records)?,
+        DataType::UInt16 => primitive::<UInt16Type>(i, field, records)?,
+        DataType::UInt32 => primitive::<UInt32Type>(i, field, records)?,
+        DataType::UInt64 => primitive::<UInt64Type>(i, field, records)?,
+        DataType::Float32 => primitive::<Float32Type>(i, field, records)?,
+        DataType::Float64 => primitive::<Float64Type>(i, field, records)?,
+        DataType::Utf8 => Arc::new(StringArray::from(strings(i, field, records)?)),
+        DataType::LargeUtf8 => Arc::new(LargeStringArray::from(strings(i, field, records)?)),
+        DataType::Binary => Arc::new(BinaryArray::from(bytes(i, field, records))),
+        DataType::LargeBinary => Arc::new(LargeBinaryArray::from(bytes(i, field, records))),
+        ref other => {
+            return Err(arrow_error(
+                None,
+                Some(i),
+                format!("unsupported data type {} of column {:?}", other, field.name()),
+            ))
+        }
+    };
+    Ok(array)
+}
+
+/// The field of column `i` in `record`, or `None` if it is empty or missing
+/// and the column is nullable.
+fn cell<'r>(i: usize, field: &Field, record: &'r ByteRecord) -> Option<&'r [u8]> {
+    let value = record.get(i).unwrap_or(b"");
+    if value.is_empty() && field.is_nullable() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn bytes<'r>(i: usize, field: &Field, records: &'r [ByteRecord]) -> Vec<Option<&'r [u8]>> {
+    records.iter().map(|record| cell(i, field, record)).collect()
+}
+
+fn strings<'r>(i: usize, field: &Field, records: &'r [ByteRecord]) -> Result<Vec<Option<&'r str>>> {
+    records
+        .iter()
+        .map(|record| match cell(i, field, record) {
+            None => Ok(None),
+            Some(value) => std::str::from_utf8(value).map(Some).map_err(|_| {
+                arrow_error(record.position().cloned(), Some(i), "invalid UTF-8".to_string())
+            }),
+        })
+        .collect()
+}
+
+fn parsed<T: FromStr>(i: usize, field: &Field, records: &[ByteRecord]) -> Result<Vec<Option<T>>> {
+    records
+        .iter()
+        .map(|record| match cell(i, field, record) {
+            None => Ok(None),
+            Some(value) => std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .map(Some)
+                .ok_or_else(|| {
+                    arrow_error(
+                        record.position().cloned(),
+                        Some(i),
+                        format!(
+                            "{:?} is not a valid {} value",
+                            String::from_utf8_lossy(value),
+                            field.data_type()
+                        ),
+                    )
+                }),
+        })
+        .collect()
+}
+
+fn primitive<T>(i: usize, field: &Field, records: &[ByteRecord]) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+    T::Native: FromStr,
+{
+    let values = parsed::<T::Native>(i, field, records)?;
+    Ok(Arc::new(values.into_iter().collect::<PrimitiveArray<T>>()))
+}
+
+fn arrow_error(pos: Option<Position>, field: Option<usize>, msg: String) -> Error {
+    Error::new(ErrorKind::Arrow { pos, field: field.map(|i| i as u64), msg })
+}
@@ -0,0 +1,211 @@
+use bytes::{Buf, BytesMut};
+use csv_core::{Reader as CoreReader, ReadRecordResult};
+use tokio_util::codec::Decoder;
+
+use crate::byte_record::{ByteRecord, Position};
+use crate::error::{Error, Result};
+use super::AsyncReaderBuilder;
+
+impl AsyncReaderBuilder {
+    /// Build a [`CsvDecoder`](struct.CsvDecoder.html) parsing CSV data with
+    /// this configuration.
+    ///
+    /// Only the options of the CSV format are used: `delimiter`, `quote`,
+    /// `escape`, `double_quote`, `quoting`, `comment` and `terminator`.
+    ///
+    /// This is only available with the `tokio-codec` feature.
+    pub fn create_decoder(&self) -> CsvDecoder {
+        CsvDecoder::new(self.builder.build())
+    }
+}
+
+/// A [`tokio_util`](https://docs.rs/tokio-util) codec decoding CSV data into
+/// `ByteRecord`s.
+///
+/// This plugs CSV parsing into a `FramedRead`, e.g. to read records from a
+/// socket. Every row is decoded as a record, including a header row, and
+/// records of any length are accepted. Records carry their position in the
+/// data.
+///
+/// This is only available with the `tokio-codec` feature.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use futures::stream::StreamExt;
+/// use tokio_util::codec::FramedRead;
+/// use csv_async::{AsyncReaderBuilder, ByteRecord};
+///
+/// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+/// async fn example() -> Result<(), Box<dyn Error>> {
+///     let data = "city;pop\nBoston;4628910\n";
+///     let decoder = AsyncReaderBuilder::new().delimiter(b';').create_decoder();
+///     let mut frames = FramedRead::new(data.as_bytes(), decoder);
+///     assert_eq!(frames.next().await.unwrap()?, vec!["city", "pop"]);
+///     assert_eq!(frames.next().await.unwrap()?, vec!["Boston", "4628910"]);
+///     assert!(frames.next().await.is_none());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CsvDecoder {
+    core: Box<CoreReader>,
+    /// The fields of the record being decoded.
+    fields: Vec<u8>,
+    /// The end offsets of the fields of the record being decoded.
+    ends: Vec<usize>,
+    fields_len: usize,
+    ends_len: usize,
+    /// The number of bytes consumed so far.
+    byte: u64,
+    /// The number of records decoded so far.
+    record: u64,
+    /// The position of the record being decoded, once it was started.
+    pos: Option<Position>,
+}
+
+impl Default for CsvDecoder {
+    fn default() -> CsvDecoder {
+        AsyncReaderBuilder::new().create_decoder()
+    }
+}
+
+impl CsvDecoder {
+    fn new(core: CoreReader) -> CsvDecoder {
+        CsvDecoder {
+            core: Box::new(core),
+            fields: vec![0; 1024],
+            ends: vec![0; 16],
+            fields_len: 0,
+            ends_len: 0,
+            byte: 0,
+            record: 0,
+            pos: None,
+        }
+    }
+
+    /// Decode the next record from `src`. At the end of the data, `eof` is
+    /// set to finish the last record.
+    fn decode_record(&mut self, src: &mut BytesMut, eof: bool) -> Result<Option<ByteRecord>> {
+        loop {
+            // The core reader takes empty input as the end of the data.
+            if src.is_empty() && !eof {
+                return Ok(None);
+            }
+            if self.pos.is_none() {
+                let mut pos = Position::new();
+                pos.set_byte(self.byte).set_line(self.core.line()).set_record(self.record);
+                self.pos = Some(pos);
+            }
+            let (res, nin, nout, nend) = self.core.read_record(
+                src,
+                &mut self.fields[self.fields_len..],
+                &mut self.ends[self.ends_len..],
+            );
+            src.advance(nin);
+            self.byte += nin as u64;
+            self.fields_len += nout;
+            self.ends_len += nend;
+            match res {
+                ReadRecordResult::InputEmpty => {
+                    if eof && nin == 0 {
+                        return Ok(None);
+                    }
+                }
+                ReadRecordResult::OutputFull => {
+                    let len = self.fields.len();
+                    self.fields.resize(len * 2, 0);
+                }
+                ReadRecordResult::OutputEndsFull => {
+                    let len = self.ends.len();
+                    self.ends.resize(len * 2, 0);
+                }
+                ReadRecordResult::Record => return Ok(Some(self.take_record())),
+                ReadRecordResult::End => return Ok(None),
+            }
+        }
+    }
+
+    /// Build the decoded record and start a new one.
+    fn take_record(&mut self) -> ByteRecord {
+        let mut record = ByteRecord::new();
+        let mut start = 0;
+        for &end in &self.ends[..self.ends_len] {
+            record.push_field(&self.fields[start..end]);
+            start = end;
+        }
+        record.set_position(self.pos.take());
+        self.fields_len = 0;
+        self.ends_len = 0;
+        self.record += 1;
+        record
+    }
+}
+
+impl Decoder for CsvDecoder {
+    type Item = ByteRecord;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<ByteRecord>> {
+        self.decode_record(src, false)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<ByteRecord>> {
+        self.decode_record(src, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use futures::stream::StreamExt;
+    use tokio_util::codec::{Decoder, FramedRead};
+
+    use crate::byte_record::ByteRecord;
+    use super::{AsyncReaderBuilder, CsvDecoder};
+
+    #[test]
+    fn decode_in_pieces() {
+        let mut decoder = CsvDecoder::default();
+        let mut src = BytesMut::from(&b"a,\"b"[..]);
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+        assert!(src.is_empty());
+        src.extend_from_slice(b",c\"\n1,2\n3,");
+        let record = decoder.decode(&mut src).unwrap().unwrap();
+        assert_eq!(record, vec!["a", "b,c"]);
+        assert_eq!(record.position().unwrap().record(), 0);
+        let record = decoder.decode(&mut src).unwrap().unwrap();
+        assert_eq!(record, vec!["1", "2"]);
+        let pos = record.position().unwrap();
+        assert_eq!((pos.byte(), pos.line(), pos.record()), (8, 2, 1));
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+        src.extend_from_slice(b"4");
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+        // The last record has no terminator.
+        assert_eq!(decoder.decode_eof(&mut src).unwrap().unwrap(), vec!["3", "4"]);
+        assert_eq!(decoder.decode_eof(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn framed_read() {
+        tokio1::runtime::Runtime::new().unwrap().block_on(async {
+            let mut data = String::new();
+            for i in 0..1000 {
+                data.push_str(&format!("{},{}\n", i, "x".repeat(i % 50)));
+            }
+            let decoder = AsyncReaderBuilder::new().comment(Some(b'#')).create_decoder();
+            let frames = FramedRead::with_capacity(data.as_bytes(), decoder, 7);
+            let records: Vec<ByteRecord> = frames.map(Result::unwrap).collect().await;
+            assert_eq!(records.len(), 1000);
+            assert_eq!(records[999], vec!["999".to_string(), "x".repeat(49)]);
+
+            let frames = FramedRead::new(&b"# note\na;b\n"[..], AsyncReaderBuilder::new()
+                .comment(Some(b'#'))
+                .delimiter(b';')
+                .create_decoder());
+            let records: Vec<ByteRecord> = frames.map(Result::unwrap).collect().await;
+            assert_eq!(records, vec![vec!["a", "b"]]);
+        });
+    }
+}
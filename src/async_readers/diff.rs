@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream, StreamExt};
+
+cfg_if::cfg_if! {
+if #[cfg(feature = "tokio")] {
+    use tokio::io;
+} else {
+    use futures::io;
+}}
+
+use crate::error::Result;
+use crate::string_record::StringRecord;
+use crate::AsyncReader;
+
+/// A single difference reported by [`diff_records`](fn.diff_records.html).
+///
+/// `index` is the zero based index of a record, counted from the first
+/// record yielded by the readers (i.e. not including the header row when
+/// `has_headers` is enabled).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordDiff {
+    /// Both readers yielded equal records.
+    Same {
+        /// Index of the record.
+        index: u64,
+    },
+    /// The second reader yielded a record the first reader does not have.
+    Added {
+        /// Index of the record.
+        index: u64,
+        /// The added record.
+        record: StringRecord,
+    },
+    /// The first reader yielded a record the second reader does not have.
+    Removed {
+        /// Index of the record.
+        index: u64,
+        /// The removed record.
+        record: StringRecord,
+    },
+    /// A field differs between the records at the same index.
+    ///
+    /// A pair of records differing in several fields is reported as several
+    /// `Changed` events, in field order. A field missing in one of the
+    /// records is reported as `None`.
+    Changed {
+        /// Index of the record.
+        index: u64,
+        /// Index of the field within the record.
+        field: usize,
+        /// The field value in the first reader.
+        old: Option<String>,
+        /// The field value in the second reader.
+        new: Option<String>,
+    },
+}
+
+/// Compare the records of two CSV readers.
+///
+/// Records are paired up by position and compared by their parsed field
+/// values, so differences in quoting do not show up as changes. Records
+/// left over in the longer input are reported as `Added` or `Removed`.
+///
+/// Errors from either reader are passed through. A record read from the
+/// other input at the same position is then reported as `Added` or
+/// `Removed`, so nothing from either input is left out. The comparison goes
+/// on with the next pair of records.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use futures::stream::StreamExt;
+/// use csv_async::{diff_records, AsyncReader, RecordDiff};
+///
+/// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+/// async fn example() -> Result<(), Box<dyn Error>> {
+///     let old = AsyncReader::from_reader("city,pop\nBoston,4628910\n".as_bytes());
+///     let new = AsyncReader::from_reader("city,pop\n\"Boston\",4628911\n".as_bytes());
+///     let mut diff = diff_records(old, new);
+///     assert_eq!(diff.next().await.unwrap()?, RecordDiff::Changed {
+///         index: 0,
+///         field: 1,
+///         old: Some("4628910".to_string()),
+///         new: Some("4628911".to_string()),
+///     });
+///     assert!(diff.next().await.is_none());
+///     Ok(())
+/// }
+/// ```
+pub fn diff_records<'r, R1, R2>(
+    a: AsyncReader<R1>,
+    b: AsyncReader<R2>,
+) -> impl Stream<Item = Result<RecordDiff>> + Unpin + 'r
+where
+    R1: io::AsyncRead + std::marker::Unpin + 'r,
+    R2: io::AsyncRead + std::marker::Unpin + 'r,
+{
+    let state = (a.into_records().fuse(), b.into_records().fuse(), 0u64, VecDeque::new());
+    Box::pin(stream::unfold(state, |(mut a, mut b, mut index, mut pending)| async move {
+        if let Some(item) = pending.pop_front() {
+            return Some((item, (a, b, index, pending)));
+        }
+        let item = match (a.next().await, b.next().await) {
+            (None, None) => return None,
+            (Some(Err(err)), Some(Err(other))) => {
+                pending.push_back(Err(other));
+                Err(err)
+            }
+            (Some(Err(err)), new) => {
+                if let Some(Ok(record)) = new {
+                    pending.push_back(Ok(RecordDiff::Added { index, record }));
+                }
+                Err(err)
+            }
+            (old, Some(Err(err))) => {
+                if let Some(Ok(record)) = old {
+                    pending.push_back(Ok(RecordDiff::Removed { index, record }));
+                }
+                Err(err)
+            }
+            (Some(Ok(record)), None) => Ok(RecordDiff::Removed { index, record }),
+            (None, Some(Ok(record))) => Ok(RecordDiff::Added { index, record }),
+            (Some(Ok(old)), Some(Ok(new))) => {
+                pending.extend(diff_fields(index, &old, &new).into_iter().map(Ok));
+                pending.pop_front().unwrap_or(Ok(RecordDiff::Same { index }))
+            }
+        };
+        index += 1;
+        Some((item, (a, b, index, pending)))
+    }))
+}
+
+/// Returns a `Changed` event for every field that differs between two
+/// records.
+fn diff_fields(index: u64, old: &StringRecord, new: &StringRecord) -> Vec<RecordDiff> {
+    (0..std::cmp::max(old.len(), new.len()))
+        .filter(|&i| old.get(i) != new.get(i))
+        .map(|field| RecordDiff::Changed {
+            index,
+            field,
+            old: old.get(field).map(String::from),
+            new: new.get(field).map(String::from),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+
+    use crate::{AsyncReader, AsyncReaderBuilder};
+
+    use super::{diff_records, RecordDiff};
+
+    #[test]
+    fn diff_one_changed_field() {
+        block_on(async {
+            let old = "a,b,c\n1,2,3\n4,5,6\n7,8,9\n";
+            let new = "a,b,c\n1,2,3\n4,\"five\",6\n7,8,9\n10,11,12\n";
+            let diff = diff_records(
+                AsyncReader::from_reader(old.as_bytes()),
+                AsyncReader::from_reader(new.as_bytes()),
+            );
+            let got: Vec<RecordDiff> =
+                diff.map(|d| d.unwrap()).collect().await;
+            assert_eq!(got, vec![
+                RecordDiff::Same { index: 0 },
+                RecordDiff::Changed {
+                    index: 1,
+                    field: 1,
+                    old: Some("5".to_string()),
+                    new: Some("five".to_string()),
+                },
+                RecordDiff::Same { index: 2 },
+                RecordDiff::Added {
+                    index: 3,
+                    record: vec!["10", "11", "12"].into(),
+                },
+            ]);
+        });
+    }
+
+    #[test]
+    fn diff_unequal_lengths() {
+        block_on(async {
+            let rdr = |data: &'static str| {
+                AsyncReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .create_reader(data.as_bytes())
+            };
+            let diff = diff_records(rdr("x,y,z\nq\n"), rdr("x,Y\n"));
+            let got: Vec<RecordDiff> =
+                diff.map(|d| d.unwrap()).collect().await;
+            assert_eq!(got, vec![
+                RecordDiff::Changed {
+                    index: 0,
+                    field: 1,
+                    old: Some("y".to_string()),
+                    new: Some("Y".to_string()),
+                },
+                RecordDiff::Changed {
+                    index: 0,
+                    field: 2,
+                    old: Some("z".to_string()),
+                    new: None,
+                },
+                RecordDiff::Removed { index: 1, record: vec!["q"].into() },
+            ]);
+        });
+    }
+    #[test]
+    fn diff_ragged_row() {
+        block_on(async {
+            let old = AsyncReader::from_reader("a,b\n1,2\n3\n5,6\n".as_bytes());
+            let new = AsyncReader::from_reader("a,b\n1,2\n3,4\n5,6\n".as_bytes());
+            let got: Vec<_> = diff_records(old, new).collect().await;
+            assert_eq!(got.len(), 4);
+            assert_eq!(got[0].as_ref().unwrap(), &RecordDiff::Same { index: 0 });
+            assert!(got[1].is_err());
+            assert_eq!(
+                got[2].as_ref().unwrap(),
+                &RecordDiff::Added { index: 1, record: vec!["3", "4"].into() }
+            );
+            assert_eq!(got[3].as_ref().unwrap(), &RecordDiff::Same { index: 2 });
+
+            // When both sides fail, both errors are reported.
+            let old = AsyncReader::from_reader("a,b\n1\n".as_bytes());
+            let new = AsyncReader::from_reader("a,b\n2\n".as_bytes());
+            let got: Vec<_> = diff_records(old, new).collect().await;
+            assert_eq!(got.len(), 2);
+            assert!(got.iter().all(Result::is_err));
+        });
+    }
+}
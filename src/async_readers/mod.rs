@@ -1,27 +1,46 @@
-use std::future::Future;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::marker::PhantomData;
 use std::pin::Pin;
 use std::result;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 cfg_if::cfg_if! {
 if #[cfg(feature = "tokio")] {
     use std::io::SeekFrom;
     use tokio::io::{self, AsyncBufRead};
+    #[cfg(feature = "serde_json")]
+    use tokio::io::AsyncWriteExt;
     use tokio_stream::Stream;
 } else {
     use futures::io::{self, AsyncBufRead, AsyncSeekExt};
+    #[cfg(feature = "serde_json")]
+    use futures::io::AsyncWriteExt;
     use futures::stream::Stream;
 }}
     
 use csv_core::{ReaderBuilder as CoreReaderBuilder};
+use futures::{future, ready};
+#[cfg(feature = "timeout")]
+use futures::Future;
+#[cfg(feature = "timeout")]
+use futures_timer::Delay;
+#[cfg(feature = "regex")]
+use regex::Regex;
 use csv_core::{Reader as CoreReader};
 #[cfg(feature = "with_serde")]
 use serde::de::DeserializeOwned;
 
-use crate::{Terminator, Trim};
+use crate::{HeaderScheme, InvalidUtf8Policy, Terminator, Trim};
 use crate::byte_record::{ByteRecord, Position};
-use crate::error::{Error, ErrorKind, Result, Utf8Error};
+use crate::error::{new_utf8_error, Error, ErrorKind, Result, Utf8Error};
+use crate::footer::{Adler32, FooterSpec};
+use crate::record_pool::RecordPool;
 use crate::string_record::StringRecord;
+use self::strict::StrictChecker;
 
 cfg_if::cfg_if! {
 if #[cfg(feature = "tokio")] {
@@ -30,6 +49,13 @@ if #[cfg(feature = "tokio")] {
     pub mod ardr_futures;
 }}
     
+pub mod diff;
+mod strict;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+
 #[cfg(all(feature = "with_serde", not(feature = "tokio")))]
 pub mod ades_futures;
     
@@ -50,8 +76,48 @@ pub struct AsyncReaderBuilder {
     capacity: usize,
     flexible: bool,
     has_headers: bool,
+    generate_headers: Option<HeaderScheme>,
+    skip_records: u64,
     trim: Trim,
+    invalid_utf8: InvalidUtf8Policy,
+    collapse_whitespace: bool,
+    normalize_newlines: bool,
+    field_wrappers: Option<(u8, u8)>,
+    transform_field: Option<FieldTransform>,
+    #[cfg(feature = "base64")]
+    base64_columns: Vec<usize>,
+    #[cfg(feature = "regex")]
+    column_patterns: Vec<(usize, Regex)>,
+    selected_columns: Option<Vec<usize>>,
+    selected_names: Option<Vec<String>>,
+    #[cfg(feature = "timeout")]
+    idle_timeout: Option<Duration>,
     end_on_io_error: bool,
+    follow: bool,
+    resumable: bool,
+    track_timing: bool,
+    collect_warnings: bool,
+    expect_footer: Option<FooterSpec>,
+    max_field_size: Option<usize>,
+    max_record_size: Option<usize>,
+    max_total_bytes: Option<u64>,
+    record_pool: Option<Arc<RecordPool>>,
+    fixed_width: Option<Vec<usize>>,
+    collapse_delimiters: bool,
+    respect_sep_directive: bool,
+    track_quoted: bool,
+    strict: bool,
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+    quoting: bool,
+    comment: Option<u8>,
+    nfa: bool,
+    multi_table: bool,
+    error_on_duplicate_headers: bool,
+    error_on_non_numeric: bool,
+    terminator: Terminator,
     /// The underlying CSV parser builder.
     ///
     /// We explicitly put this on the heap because CoreReaderBuilder embeds an
@@ -66,8 +132,48 @@ impl Default for AsyncReaderBuilder {
             capacity: 8 * (1 << 10),
             flexible: false,
             has_headers: true,
+            generate_headers: None,
+            skip_records: 0,
             trim: Trim::default(),
+            invalid_utf8: InvalidUtf8Policy::default(),
+            collapse_whitespace: false,
+            normalize_newlines: false,
+            field_wrappers: None,
+            transform_field: None,
+            #[cfg(feature = "base64")]
+            base64_columns: Vec::new(),
+            #[cfg(feature = "regex")]
+            column_patterns: Vec::new(),
+            selected_columns: None,
+            selected_names: None,
+            #[cfg(feature = "timeout")]
+            idle_timeout: None,
             end_on_io_error: true,
+            follow: false,
+            resumable: false,
+            track_timing: false,
+            collect_warnings: false,
+            expect_footer: None,
+            max_field_size: None,
+            max_record_size: None,
+            max_total_bytes: None,
+            record_pool: None,
+            fixed_width: None,
+            collapse_delimiters: false,
+            respect_sep_directive: false,
+            track_quoted: false,
+            strict: false,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            double_quote: true,
+            quoting: true,
+            comment: None,
+            nfa: false,
+            multi_table: false,
+            error_on_duplicate_headers: false,
+            error_on_non_numeric: false,
+            terminator: Terminator::default(),
             builder: Box::new(CoreReaderBuilder::default()),
         }
     }
@@ -151,6 +257,178 @@ impl AsyncReaderBuilder {
     /// ```
     pub fn delimiter(&mut self, delimiter: u8) -> &mut AsyncReaderBuilder {
         self.builder.delimiter(delimiter);
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether runs of delimiters are treated as a single delimiter.
+    ///
+    /// This is meant for text aligned with runs of spaces, e.g. the output
+    /// of command line tools, read with a space as the delimiter. When
+    /// enabled, empty fields are dropped from every record, headers
+    /// included, so delimiters at the start or end of a line are ignored
+    /// too, and a line holding only delimiters is skipped like a blank line.
+    ///
+    /// This makes it impossible to read fields that are legitimately empty:
+    /// they are dropped as well, even when quoted, and the fields after them
+    /// shift left. This has no effect on fixed-width data.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city        pop
+    /// Boston      4628910
+    /// Concord       42695
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .delimiter(b' ')
+    ///         .collapse_delimiters(true)
+    ///         .create_reader(data.as_bytes());
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "pop"]);
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Concord", "42695"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn collapse_delimiters(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.collapse_delimiters = yes;
+        self
+    }
+
+    /// Whether a leading `sep=` line sets the delimiter.
+    ///
+    /// Spreadsheet applications like Excel may start CSV data with a line
+    /// like `sep=;`, declaring the delimiter used in the rest of the data.
+    /// When enabled, such a line (the `sep=` prefix, one delimiter byte, and
+    /// a line terminator) at the very start of the data replaces the
+    /// configured delimiter and is skipped, so the header row is read from
+    /// the next line. Data without the line is read with the configured
+    /// delimiter, as usual.
+    ///
+    /// With `multi_table`, every table may start with its own `sep=` line,
+    /// which sets the delimiter for that table only.
+    ///
+    /// This is disabled by default, and has no effect on fixed-width data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "sep=;\r\ncity;pop\r\nBoston;4628910\r\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .respect_sep_directive(true)
+    ///         .create_reader(data.as_bytes());
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "pop"]);
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn respect_sep_directive(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.respect_sep_directive = yes;
+        self
+    }
+
+    /// Whether to record which fields were quoted in the source data.
+    ///
+    /// When enabled, `ByteRecord::was_quoted` tells, for every field of the
+    /// records read, whether it was enclosed in quotes. This tells a quoted
+    /// empty field (`""`) apart from an unquoted one, e.g. to preserve the
+    /// quoting style of data when rewriting it. With `collapse_delimiters`,
+    /// quoted empty fields are dropped like any other empty field.
+    ///
+    /// This is disabled by default, and has no effect on fixed-width data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, StringRecord};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "name,note\nBoston,\"\"\nConcord,\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .track_quoted(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut record = StringRecord::new();
+    ///     assert!(rdr.read_record(&mut record).await?);
+    ///     assert_eq!(record.as_byte_record().was_quoted(1), Some(true));
+    ///     assert!(rdr.read_record(&mut record).await?);
+    ///     assert_eq!(record.as_byte_record().was_quoted(1), Some(false));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn track_quoted(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.track_quoted = yes;
+        self
+    }
+
+    /// Whether delimited data must strictly follow RFC 4180.
+    ///
+    /// By default, this reader is lenient and parses non-compliant data as
+    /// best it can. When strict mode is enabled, records are still parsed
+    /// the same way, but a record violating RFC 4180 in any of the following
+    /// ways is reported as an `ErrorKind::Strict` error, holding the parsed
+    /// record:
+    ///
+    /// * A record is terminated by a bare `\r` or `\n` instead of `\r\n`.
+    ///   This check is skipped if a `terminator` other than the default is
+    ///   set. Line breaks within quoted fields are not checked.
+    /// * A quoted field has spaces or tabs before its opening quote or after
+    ///   its closing quote.
+    /// * A quoted field has a quote that is not doubled, or an unquoted field
+    ///   has a quote.
+    ///
+    /// Like for other errors, reading can go on with the next record. The
+    /// checks use the `delimiter` and `quote` of this builder, and assume
+    /// the RFC 4180 way of escaping quotes by doubling them.
+    ///
+    /// This is disabled by default, and has no effect on fixed-width data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind, StringRecord};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\r\nBoston, \"4628910\"\r\nConcord,42695\r\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .strict(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut record = StringRecord::new();
+    ///
+    ///     let err = rdr.read_record(&mut record).await.unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::Strict { ref reason, .. } => {
+    ///             assert_eq!(reason, "whitespace before a quoted field");
+    ///         }
+    ///         _ => panic!("unexpected error: {}", err),
+    ///     }
+    ///     assert!(rdr.read_record(&mut record).await?);
+    ///     assert_eq!(record, vec!["Concord", "42695"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn strict(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.strict = yes;
         self
     }
     
@@ -208,6 +486,83 @@ impl AsyncReaderBuilder {
         self.has_headers = yes;
         self
     }
+
+    /// Generate names for the headers of CSV data without a header row.
+    ///
+    /// When `has_headers` is disabled and a scheme is given, `headers` and
+    /// `byte_headers` return generated names, one for each field of the first
+    /// record, instead of the first record itself. The first record is still
+    /// read as a record. This makes deserializing records into structs by
+    /// field name possible, with fields named after the scheme.
+    ///
+    /// This has no effect when `has_headers` is enabled. The default is
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, HeaderScheme};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "Boston,United States,4628910\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .has_headers(false)
+    ///         .generate_headers(Some(HeaderScheme::Alphabetic))
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     assert_eq!(rdr.headers().await?, vec!["a", "b", "c"]);
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["Boston", "United States", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn generate_headers(&mut self, scheme: Option<HeaderScheme>) -> &mut AsyncReaderBuilder {
+        self.generate_headers = scheme;
+        self
+    }
+
+    /// Discard the given number of records at the start of the data.
+    ///
+    /// The records are read after the header row, if there is one, and are
+    /// never returned. Their number of fields is not checked, so rows of a
+    /// preamble do not need to match the header row even when the reader
+    /// is not `flexible`. Without a header row, the first row is the first
+    /// record discarded. Records are only discarded at the start of the
+    /// data, so nothing is discarded after a `seek`.
+    ///
+    /// The default is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Exported 2024-01-01
+    /// Boston,United States,4628910
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .skip_records(1)
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(records.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn skip_records(&mut self, n: u64) -> &mut AsyncReaderBuilder {
+        self.skip_records = n;
+        self
+    }
     
     /// Returns information if read file has headers.
     #[deprecated(
@@ -274,16 +629,16 @@ impl AsyncReaderBuilder {
     ///     let mut records = rdr.records();
     ///     match records.next().await {
     ///         Some(Err(err)) => match *err.kind() {
-    ///             ErrorKind::UnequalLengths { expected_len, len, .. } => {
+    ///             ErrorKind::HeaderLengthMismatch { header_len, len, .. } => {
     ///                 // The header row has 3 fields...
-    ///                 assert_eq!(expected_len, 3);
+    ///                 assert_eq!(header_len, 3);
     ///                 // ... but the first row has only 2 fields.
     ///                 assert_eq!(len, 2);
     ///                 Ok(())
     ///             }
     ///             ref wrong => {
     ///                 Err(From::from(format!(
-    ///                     "expected UnequalLengths error but got {:?}",
+    ///                     "expected HeaderLengthMismatch error but got {:?}",
     ///                     wrong)))
     ///             }
     ///         }
@@ -322,195 +677,897 @@ impl AsyncReaderBuilder {
         self
     }
 
-    /// Whether fields are trimmed of leading and trailing whitespace or not.
+    /// Keep waiting for more data at the end of the input, like `tail -f`.
     ///
-    /// By default, no trimming is performed. This method permits one to
-    /// override that behavior and choose one of the following options:
+    /// When enabled, reaching the end of the underlying reader does not end
+    /// the CSV data. Instead, reading returns `Poll::Pending` and tries the
+    /// underlying reader again the next time it is polled, so records
+    /// appended to a growing source show up as they are written. A record
+    /// that is not yet terminated at the end of the input is kept until the
+    /// rest of it arrives.
     ///
-    /// 1. `Trim::Headers` trims only header values.
-    /// 2. `Trim::Fields` trims only non-header or "field" values.
-    /// 3. `Trim::All` trims both header and non-header values.
+    /// A reader in follow mode never terminates unless the underlying reader
+    /// fails. Hitting the end of the input does not wake the task, so the
+    /// underlying reader should answer with `Poll::Pending` and wake the
+    /// task once new data is available. With a source that just reports the
+    /// end again (such as a plain file), the reader is only polled again
+    /// when the task is woken by something else, e.g. an `idle_timeout`
+    /// or a timer the caller selects on.
     ///
-    /// A value is only interpreted as a header value if this CSV reader is
-    /// configured to read a header record (which is the default).
+    /// This is disabled by default.
+    pub fn follow(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.follow = yes;
+        self
+    }
+
+    /// Keep the bytes of the record being parsed, so that parsing can be
+    /// resumed elsewhere.
     ///
-    /// When reading string records, characters meeting the definition of
-    /// Unicode whitespace are trimmed. When reading byte records, characters
-    /// meeting the definition of ASCII whitespace are trimmed. ASCII
-    /// whitespace characters correspond to the set `[\t\n\v\f\r ]`.
+    /// When enabled, `AsyncReader::parser_state` returns everything needed
+    /// to continue parsing the data with another reader, e.g. after the
+    /// connection delivering the data was reestablished in the middle of a
+    /// quoted field. See `create_reader_with_state`.
     ///
-    /// # Example
+    /// This is disabled by default.
+    pub fn resumable(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.resumable = yes;
+        self
+    }
+
+    /// Whether to measure the throughput of parsing or not.
     ///
-    /// This example shows what happens when all values are trimmed.
+    /// When enabled, the reader counts the bytes consumed and the records
+    /// read, and adds up the wall-clock time spent parsing them. Time spent
+    /// waiting for the underlying reader to deliver data is not included,
+    /// so comparing `elapsed` with the total time taken by reading tells
+    /// whether it is bound by I/O or by parsing. The numbers are returned by
+    /// `AsyncReader::timing`.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::{AsyncReaderBuilder, StringRecord, Trim};
+    /// use csv_async::AsyncReaderBuilder;
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city ,   country ,  pop
-    /// Boston,\"
-    ///    United States\",4628910
-    /// Concord,   United States   ,42695
-    /// ";
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\n";
     ///     let mut rdr = AsyncReaderBuilder::new()
-    ///         .trim(Trim::All)
-    ///         .from_reader(data.as_bytes());
-    ///     let records = rdr
-    ///         .records()
-    ///         .map(Result::unwrap)
-    ///         .collect::<Vec<StringRecord>>().await;
-    ///     assert_eq!(records, vec![
-    ///         vec!["Boston", "United States", "4628910"],
-    ///         vec!["Concord", "United States", "42695"],
-    ///     ]);
+    ///         .track_timing(true)
+    ///         .create_reader(data.as_bytes());
+    ///     while let Some(record) = rdr.records().next().await {
+    ///         record?;
+    ///     }
+    ///     let timing = rdr.timing();
+    ///     assert_eq!(timing.bytes, data.len() as u64);
+    ///     assert_eq!(timing.records, 2);
+    ///     println!("parsed in {:?}", timing.elapsed);
     ///     Ok(())
     /// }
     /// ```
-    pub fn trim(&mut self, trim: Trim) -> &mut AsyncReaderBuilder {
-        self.trim = trim;
+    pub fn track_timing(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.track_timing = yes;
         self
     }
 
-    /// The record terminator to use when parsing CSV.
+    /// Whether to collect warnings about non-fatal anomalies in the data.
     ///
-    /// A record terminator can be any single byte. The default is a special
-    /// value, `Terminator::CRLF`, which treats any occurrence of `\r`, `\n`
-    /// or `\r\n` as a single record terminator.
+    /// When enabled, the reader keeps a `Warning` for every record that is
+    /// read successfully but is suspicious, and `AsyncReader::warnings`
+    /// returns them. These are records with a different number of fields
+    /// than the first record, which are only allowed in `flexible` mode, and
+    /// records with invalid UTF-8 that were read as strings anyway, or
+    /// skipped, according to `invalid_utf8`. Warnings are kept for the whole
+    /// input, so this is meant for data of a reasonable size.
     ///
-    /// # Example: `$` as a record terminator
+    /// This is disabled by default.
+    ///
+    /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::{AsyncReaderBuilder, Terminator};
+    /// use csv_async::{AsyncReaderBuilder, WarningKind};
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "city,country,pop$Boston,United States,4628910";
+    ///     let data = "city,pop\nBoston,4628910\nConcord\n";
     ///     let mut rdr = AsyncReaderBuilder::new()
-    ///         .terminator(Terminator::Any(b'$'))
-    ///         .from_reader(data.as_bytes());
-    ///     let mut iter = rdr.records();
-    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
-    ///     assert!(iter.next().await.is_none());
+    ///         .flexible(true)
+    ///         .collect_warnings(true)
+    ///         .create_reader(data.as_bytes());
+    ///     while let Some(record) = rdr.records().next().await {
+    ///         record?;
+    ///     }
+    ///     let warnings = rdr.warnings();
+    ///     assert_eq!(warnings.len(), 1);
+    ///     assert_eq!(warnings[0].pos.as_ref().unwrap().line(), 3);
+    ///     assert_eq!(warnings[0].kind, WarningKind::UnequalLengths { expected_len: 2, len: 1 });
     ///     Ok(())
     /// }
     /// ```
-    pub fn terminator(&mut self, term: Terminator) -> &mut AsyncReaderBuilder {
-        self.builder.terminator(term.to_core());
+    pub fn collect_warnings(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.collect_warnings = yes;
         self
     }
 
-    /// The quote character to use when parsing CSV.
+    /// Expect the last row of the data to be a footer with the given layout,
+    /// as written by `AsyncWriter::write_footer`.
     ///
-    /// The default is `b'"'`.
+    /// The footer row is not returned as a record. Once the end of the data
+    /// is reached, the record count and checksum it declares are compared
+    /// against the data actually read, and an error of kind
+    /// `ErrorKind::FooterMismatch` is returned if they disagree or the footer
+    /// is missing. Since the reader only knows a record is the last one once
+    /// it sees the end of the data, every record is returned only after the
+    /// next one has been read.
     ///
-    /// # Example: single quotes instead of double quotes
+    /// The footer row may have a different number of fields than the other
+    /// records, even if `flexible` is disabled.
+    ///
+    /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::AsyncReaderBuilder;
+    /// use csv_async::{AsyncReaderBuilder, FooterSpec};
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,pop
-    /// Boston,'United States',4628910
-    /// ";
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\nTOTAL,2\n";
     ///     let mut rdr = AsyncReaderBuilder::new()
-    ///         .quote(b'\'')
-    ///         .from_reader(data.as_bytes());
-    ///     let mut iter = rdr.records();
-    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
-    ///     assert!(iter.next().await.is_none());
+    ///         .expect_footer(FooterSpec { label: Some("TOTAL".to_string()), checksum: false })
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Concord", "42695"]);
+    ///     assert!(records.next().await.is_none());
     ///     Ok(())
     /// }
     /// ```
-    pub fn quote(&mut self, quote: u8) -> &mut AsyncReaderBuilder {
-        self.builder.quote(quote);
+    pub fn expect_footer(&mut self, spec: FooterSpec) -> &mut AsyncReaderBuilder {
+        self.expect_footer = Some(spec);
         self
     }
 
-    /// The escape character to use when parsing CSV.
+    /// The largest size of a single field, in bytes.
     ///
-    /// In some variants of CSV, quotes are escaped using a special escape
-    /// character like `\` (instead of escaping quotes by doubling them).
+    /// When a field of delimited data grows beyond this size, reading stops
+    /// with an error of kind `ErrorKind::FieldTooLarge`. The rest of the
+    /// record is discarded without being buffered, so the next read starts
+    /// at the next record. This guards against input where, e.g., a
+    /// quote that is never closed turns the rest of the data into one
+    /// giant field.
     ///
-    /// By default, recognizing these idiosyncratic escapes is disabled.
+    /// This is disabled (`None`) by default.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::AsyncReaderBuilder;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,pop
-    /// Boston,\"The \\\"United\\\" States\",4628910
-    /// ";
+    ///     let data = "city,pop\nBoston,\"4628910\nConcord,42695\n";
     ///     let mut rdr = AsyncReaderBuilder::new()
-    ///         .escape(Some(b'\\'))
-    ///         .from_reader(data.as_bytes());
-    ///     let mut records = rdr.records();
-    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "The \"United\" States", "4628910"]);
+    ///         .max_field_size(Some(16))
+    ///         .create_reader(data.as_bytes());
+    ///     let err = rdr.records().next().await.unwrap().unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::FieldTooLarge { field_index, limit, .. } => {
+    ///             assert_eq!(field_index, 1);
+    ///             assert_eq!(limit, 16);
+    ///         }
+    ///         _ => panic!("expected a field size error"),
+    ///     }
     ///     Ok(())
     /// }
     /// ```
-    pub fn escape(&mut self, escape: Option<u8>) -> &mut AsyncReaderBuilder {
-        self.builder.escape(escape);
+    pub fn max_field_size(&mut self, limit: Option<usize>) -> &mut AsyncReaderBuilder {
+        self.max_field_size = limit;
         self
     }
 
-    /// Enable double quote escapes.
+    /// The largest size of a single record, in bytes.
     ///
-    /// This is enabled by default, but it may be disabled. When disabled,
-    /// doubled quotes are not interpreted as escapes.
+    /// The size of a record is the sum of the sizes of its fields, without
+    /// delimiters or quotes. When a record of delimited data grows beyond
+    /// this size, reading stops with an error of kind
+    /// `ErrorKind::RecordTooLarge`. The rest of the record is discarded
+    /// without being buffered, so the next read starts at the next record.
+    /// Unlike `max_field_size`, this also bounds records made of many small
+    /// fields.
+    ///
+    /// This is disabled (`None`) by default.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::AsyncReaderBuilder;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
-    ///     let data = "\
-    /// city,country,pop
-    /// Boston,\"The \"\"United\"\" States\",4628910
-    /// ";
+    ///     let data = "city,pop\nBoston,\"4628910\nConcord,42695\n";
     ///     let mut rdr = AsyncReaderBuilder::new()
-    ///         .double_quote(false)
-    ///         .from_reader(data.as_bytes());
-    ///     let mut records = rdr.records();
-    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "The \"United\"\" States\"", "4628910"]);
+    ///         .max_record_size(Some(16))
+    ///         .create_reader(data.as_bytes());
+    ///     let err = rdr.records().next().await.unwrap().unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::RecordTooLarge { limit, .. } => assert_eq!(limit, 16),
+    ///         _ => panic!("expected a record size error"),
+    ///     }
     ///     Ok(())
     /// }
     /// ```
-    pub fn double_quote(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
-        self.builder.double_quote(yes);
+    pub fn max_record_size(&mut self, limit: Option<usize>) -> &mut AsyncReaderBuilder {
+        self.max_record_size = limit;
         self
     }
 
-    /// Enable or disable quoting.
+    /// The largest number of bytes read in total.
     ///
-    /// This is enabled by default, but it may be disabled. When disabled,
-    /// quotes are not treated specially.
+    /// Once the bytes consumed from the underlying reader, as counted by
+    /// `Position::byte`, exceed this limit, reading stops with an error of
+    /// kind `ErrorKind::InputTooLarge`, and every later read fails the same
+    /// way. Unlike `max_field_size`, this bounds the work done on an input
+    /// regardless of its structure.
+    ///
+    /// This is disabled (`None`) by default.
     ///
     /// # Example
     ///
     /// ```
     /// use std::error::Error;
     /// use futures::stream::StreamExt;
-    /// use csv_async::AsyncReaderBuilder;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .max_total_bytes(Some(30))
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     let err = records.next().await.unwrap().unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::InputTooLarge { limit } => assert_eq!(limit, 30),
+    ///         _ => panic!("expected an input size error"),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn max_total_bytes(&mut self, limit: Option<u64>) -> &mut AsyncReaderBuilder {
+        self.max_total_bytes = limit;
+        self
+    }
+
+    /// Take the records yielded by record streams from `pool`.
+    ///
+    /// Without a pool, streams like `records` or `byte_records` allocate a
+    /// new record for every record they yield. With a pool, they take an
+    /// empty record from it instead, so records handed back to the pool
+    /// with `RecordPool::put` once they are processed are reused. A pool
+    /// may be shared by any number of readers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use std::sync::Arc;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, RecordPool};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let pool = Arc::new(RecordPool::new(16));
+    ///     for data in &["city\nBoston\n", "city\nConcord\n"] {
+    ///         let mut rdr = AsyncReaderBuilder::new()
+    ///             .record_pool(pool.clone())
+    ///             .create_reader(data.as_bytes());
+    ///         let mut records = rdr.byte_records();
+    ///         while let Some(record) = records.next().await {
+    ///             let record = record?;
+    ///             println!("{:?}", record);
+    ///             pool.put(record);
+    ///         }
+    ///     }
+    ///     assert!(pool.reused() > 0);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn record_pool(&mut self, pool: Arc<RecordPool>) -> &mut AsyncReaderBuilder {
+        self.record_pool = Some(pool);
+        self
+    }
+
+    /// Whether fields are trimmed of leading and trailing whitespace or not.
+    ///
+    /// By default, no trimming is performed. This method permits one to
+    /// override that behavior and choose one of the following options:
+    ///
+    /// 1. `Trim::Headers` trims only header values.
+    /// 2. `Trim::Fields` trims only non-header or "field" values.
+    /// 3. `Trim::All` trims both header and non-header values.
+    ///
+    /// A value is only interpreted as a header value if this CSV reader is
+    /// configured to read a header record (which is the default).
+    ///
+    /// When reading string records, characters meeting the definition of
+    /// Unicode whitespace are trimmed. When reading byte records, characters
+    /// meeting the definition of ASCII whitespace are trimmed. ASCII
+    /// whitespace characters correspond to the set `[\t\n\v\f\r ]`.
+    ///
+    /// # Example
+    ///
+    /// This example shows what happens when all values are trimmed.
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, StringRecord, Trim};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city ,   country ,  pop
+    /// Boston,\"
+    ///    United States\",4628910
+    /// Concord,   United States   ,42695
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .trim(Trim::All)
+    ///         .from_reader(data.as_bytes());
+    ///     let records = rdr
+    ///         .records()
+    ///         .map(Result::unwrap)
+    ///         .collect::<Vec<StringRecord>>().await;
+    ///     assert_eq!(records, vec![
+    ///         vec!["Boston", "United States", "4628910"],
+    ///         vec!["Concord", "United States", "42695"],
+    ///     ]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn trim(&mut self, trim: Trim) -> &mut AsyncReaderBuilder {
+        self.trim = trim;
+        self
+    }
+
+    /// How records that are not valid UTF-8 are handled when reading them
+    /// as strings.
+    ///
+    /// By default, reading such a record with `read_record`, or as part of
+    /// a stream of `StringRecord`s, results in a `Utf8` error. Use
+    /// `InvalidUtf8Policy::ReplaceLossy` to replace invalid sequences with
+    /// U+FFFD instead, like `StringRecord::from_byte_record_lossy` does, or
+    /// `InvalidUtf8Policy::Skip` to drop such records silently.
+    ///
+    /// This has no effect on byte records, nor on the header row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, InvalidUtf8Policy, StringRecord};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = &b"city,pop\nBoston,4628910\nConc\xffrd,42695\n"[..];
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .invalid_utf8(InvalidUtf8Policy::ReplaceLossy)
+    ///         .create_reader(data);
+    ///     let mut record = StringRecord::new();
+    ///
+    ///     assert!(rdr.read_record(&mut record).await?);
+    ///     assert!(rdr.read_record(&mut record).await?);
+    ///     assert_eq!(record, vec!["Conc\u{FFFD}rd", "42695"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn invalid_utf8(&mut self, policy: InvalidUtf8Policy) -> &mut AsyncReaderBuilder {
+        self.invalid_utf8 = policy;
+        self
+    }
+
+    /// Whether runs of whitespace within fields should be collapsed into
+    /// a single space.
+    ///
+    /// This is a normalization distinct from trimming: whitespace at the
+    /// start or end of a field is collapsed too, but not removed. Combine it
+    /// with [`trim`](struct.AsyncReaderBuilder.html#method.trim) to get both.
+    /// Headers are not affected.
+    ///
+    /// Whitespace is defined as ASCII whitespace, i.e. bytes in the class
+    /// `[\t\n\v\f\r ]`.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, Trim};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country
+    /// New   York,  United\tStates
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .collapse_whitespace(true)
+    ///         .trim(Trim::Fields)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["New York", "United States"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn collapse_whitespace(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.collapse_whitespace = yes;
+        self
+    }
+
+    /// Whether line endings within fields are normalized to `\n` or not.
+    ///
+    /// When enabled, every `\r\n` and lone `\r` within a field, which can
+    /// only occur in quoted fields, is replaced by `\n`. This is applied to
+    /// every field after parsing, like trimming, so record terminators are
+    /// not affected. Headers are not affected either.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,address\r\nBoston,\"1 Main St\r\nSuite 2\"\r\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .normalize_newlines(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "1 Main St\nSuite 2"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn normalize_newlines(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.normalize_newlines = yes;
+        self
+    }
+
+    /// Strip a pair of wrapper bytes surrounding fields.
+    ///
+    /// When set to `Some((open, close))`, a field that starts with `open`
+    /// and ends with `close` has both removed as it is parsed, e.g. `[a]`
+    /// becomes `a` for `Some((b'[', b']'))`. Other fields are left as is.
+    /// Unlike quotes, wrappers have no escaping: only the first and last
+    /// byte of a field are looked at, and delimiters within wrappers still
+    /// separate fields unless the field is also quoted. Wrappers are
+    /// stripped after quotes are removed, and from the header row too.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "[city],[pop]\n[Boston],[4628910]\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .field_wrappers(Some((b'[', b']')))
+    ///         .create_reader(data.as_bytes());
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "pop"]);
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn field_wrappers(&mut self, wrappers: Option<(u8, u8)>) -> &mut AsyncReaderBuilder {
+        self.field_wrappers = wrappers;
+        self
+    }
+
+    /// Set a function that transforms every field as records are read.
+    ///
+    /// The function is called with the index of the column and the bytes of
+    /// the field, and its result replaces the field. It runs after trimming
+    /// and whitespace collapsing, and before UTF-8 validation when reading
+    /// string records, so it may also repair invalid UTF-8. The header row
+    /// is not transformed.
+    ///
+    /// All readers created by this builder share the same function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country
+    /// BOSTON,United States
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .transform_field(Box::new(|i, field: &[u8]| match i {
+    ///             0 => Cow::Owned(field.to_ascii_lowercase()),
+    ///             _ => Cow::Borrowed(field),
+    ///         }))
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["boston", "United States"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn transform_field(
+        &mut self,
+        transform: Box<TransformFn>,
+    ) -> &mut AsyncReaderBuilder {
+        self.transform_field = Some(FieldTransform(Arc::new(Mutex::new(transform))));
+        self
+    }
+
+    /// Decode the fields in the given columns from base64 as records are
+    /// read.
+    ///
+    /// Columns are identified by their zero based index. The decoded bytes
+    /// replace the field, so binary data is best read with `byte_records`
+    /// or `read_byte_record`: reading a binary column as a `StringRecord`
+    /// fails with a UTF-8 error unless the decoded bytes happen to be valid
+    /// UTF-8. A field that is not valid base64 (standard alphabet, with
+    /// padding) results in a `Base64` error naming the record and field. The
+    /// header row is not decoded.
+    ///
+    /// This is only available with the `base64` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "name,blob\nfoo,3q2+7w==\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .base64_columns(&[1])
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let record = rdr.byte_records().next().await.unwrap()?;
+    ///     assert_eq!(&record[1], &[0xde, 0xad, 0xbe, 0xef][..]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn base64_columns(&mut self, columns: &[usize]) -> &mut AsyncReaderBuilder {
+        self.base64_columns = columns.to_vec();
+        self
+    }
+
+    /// Check the fields in the given columns against a pattern as records
+    /// are read.
+    ///
+    /// Each pair holds the zero based index of a column and the pattern its
+    /// fields must match. A field that does not match, or that is not valid
+    /// UTF-8, results in a `PatternMismatch` error naming the record, the
+    /// field and its value. Records too short to have a listed column are
+    /// not checked for it. The header row is not checked.
+    ///
+    /// Patterns match anywhere within a field, so they should be anchored
+    /// with `^` and `$` to match whole fields. Fields are checked after all
+    /// other per-field options, like trimming, are applied.
+    ///
+    /// This is only available with the `regex` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
+    /// use regex::Regex;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "id,zip\n1,02101\n2,2101\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .column_patterns(vec![(1, Regex::new(r"^[0-9]{5}$")?)])
+    ///         .create_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["1", "02101"]);
+    ///     let err = records.next().await.unwrap().unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::PatternMismatch { field_index, ref value, .. } => {
+    ///             assert_eq!(field_index, 1);
+    ///             assert_eq!(value, "2101");
+    ///         }
+    ///         _ => panic!("unexpected error: {}", err),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn column_patterns(&mut self, patterns: Vec<(usize, Regex)>) -> &mut AsyncReaderBuilder {
+        self.column_patterns = patterns;
+        self
+    }
+
+    /// Keep only the given columns of every record, in the given order.
+    ///
+    /// Columns are identified by their zero based index. The header row is
+    /// narrowed down the same way, and so are all options referring to
+    /// columns by index, like `base64_columns`: their indices refer to the
+    /// selected columns. Records too short to have a selected column get an
+    /// empty field in its place. The length of records is checked before
+    /// columns are selected, so a reader that is not `flexible` still
+    /// reports records with a different number of fields.
+    ///
+    /// This replaces any selection made with `select_headers`. Passing
+    /// `None` keeps all columns, which is the default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,country,pop\nBoston,United States,4628910\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .select_columns(Some(&[2, 0]))
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     assert_eq!(rdr.headers().await?, vec!["pop", "city"]);
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["4628910", "Boston"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn select_columns(&mut self, columns: Option<&[usize]>) -> &mut AsyncReaderBuilder {
+        self.selected_columns = columns.map(<[usize]>::to_vec);
+        self.selected_names = None;
+        self
+    }
+
+    /// Keep only the columns with the given header names of every record,
+    /// in the given order.
+    ///
+    /// Names are looked up in the first row of the data once it is read,
+    /// and then work like `select_columns` with the indices of their
+    /// columns. A name that is not in the first row results in an
+    /// `UnknownHeader` error. In `multi_table` mode, names are looked up in
+    /// the first row of every table.
+    ///
+    /// This replaces any selection made with `select_columns`. Passing
+    /// `None` keeps all columns, which is the default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,country,pop\nBoston,United States,4628910\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .select_headers(Some(&["pop", "city"]))
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["4628910", "Boston"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn select_headers(&mut self, names: Option<&[&str]>) -> &mut AsyncReaderBuilder {
+        self.selected_names = names.map(|names| names.iter().map(|&name| name.to_string()).collect());
+        self.selected_columns = None;
+        self
+    }
+
+    /// Give up waiting for the underlying reader after the given time
+    /// without any data.
+    ///
+    /// When set, every wait for more input is raced against a timer. If no
+    /// bytes arrive before it fires, reading results in an `IdleTimeout`
+    /// error, even when a record is only partially read. The partial record
+    /// is kept, so calling the reader again resumes where it stopped.
+    ///
+    /// This is disabled (`None`) by default and is only available with the
+    /// `timeout` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use std::time::Duration;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,country\nBoston,United States\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .idle_timeout(Some(Duration::from_secs(30)))
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let record = rdr.records().next().await.unwrap()?;
+    ///     assert_eq!(record, vec!["Boston", "United States"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "timeout")]
+    pub fn idle_timeout(&mut self, timeout: Option<Duration>) -> &mut AsyncReaderBuilder {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// The record terminator to use when parsing CSV.
+    ///
+    /// A record terminator can be any single byte. The default is a special
+    /// value, `Terminator::CRLF`, which treats any occurrence of `\r`, `\n`
+    /// or `\r\n` as a single record terminator.
+    ///
+    /// # Example: `$` as a record terminator
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, Terminator};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,country,pop$Boston,United States,4628910";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .terminator(Terminator::Any(b'$'))
+    ///         .from_reader(data.as_bytes());
+    ///     let mut iter = rdr.records();
+    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(iter.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn terminator(&mut self, term: Terminator) -> &mut AsyncReaderBuilder {
+        self.builder.terminator(term.to_core());
+        self.terminator = term;
+        self
+    }
+
+    /// The quote character to use when parsing CSV.
+    ///
+    /// The default is `b'"'`.
+    ///
+    /// # Example: single quotes instead of double quotes
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,'United States',4628910
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .quote(b'\'')
+    ///         .from_reader(data.as_bytes());
+    ///     let mut iter = rdr.records();
+    ///     assert_eq!(iter.next().await.unwrap()?, vec!["Boston", "United States", "4628910"]);
+    ///     assert!(iter.next().await.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn quote(&mut self, quote: u8) -> &mut AsyncReaderBuilder {
+        self.builder.quote(quote);
+        self.quote = quote;
+        self
+    }
+
+    /// The escape character to use when parsing CSV.
+    ///
+    /// In some variants of CSV, quotes are escaped using a special escape
+    /// character like `\` (instead of escaping quotes by doubling them).
+    ///
+    /// By default, recognizing these idiosyncratic escapes is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,\"The \\\"United\\\" States\",4628910
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .escape(Some(b'\\'))
+    ///         .from_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "The \"United\" States", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut AsyncReaderBuilder {
+        self.builder.escape(escape);
+        self.escape = escape;
+        self
+    }
+
+    /// Enable double quote escapes.
+    ///
+    /// This is enabled by default, but it may be disabled. When disabled,
+    /// doubled quotes are not interpreted as escapes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,country,pop
+    /// Boston,\"The \"\"United\"\" States\",4628910
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .double_quote(false)
+    ///         .from_reader(data.as_bytes());
+    ///     let mut records = rdr.records();
+    ///     assert_eq!(records.next().await.unwrap()?, vec!["Boston", "The \"United\"\" States\"", "4628910"]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn double_quote(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.builder.double_quote(yes);
+        self.double_quote = yes;
+        self
+    }
+
+    /// Enable or disable quoting.
+    ///
+    /// This is enabled by default, but it may be disabled. When disabled,
+    /// quotes are not treated specially.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
     ///
     /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
     /// async fn example() -> Result<(), Box<dyn Error>> {
@@ -528,6 +1585,7 @@ impl AsyncReaderBuilder {
     /// ```
     pub fn quoting(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
         self.builder.quoting(yes);
+        self.quoting = yes;
         self
     }
 
@@ -563,6 +1621,7 @@ impl AsyncReaderBuilder {
     /// ```
     pub fn comment(&mut self, comment: Option<u8>) -> &mut AsyncReaderBuilder {
         self.builder.comment(comment);
+        self.comment = comment;
         self
     }
 
@@ -594,6 +1653,180 @@ impl AsyncReaderBuilder {
     /// ```
     pub fn ascii(&mut self) -> &mut AsyncReaderBuilder {
         self.builder.ascii();
+        self.delimiter = b'\x1F';
+        self.terminator = Terminator::Any(b'\x1E');
+        self
+    }
+
+    /// Parse fixed-width data instead of delimited data.
+    ///
+    /// Each line of input is split into fields of the given byte widths.
+    /// Lines are terminated by `\n` (a preceding `\r` is dropped) and empty
+    /// lines are skipped. Bytes past the sum of all widths are ignored, and
+    /// fields past the end of a short line are empty.
+    ///
+    /// In this mode the delimiter, quote, escape, comment and terminator
+    /// settings are not used. In particular quoting is disabled, so quote
+    /// characters are kept as part of the field data. Trimming and the
+    /// `flexible` setting still apply.
+    ///
+    /// Passing `None` switches back to delimited parsing (the default).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::{AsyncReaderBuilder, StringRecord, Trim};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city      pop
+    /// Boston    4628910
+    /// Concord   42695
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .fixed_width(Some(&[10, 7]))
+    ///         .trim(Trim::All)
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     assert_eq!(rdr.headers().await?, vec!["city", "pop"]);
+    ///     let records = rdr
+    ///         .records()
+    ///         .map(Result::unwrap)
+    ///         .collect::<Vec<StringRecord>>().await;
+    ///     assert_eq!(records, vec![
+    ///         vec!["Boston", "4628910"],
+    ///         vec!["Concord", "42695"],
+    ///     ]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn fixed_width(&mut self, widths: Option<&[usize]>) -> &mut AsyncReaderBuilder {
+        self.fixed_width = widths.map(|w| w.to_vec());
+        self
+    }
+
+    /// Enable or disable parsing of multi-table documents.
+    ///
+    /// When enabled, the input is treated as several independent CSV tables,
+    /// each with its own header row, separated by blank lines. Reading
+    /// records stops at the end of each table, and the next table is
+    /// started with `next_table`. Blank lines are otherwise skipped, as
+    /// usual. Fixed-width parsing does not support multiple tables.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::stream::StreamExt;
+    /// use csv_async::AsyncReaderBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\
+    /// city,pop
+    /// Boston,4628910
+    ///
+    /// name,age
+    /// Alice,30
+    /// ";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .multi_table(true)
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let mut table = rdr.next_table().await?.unwrap();
+    ///     assert_eq!(table.headers().await?, vec!["city", "pop"]);
+    ///     assert_eq!(table.records().next().await.unwrap()?, vec!["Boston", "4628910"]);
+    ///
+    ///     let mut table = rdr.next_table().await?.unwrap();
+    ///     assert_eq!(table.headers().await?, vec!["name", "age"]);
+    ///     assert_eq!(table.records().next().await.unwrap()?, vec!["Alice", "30"]);
+    ///
+    ///     assert!(rdr.next_table().await?.is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn multi_table(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.multi_table = yes;
+        self
+    }
+
+    /// Whether to report an error when the header row contains the same
+    /// name more than once.
+    ///
+    /// When enabled, `headers`, `byte_headers` and deserializing records
+    /// return a `DuplicateHeader` error naming the first repeated header.
+    /// Otherwise (the default), later columns silently shadow earlier ones
+    /// with the same name when deserializing by name. Duplicates can also
+    /// be inspected with `check_duplicate_headers`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "id,name,id\n1,foo,2\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .error_on_duplicate_headers(true)
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let err = rdr.headers().await.unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::DuplicateHeader { ref name, ref fields, .. } => {
+    ///             assert_eq!(name, "id");
+    ///             assert_eq!(fields, &[0, 2]);
+    ///         }
+    ///         ref wrong => panic!("expected DuplicateHeader but got {:?}", wrong),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn error_on_duplicate_headers(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.error_on_duplicate_headers = yes;
+        self
+    }
+
+    /// Whether `summarize` fails on values that are not numbers.
+    ///
+    /// This is disabled by default, in which case such values are counted
+    /// as nulls of their column. When enabled, `summarize` returns an error
+    /// of kind `ErrorKind::NonNumeric` instead. Empty fields are always
+    /// counted as nulls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, ErrorKind};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,n/a\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .error_on_non_numeric(true)
+    ///         .create_reader(data.as_bytes());
+    ///
+    ///     let err = rdr.summarize(&[1]).await.unwrap_err();
+    ///     match *err.kind() {
+    ///         ErrorKind::NonNumeric { ref pos, field, ref value } => {
+    ///             assert_eq!(pos.as_ref().unwrap().line(), 3);
+    ///             assert_eq!(field, 1);
+    ///             assert_eq!(value, "n/a");
+    ///         }
+    ///         ref wrong => panic!("expected NonNumeric but got {:?}", wrong),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn error_on_non_numeric(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
+        self.error_on_non_numeric = yes;
         self
     }
     
@@ -620,10 +1853,448 @@ impl AsyncReaderBuilder {
     #[doc(hidden)]
     pub fn nfa(&mut self, yes: bool) -> &mut AsyncReaderBuilder {
         self.builder.nfa(yes);
+        self.nfa = yes;
         self
     }
 }
 
+/// The parser settings of a builder, kept to build another parser once the
+/// delimiter declared by the data is known.
+#[derive(Clone, Debug)]
+struct CoreSettings {
+    /// The configured delimiter, used when the data declares none.
+    delimiter: u8,
+    terminator: Terminator,
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+    quoting: bool,
+    comment: Option<u8>,
+    nfa: bool,
+}
+
+impl CoreSettings {
+    fn new(builder: &AsyncReaderBuilder) -> CoreSettings {
+        CoreSettings {
+            delimiter: builder.delimiter,
+            terminator: builder.terminator,
+            quote: builder.quote,
+            escape: builder.escape,
+            double_quote: builder.double_quote,
+            quoting: builder.quoting,
+            comment: builder.comment,
+            nfa: builder.nfa,
+        }
+    }
+
+    /// Build a parser with these settings and the given delimiter.
+    fn build(&self, delimiter: u8) -> CoreReader {
+        CoreReaderBuilder::new()
+            .delimiter(delimiter)
+            .terminator(self.terminator.to_core())
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.double_quote)
+            .quoting(self.quoting)
+            .comment(self.comment)
+            .nfa(self.nfa)
+            .build()
+    }
+}
+
+/// Poll the underlying reader for more data. Once the reader has had no data
+/// ready for longer than `idle_timeout`, an `IdleTimeout` error is returned.
+/// In follow mode, the end of the input counts as no data being ready.
+fn poll_fill_buf<'b, R>(
+    rdr: &'b mut io::BufReader<R>,
+    state: &mut ReaderState,
+    cx: &mut Context,
+) -> Poll<Result<&'b [u8]>>
+where
+    R: io::AsyncRead + std::marker::Unpin,
+{
+    match Pin::new(rdr).poll_fill_buf(cx) {
+        Poll::Ready(Ok(buf)) if !buf.is_empty() || !state.follow => {
+            #[cfg(feature = "timeout")]
+            {
+                state.idle_timer = None;
+            }
+            return Poll::Ready(Ok(buf));
+        }
+        // Try again on the next poll, as more data may be appended. The task
+        // is not woken here, which would poll a source that keeps reporting
+        // the end of the input in a busy loop.
+        Poll::Ready(Ok(_)) => {}
+        Poll::Ready(Err(err)) => {
+            state.eof = ReaderEofState::IOError;
+            return Poll::Ready(Err(err.into()));
+        }
+        Poll::Pending => {}
+    }
+    #[cfg(feature = "timeout")]
+    {
+        if let Some(timeout) = state.idle_timeout {
+            let timer = state.idle_timer.get_or_insert_with(|| Delay::new(timeout));
+            if Pin::new(timer).poll(cx).is_ready() {
+                state.idle_timer = None;
+                return Poll::Ready(Err(Error::new(ErrorKind::IdleTimeout)));
+            }
+        }
+    }
+    Poll::Pending
+}
+
+/// The type of a column's values, as inferred by `AsyncReader::infer_schema`.
+///
+/// Variants are ordered from the narrowest to the widest type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColumnType {
+    /// Every value is `true` or `false`.
+    Bool,
+    /// Every value parses as an `i64`.
+    Int,
+    /// Every value parses as an `f64`.
+    Float,
+    /// Any other values.
+    Text,
+}
+
+/// The inferred type of a column, as returned by `AsyncReader::infer_schema`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InferredType {
+    column_type: ColumnType,
+    nullable: bool,
+}
+
+impl InferredType {
+    /// Create a new inferred type.
+    pub fn new(column_type: ColumnType, nullable: bool) -> InferredType {
+        InferredType { column_type, nullable }
+    }
+
+    /// The narrowest type fitting all non-empty values of the column.
+    pub fn column_type(&self) -> ColumnType {
+        self.column_type
+    }
+
+    /// Whether the column had empty or missing values.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+}
+
+/// The type of a column inferred so far.
+#[derive(Clone, Debug, Default)]
+struct ColumnInference {
+    column_type: Option<ColumnType>,
+    nullable: bool,
+}
+
+impl ColumnInference {
+    fn add(&mut self, field: &[u8]) {
+        if field.is_empty() {
+            self.nullable = true;
+            return;
+        }
+        let ty = match std::str::from_utf8(field) {
+            Ok("true") | Ok("false") => ColumnType::Bool,
+            Ok(s) if s.parse::<i64>().is_ok() => ColumnType::Int,
+            Ok(s) if s.parse::<f64>().is_ok() => ColumnType::Float,
+            _ => ColumnType::Text,
+        };
+        self.column_type = Some(match self.column_type {
+            None => ty,
+            Some(cur) if cur == ty => ty,
+            // Integers are also floats, but booleans are neither.
+            Some(ColumnType::Int) | Some(ColumnType::Float)
+                if ty == ColumnType::Int || ty == ColumnType::Float =>
+            {
+                ColumnType::Float
+            }
+            Some(_) => ColumnType::Text,
+        });
+    }
+
+    fn finish(self) -> InferredType {
+        match self.column_type {
+            Some(ty) => InferredType::new(ty, self.nullable),
+            None => InferredType::new(ColumnType::Text, true),
+        }
+    }
+}
+
+/// Add the fields of a record to the inferred column types. Columns missing
+/// from the record become nullable.
+fn infer_record(columns: &mut Vec<ColumnInference>, record: &ByteRecord) {
+    if columns.len() < record.len() {
+        columns.resize(record.len(), ColumnInference::default());
+    }
+    for (i, column) in columns.iter_mut().enumerate() {
+        column.add(record.get(i).unwrap_or(b""));
+    }
+}
+
+/// Statistics about a numeric column, as returned by
+/// `AsyncReader::summarize`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColumnSummary {
+    column: usize,
+    count: u64,
+    nulls: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    sum: f64,
+}
+
+impl ColumnSummary {
+    fn new(column: usize) -> ColumnSummary {
+        ColumnSummary { column, count: 0, nulls: 0, min: None, max: None, sum: 0.0 }
+    }
+
+    /// Add a field of the column. Returns false if it is not a number.
+    fn add(&mut self, field: &[u8]) -> bool {
+        if field.is_empty() {
+            self.nulls += 1;
+            return true;
+        }
+        let value = match std::str::from_utf8(field).ok().and_then(|s| s.parse::<f64>().ok()) {
+            Some(value) => value,
+            None => {
+                self.nulls += 1;
+                return false;
+            }
+        };
+        self.count += 1;
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        true
+    }
+
+    /// The index of the summarized column.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The number of numeric values in the column.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The number of empty, missing or non-numeric values in the column.
+    pub fn null_count(&self) -> u64 {
+        self.nulls
+    }
+
+    /// The smallest value of the column, if it has any numeric values.
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// The largest value of the column, if it has any numeric values.
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// The sum of all numeric values of the column.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// The mean of all numeric values of the column, if it has any.
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f64)
+        }
+    }
+}
+
+/// The parsing throughput of a CSV reader, as returned by
+/// `AsyncReader::timing`.
+///
+/// All numbers stay zero unless the `track_timing` option of
+/// `AsyncReaderBuilder` is enabled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseTiming {
+    /// The number of bytes consumed from the underlying reader.
+    pub bytes: u64,
+    /// The number of records read, not including the header row.
+    pub records: u64,
+    /// The wall-clock time spent parsing, not including the time spent
+    /// waiting for the underlying reader.
+    pub elapsed: Duration,
+}
+
+/// A non-fatal anomaly found while reading, as returned by
+/// `AsyncReader::warnings`.
+///
+/// Warnings are only collected if the `collect_warnings` option of
+/// `AsyncReaderBuilder` is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// The position of the record the anomaly was found in, if available.
+    pub pos: Option<Position>,
+    /// The anomaly found.
+    pub kind: WarningKind,
+}
+
+/// The kind of a `Warning`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A record has a different number of fields than the first record,
+    /// which is allowed in `flexible` mode.
+    UnequalLengths {
+        /// The number of fields in the first record.
+        expected_len: u64,
+        /// The number of fields in the record.
+        len: u64,
+    },
+    /// A record has invalid UTF-8, which was replaced as allowed by
+    /// `InvalidUtf8Policy::ReplaceLossy`.
+    ReplacedInvalidUtf8 {
+        /// The index of the first field with invalid UTF-8.
+        field: u64,
+    },
+    /// A record has invalid UTF-8, so it was skipped as allowed by
+    /// `InvalidUtf8Policy::Skip`.
+    SkippedInvalidUtf8 {
+        /// The index of the first field with invalid UTF-8.
+        field: u64,
+    },
+}
+
+/// The state of a CSV reader in the middle of the data, from which parsing
+/// can be resumed by another reader.
+///
+/// The quoting state of the parser is captured as the bytes consumed since
+/// the last complete record, which a resumed reader parses again before
+/// continuing with new data. The header row, if any, is kept as well.
+///
+/// A state is returned by `AsyncReader::parser_state`, and used by
+/// `AsyncReaderBuilder::create_reader_with_state`. It can be taken apart
+/// and rebuilt, e.g. to persist it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParserState {
+    pending: Vec<u8>,
+    position: Position,
+    headers: Option<ByteRecord>,
+}
+
+impl ParserState {
+    /// Create a new parser state from its parts.
+    pub fn new(pending: Vec<u8>, position: Position, headers: Option<ByteRecord>) -> ParserState {
+        ParserState { pending, position, headers }
+    }
+
+    /// The bytes of the record that was being parsed.
+    pub fn pending(&self) -> &[u8] {
+        &self.pending
+    }
+
+    /// The position of the first pending byte within the data.
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    /// The header row, if the reader was configured to have one.
+    pub fn headers(&self) -> Option<&ByteRecord> {
+        self.headers.as_ref()
+    }
+
+    /// Take the state apart into the pending bytes, their position and the
+    /// header row.
+    pub fn into_parts(self) -> (Vec<u8>, Position, Option<ByteRecord>) {
+        (self.pending, self.position, self.headers)
+    }
+}
+
+/// Parse the raw bytes of a delimited record again with `core`, and push
+/// the span of each field relative to `start` into `spans`.
+///
+/// The bytes are fed one at a time, so that the parser reports the end of
+/// each field at the delimiter or terminator that ends it. `core` must not
+/// parse anything else, so it is left between records like the parser that
+/// read them.
+fn field_spans(
+    core: &mut CoreReader,
+    raw: &[u8],
+    start: u64,
+    term: Terminator,
+    spans: &mut Vec<(u64, u64)>,
+) {
+    use csv_core::ReadRecordResult;
+
+    let is_term = |b: u8| match term {
+        Terminator::Any(t) => b == t,
+        _ => b == b'\r' || b == b'\n',
+    };
+    let mut out = [0; 1];
+    let mut ends = [0; 1];
+    let mut i = 0;
+    // A byte order mark is stripped by the parser only if it is fed whole.
+    if start == 0 && raw.starts_with(b"\xef\xbb\xbf") {
+        i = 3;
+    }
+    let mut field_start = i;
+    // Blank lines and comments before the record are skipped by the parser
+    // without any output, while the first line of the record ends a field
+    // or has some output by its end.
+    let mut started = false;
+    while i < raw.len() {
+        let (res, _, nout, nend) = core.read_record(&raw[i..=i], &mut out, &mut ends);
+        if !started {
+            if nout > 0 || nend > 0 || res == ReadRecordResult::Record {
+                started = true;
+            } else if raw[i] == b'\n' || (i == field_start && is_term(raw[i])) {
+                field_start = i + 1;
+            }
+        }
+        if nend > 0 {
+            spans.push((start + field_start as u64, (i - field_start) as u64));
+            field_start = i + 1;
+        }
+        if res == ReadRecordResult::Record {
+            return;
+        }
+        i += 1;
+    }
+    // The last record of the data may have no terminator.
+    core.read_record(&[], &mut out, &mut ends);
+    spans.push((start + field_start as u64, (raw.len() - field_start) as u64));
+}
+
+/// Returns the names appearing more than once in a header row, in order of
+/// their first appearance, along with the indices of their columns.
+fn duplicate_headers(headers: &ByteRecord) -> Vec<(String, Vec<usize>)> {
+    let mut names: Vec<(&[u8], Vec<usize>)> = Vec::new();
+    for (i, name) in headers.iter().enumerate() {
+        match names.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, fields)) => fields.push(i),
+            None => names.push((name, vec![i])),
+        }
+    }
+    names
+        .into_iter()
+        .filter(|(_, fields)| fields.len() > 1)
+        .map(|(name, fields)| (String::from_utf8_lossy(name).into_owned(), fields))
+        .collect()
+}
+
+/// A function transforming fields as they are read, shared by the readers
+/// created from one builder.
+#[derive(Clone)]
+struct FieldTransform(Arc<Mutex<Box<TransformFn>>>);
+
+type TransformFn = dyn FnMut(usize, &[u8]) -> Cow<[u8]> + Send;
+
+impl fmt::Debug for FieldTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("FieldTransform(..)")
+    }
+}
+
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-// Reader
 //-//////////////////////////////////////////////////////////////////////////////////////////////
@@ -637,13 +2308,150 @@ pub struct ReaderState {
     /// When set, the first row of parsed CSV data is excluded from things
     /// that read records, like iterators and `read_record`.
     has_headers: bool,
+    /// When set and `has_headers` is not, the headers are generated names
+    /// instead of the first row.
+    generate_headers: Option<HeaderScheme>,
+    /// The first row, when it was replaced by generated headers and not
+    /// yielded as a record yet.
+    first_row: Option<ByteRecord>,
+    /// Records read ahead by `infer_schema`, to be yielded before any
+    /// further records are read.
+    replay: VecDeque<ByteRecord>,
     /// When set, there is no restriction on the length of records. When not
     /// set, every record must have the same number of fields, or else an error
     /// is reported.
     flexible: bool,
     trim: Trim,
+    /// How records that are not valid UTF-8 are read as strings.
+    invalid_utf8: InvalidUtf8Policy,
+    /// When set, runs of whitespace within fields are replaced by a single
+    /// space.
+    collapse_whitespace: bool,
+    /// When set, `\r\n` and `\r` within fields are replaced by `\n`.
+    normalize_newlines: bool,
+    /// When set, these opening and closing bytes are stripped from fields
+    /// they surround.
+    field_wrappers: Option<(u8, u8)>,
+    /// When set, every field is replaced by the result of this function.
+    transform_field: Option<FieldTransform>,
+    /// Indices of the fields holding base64 encoded data to be decoded.
+    #[cfg(feature = "base64")]
+    base64_columns: Vec<usize>,
+    /// Indices of the fields checked against a pattern, with the pattern.
+    #[cfg(feature = "regex")]
+    column_patterns: Vec<(usize, Regex)>,
+    /// Scratch space for rebuilding records in `postprocess_fields`.
+    transformed: ByteRecord,
+    /// When set, the indices of the only columns kept in records.
+    selected_columns: Option<Vec<usize>>,
+    /// When set, the header names of the only columns kept in records,
+    /// resolved into `selected_columns` whenever a header row is read.
+    selected_names: Option<Vec<String>>,
+    /// When set, lines are split into fields of these byte widths instead
+    /// of being parsed as delimited data.
+    fixed_width: Option<Vec<usize>>,
+    /// When set, empty fields are dropped from delimited records.
+    collapse_delimiters: bool,
+    /// When set, delimited data is checked for compliance with RFC 4180.
+    strict: Option<StrictChecker>,
+    /// Set when a record ended with a `\r` that the `strict` checker has
+    /// yet to see the next byte of.
+    strict_lookahead: bool,
+    /// When set, `sep=` lines starting tables are looked for, and these
+    /// settings build the parser for the delimiter they declare.
+    sep_directive: Option<CoreSettings>,
+    /// Set when a `sep=` line has yet to be looked for at the start of the
+    /// current table.
+    sep_pending: bool,
+    /// Bytes consumed from the underlying reader that are still to be
+    /// parsed, ahead of its buffer.
+    pushback: Vec<u8>,
+    /// When set, blank lines separate independent tables.
+    multi_table: bool,
+    /// Set when the end of the current table has been reached.
+    table_done: bool,
+    /// Set once the first table has been handed out by `next_table`.
+    table_started: bool,
+    /// When set, repeated names in the header row are reported as an error.
+    error_on_duplicate_headers: bool,
+    /// When set, `summarize` fails on values that are not numbers.
+    error_on_non_numeric: bool,
+    /// The last byte consumed from the underlying reader.
+    last_byte: u8,
+    /// The record terminator, used to strip raw record text.
+    terminator: Terminator,
+    /// When set, the bytes consumed while parsing a record are copied into
+    /// `raw`.
+    capture_raw: bool,
+    /// The raw bytes of the last record read while `capture_raw` was set.
+    raw: Vec<u8>,
+    /// When set, the quote byte, and whether each field of delimited
+    /// records was quoted is recorded.
+    track_quoted: Option<u8>,
+    /// The spans of the fields of the last record read, when tracking
+    /// quoted fields.
+    spans: Vec<(u64, u64)>,
+    /// When set, the bytes consumed since the last complete record are
+    /// kept in `pending`.
+    resumable: bool,
+    /// The bytes consumed since the last complete record.
+    pending: Vec<u8>,
+    /// The position of the first byte in `pending`.
+    pending_pos: Position,
+    /// When set, parsing throughput is measured into `timing`.
+    track_timing: bool,
+    /// The warnings collected, when `collect_warnings` is set.
+    warnings: Option<Vec<Warning>>,
+    /// The parsing throughput measured so far.
+    timing: ParseTiming,
+    /// The layout of the footer row expected at the end of the data.
+    expect_footer: Option<FooterSpec>,
+    /// The checksum of the bytes consumed, if the footer includes one.
+    checksum: Adler32,
+    /// The checksum of the bytes consumed before the current record.
+    record_sum: Option<u32>,
+    /// The last record read, with the checksum of the bytes before it. It
+    /// is held back until it is known not to be the footer.
+    held: Option<(ByteRecord, u32)>,
+    /// The number of records read before the held record.
+    released: u64,
+    /// The number of header rows among the records released, one per table.
+    header_rows: u64,
+    /// Set once the footer has been verified.
+    footer_checked: bool,
+    /// The largest size of a single field, in bytes.
+    max_field_size: Option<usize>,
+    /// The largest size of a single record, in bytes.
+    max_record_size: Option<usize>,
+    /// The largest number of bytes read in total.
+    max_total_bytes: Option<u64>,
+    /// Set once more than `max_total_bytes` bytes have been read.
+    input_too_large: bool,
+    /// The error of a record that exceeded `max_field_size` or
+    /// `max_record_size`, reported once the rest of it has been discarded.
+    oversized: Option<Error>,
+    /// The pool providing the records yielded by streams.
+    record_pool: Option<Arc<RecordPool>>,
+    /// Whether a record is being read, i.e. a read returned `Poll::Pending`
+    /// before the record was complete.
+    reading: bool,
+    /// The record being read. It is swapped into the caller's record once
+    /// complete, so a read may be resumed with any record.
+    partial: ByteRecord,
+    /// The number of field bytes and field ends already written to `partial`.
+    partial_len: (usize, usize),
+    /// The line being read in fixed-width mode.
+    line: Vec<u8>,
+    /// When set, the record being read is the first one after the header
+    /// row, and a mismatch in length is reported against the header row.
+    check_header_len: bool,
     /// The number of fields in the first record parsed.
     first_field_count: Option<u64>,
+    /// The number of records still to discard at the start of the data.
+    skip_records: u64,
+    /// Whether the record being read is discarded, so its number of fields
+    /// is not checked.
+    skipping: bool,
     /// The current position of the parser.
     ///
     /// Note that this position is only observable by callers at the start
@@ -656,6 +2464,14 @@ pub struct ReaderState {
     /// If set, CSV records' stream will end when first i/o error happens. 
     /// Otherwise it will continue trying to read from underlying reader.
     end_on_io_error: bool,
+    /// Whether to wait for more data at the end of the input.
+    follow: bool,
+    /// The longest time to wait for data from the underlying reader.
+    #[cfg(feature = "timeout")]
+    idle_timeout: Option<Duration>,
+    /// The timer started when the underlying reader had no data ready.
+    #[cfg(feature = "timeout")]
+    idle_timer: Option<Delay>,
     /// IO errors on the underlying reader will be considered as an EOF for
     /// subsequent read attempts, as it would be incorrect to keep on trying
     /// to read when the underlying reader has broken.
@@ -696,27 +2512,150 @@ struct Headers {
     string_record: result::Result<StringRecord, Utf8Error>,
 }
 
-impl ReaderState {
-    #[inline(always)]
-    fn add_record(&mut self, record: &ByteRecord) -> Result<()> {
-        let i = self.cur_pos.record();
-        self.cur_pos.set_record(i.checked_add(1).unwrap());
-        if !self.flexible {
-            match self.first_field_count {
-                None => self.first_field_count = Some(record.len() as u64),
-                Some(expected) => {
-                    if record.len() as u64 != expected {
-                        return Err(Error::new(ErrorKind::UnequalLengths {
-                            pos: record.position().map(Clone::clone),
-                            expected_len: expected,
-                            len: record.len() as u64,
-                        }));
-                    }
+impl ReaderState {
+    #[inline(always)]
+    fn add_record(&mut self, record: &ByteRecord) -> Result<()> {
+        let i = self.cur_pos.record();
+        self.cur_pos.set_record(i.checked_add(1).unwrap());
+        // The length of a record that may be the footer is checked once it
+        // is known not to be.
+        if self.expect_footer.is_some() {
+            return Ok(());
+        }
+        self.check_field_count(record)
+    }
+
+    /// Keep only the selected columns of `record`. If `record` is the first
+    /// row, selected header names are looked up in it first.
+    fn select_columns(&mut self, record: &mut ByteRecord) -> Result<()> {
+        if let Some(ref names) = self.selected_names {
+            if self.headers.is_none() {
+                if self.seeked {
+                    return Err(Error::new(ErrorKind::Seek));
+                }
+                self.selected_columns = Some(header_indices(record, names)?);
+            }
+        }
+        let columns = match self.selected_columns {
+            Some(ref columns) => columns,
+            None => return Ok(()),
+        };
+        let out = &mut self.transformed;
+        out.clear();
+        for &i in columns {
+            out.push_field(record.get(i).unwrap_or(b""));
+        }
+        out.set_position(record.position().cloned());
+        out.set_quoted(record.take_quoted().map(|quoted| {
+            columns.iter().map(|&i| quoted.get(i).cloned().unwrap_or(false)).collect()
+        }));
+        std::mem::swap(record, out);
+        Ok(())
+    }
+
+    fn check_field_count(&mut self, record: &ByteRecord) -> Result<()> {
+        if self.skipping || (self.flexible && self.warnings.is_none()) {
+            return Ok(());
+        }
+        match self.first_field_count {
+            None => self.first_field_count = Some(record.len() as u64),
+            Some(expected) if record.len() as u64 != expected => {
+                if !self.flexible {
+                    return Err(Error::new(ErrorKind::UnequalLengths {
+                        pos: record.position().map(Clone::clone),
+                        expected_len: expected,
+                        len: record.len() as u64,
+                    }));
                 }
+                self.warn(record.position(), WarningKind::UnequalLengths {
+                    expected_len: expected,
+                    len: record.len() as u64,
+                });
             }
+            Some(_) => {}
         }
         Ok(())
     }
+
+    /// Keep a warning, if warnings are collected.
+    fn warn(&mut self, pos: Option<&Position>, kind: WarningKind) {
+        if let Some(ref mut warnings) = self.warnings {
+            warnings.push(Warning { pos: pos.cloned(), kind });
+        }
+    }
+
+    /// Add bytes consumed while reading a record to the footer checksum.
+    fn add_checksum(&mut self, mut data: &[u8]) {
+        match self.expect_footer {
+            Some(ref spec) if spec.checksum => {}
+            _ => return,
+        }
+        if self.record_sum.is_none() && !data.is_empty() {
+            // The `\n` of a `\r\n` pair still belongs to the previous line.
+            if self.last_byte == b'\r' && data[0] == b'\n' {
+                self.checksum.update(&data[..1]);
+                data = &data[1..];
+            }
+            self.record_sum = Some(self.checksum.value());
+        }
+        self.checksum.update(data);
+    }
+
+    /// Check `bytes` read in total against `max_total_bytes`. Once the
+    /// limit has been exceeded, this always fails.
+    fn check_total_bytes(&mut self, bytes: u64) -> Result<()> {
+        match self.max_total_bytes {
+            Some(limit) if self.input_too_large || bytes > limit => {
+                self.input_too_large = true;
+                Err(Error::new(ErrorKind::InputTooLarge { limit }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the size of the fields of the partial record, starting with
+    /// field `from`, against `max_field_size`. The last field may still be
+    /// incomplete.
+    fn check_field_sizes(&mut self, from: usize) -> Result<()> {
+        let limit = match self.max_field_size {
+            None => return Ok(()),
+            Some(limit) => limit,
+        };
+        let (len, nends) = self.partial_len;
+        let (_, ends) = self.partial.as_parts();
+        let mut start = if from == 0 { 0 } else { ends[from - 1] };
+        let mut oversized = None;
+        for (i, &end) in ends[from..nends].iter().chain(Some(&len)).enumerate() {
+            if end - start > limit {
+                oversized = Some((from + i, end - start));
+                break;
+            }
+            start = end;
+        }
+        match oversized {
+            None => Ok(()),
+            Some((i, size)) => Err(Error::new(ErrorKind::FieldTooLarge {
+                pos: self.partial.position().cloned(),
+                field_index: i as u64,
+                size: size as u64,
+                limit: limit as u64,
+            })),
+        }
+    }
+
+    /// Check the size of the partial record against `max_record_size`.
+    fn check_record_size(&self) -> Result<()> {
+        match self.max_record_size {
+            Some(limit) if self.partial_len.0 > limit => {
+                Err(Error::new(ErrorKind::RecordTooLarge {
+                    pos: self.partial.position().cloned(),
+                    size: self.partial_len.0 as u64,
+                    limit: limit as u64,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 /// CSV async reader internal implementation used by both record reader and deserializer.
 /// 
@@ -728,6 +2667,9 @@ pub struct AsyncReaderImpl<R> {
     /// DFA transition table, which along with other things, tallies up to
     /// almost 500 bytes on the stack.
     core: Box<CoreReader>,
+    /// A second parser with the same configuration, which parses the raw
+    /// bytes of records again to find the spans of their fields.
+    span_core: Box<CoreReader>,
     /// The underlying reader.
     rdr: io::BufReader<R>,
     /// Various state tracking.
@@ -736,35 +2678,6 @@ pub struct AsyncReaderImpl<R> {
     state: ReaderState,
 }
 
-#[must_use = "futures do nothing unless you `.await` or poll them"]
-struct FillBuf<'a, R: AsyncBufRead + ?Sized> {
-    reader: &'a mut R,
-}
-
-impl<R: AsyncBufRead + ?Sized + Unpin> Unpin for FillBuf<'_, R> {}
-
-impl<'a, R: AsyncBufRead + ?Sized + Unpin> FillBuf<'a, R> {
-    pub fn new(reader: &'a mut R) -> Self {
-        Self { reader }
-    }
-}
-
-impl<R: AsyncBufRead + ?Sized + Unpin> Future for FillBuf<'_, R> {
-    type Output = io::Result<usize>;
-    
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        match Pin::new(&mut *self.reader).poll_fill_buf(cx) {
-            Poll::Ready(res) => {
-                match res {
-                    Ok(res) => Poll::Ready(Ok(res.len())),
-                    Err(e) => Poll::Ready(Err(e))
-                }
-            },
-            Poll::Pending => Poll::Pending
-        }
-    }
-} 
-
 impl<'r, R> AsyncReaderImpl<R>
 where
     R: io::AsyncRead + std::marker::Unpin + 'r,
@@ -774,14 +2687,91 @@ where
     fn new(builder: &AsyncReaderBuilder, rdr: R) -> AsyncReaderImpl<R> {
         AsyncReaderImpl {
             core: Box::new(builder.builder.build()),
+            span_core: Box::new(builder.builder.build()),
             rdr: io::BufReader::with_capacity(builder.capacity, rdr),
             state: ReaderState {
                 headers: None,
                 has_headers: builder.has_headers,
+                generate_headers: builder.generate_headers.clone(),
+                first_row: None,
+                replay: VecDeque::new(),
                 flexible: builder.flexible,
                 trim: builder.trim,
+                invalid_utf8: builder.invalid_utf8,
+                collapse_whitespace: builder.collapse_whitespace,
+                normalize_newlines: builder.normalize_newlines,
+                field_wrappers: builder.field_wrappers,
+                transform_field: builder.transform_field.clone(),
+                #[cfg(feature = "base64")]
+                base64_columns: builder.base64_columns.clone(),
+                #[cfg(feature = "regex")]
+                column_patterns: builder.column_patterns.clone(),
+                transformed: ByteRecord::new(),
+                selected_columns: builder.selected_columns.clone(),
+                selected_names: builder.selected_names.clone(),
+                fixed_width: builder.fixed_width.clone(),
+                collapse_delimiters: builder.collapse_delimiters,
+                strict: if builder.strict {
+                    Some(StrictChecker::new(builder.delimiter, builder.quote, builder.terminator))
+                } else {
+                    None
+                },
+                strict_lookahead: false,
+                sep_directive: if builder.respect_sep_directive && builder.fixed_width.is_none() {
+                    Some(CoreSettings::new(builder))
+                } else {
+                    None
+                },
+                sep_pending: builder.respect_sep_directive && builder.fixed_width.is_none(),
+                pushback: Vec::new(),
+                multi_table: builder.multi_table,
+                table_done: false,
+                table_started: false,
+                error_on_duplicate_headers: builder.error_on_duplicate_headers,
+                error_on_non_numeric: builder.error_on_non_numeric,
+                last_byte: 0,
+                terminator: builder.terminator,
+                capture_raw: false,
+                track_quoted: if builder.track_quoted && builder.fixed_width.is_none() {
+                    Some(builder.quote)
+                } else {
+                    None
+                },
+                spans: Vec::new(),
+                raw: Vec::new(),
+                resumable: builder.resumable,
+                pending: Vec::new(),
+                pending_pos: Position::new(),
+                track_timing: builder.track_timing,
+                warnings: if builder.collect_warnings { Some(Vec::new()) } else { None },
+                timing: ParseTiming::default(),
+                expect_footer: builder.expect_footer.clone(),
+                checksum: Adler32::default(),
+                record_sum: None,
+                held: None,
+                released: 0,
+                header_rows: 0,
+                footer_checked: false,
+                max_field_size: builder.max_field_size,
+                max_record_size: builder.max_record_size,
+                max_total_bytes: builder.max_total_bytes,
+                input_too_large: false,
+                oversized: None,
+                record_pool: builder.record_pool.clone(),
+                reading: false,
+                partial: ByteRecord::new(),
+                partial_len: (0, 0),
+                line: Vec::new(),
+                check_header_len: false,
                 end_on_io_error: builder.end_on_io_error,
+                follow: builder.follow,
+                #[cfg(feature = "timeout")]
+                idle_timeout: builder.idle_timeout,
+                #[cfg(feature = "timeout")]
+                idle_timer: None,
                 first_field_count: None,
+                skip_records: builder.skip_records,
+                skipping: false,
                 cur_pos: Position::new(),
                 first: false,
                 seeked: false,
@@ -793,11 +2783,13 @@ where
     /// Returns a reference to the first row read by this parser.
     ///
     pub async fn headers(&mut self) -> Result<&StringRecord> {
-        if self.state.headers.is_none() {
-            let mut record = ByteRecord::new();
-            self.read_byte_record_impl(&mut record).await?;
-            self.set_headers_impl(Err(record));
-        }
+        future::poll_fn(|cx| self.poll_read_headers(cx)).await?;
+        self.loaded_headers()
+    }
+
+    /// Returns the headers as strings, once they have been read.
+    fn loaded_headers(&self) -> Result<&StringRecord> {
+        self.check_loaded_headers()?;
         let headers = self.state.headers.as_ref().unwrap();
         match headers.string_record {
             Ok(ref record) => Ok(record),
@@ -811,12 +2803,74 @@ where
     /// Returns a reference to the first row read by this parser as raw bytes.
     ///
     pub async fn byte_headers(&mut self) -> Result<&ByteRecord> {
+        future::poll_fn(|cx| self.poll_read_headers(cx)).await?;
+        self.check_loaded_headers()?;
+        Ok(&self.state.headers.as_ref().unwrap().byte_record)
+    }
+
+    /// Returns every header name that appears more than once, along with
+    /// the indices of its columns.
+    ///
+    pub async fn check_duplicate_headers(&mut self) -> Result<Vec<(String, Vec<usize>)>> {
+        future::poll_fn(|cx| self.poll_read_headers(cx)).await?;
+        Ok(duplicate_headers(&self.state.headers.as_ref().unwrap().byte_record))
+    }
+
+    /// Reports repeated header names, if `error_on_duplicate_headers` is set.
+    fn check_loaded_headers(&self) -> Result<()> {
+        if !self.state.error_on_duplicate_headers {
+            return Ok(());
+        }
+        let headers = &self.state.headers.as_ref().unwrap().byte_record;
+        match duplicate_headers(headers).into_iter().next() {
+            None => Ok(()),
+            Some((name, fields)) => Err(Error::new(ErrorKind::DuplicateHeader {
+                pos: headers.position().cloned(),
+                name,
+                fields: fields.into_iter().map(|i| i as u64).collect(),
+            })),
+        }
+    }
+
+    /// Read the first row as headers, unless it has been read already.
+    fn poll_read_headers(&mut self, cx: &mut Context) -> Poll<Result<()>> {
         if self.state.headers.is_none() {
             let mut record = ByteRecord::new();
-            self.read_byte_record_impl(&mut record).await?;
-            self.set_headers_impl(Err(record));
+            ready!(self.poll_read_byte_record_impl(cx, &mut record))?;
+            self.set_headers_from_first_row(record);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Set the headers to the first row, or to generated names if the
+    /// first row is not a header row and `generate_headers` is set.
+    fn set_headers_from_first_row(&mut self, record: ByteRecord) {
+        if self.state.has_headers {
+            self.state.header_rows += 1;
+        }
+        match self.state.generate_headers {
+            Some(ref scheme) if !self.state.has_headers => {
+                // Selected columns are named after their original index.
+                let columns = self.state.selected_columns.as_ref();
+                let headers: StringRecord = (0..record.len())
+                    .map(|i| scheme.header(columns.map_or(i, |columns| columns[i])))
+                    .collect();
+                self.state.first_row = Some(record);
+                self.set_headers_impl(Ok(headers));
+            }
+            _ => self.set_headers_impl(Err(record)),
         }
-        Ok(&self.state.headers.as_ref().unwrap().byte_record)
+    }
+
+    /// How records that are not valid UTF-8 are read as strings.
+    pub(crate) fn invalid_utf8(&self) -> InvalidUtf8Policy {
+        self.state.invalid_utf8
+    }
+
+    /// Whether deserialized records are matched to the headers by name.
+    #[cfg(feature = "with_serde")]
+    fn deserializes_by_name(&self) -> bool {
+        self.state.has_headers || self.state.generate_headers.is_some()
     }
 
     /// Set the headers of this CSV parser manually.
@@ -831,6 +2885,22 @@ where
         self.set_headers_impl(Err(headers));
     }
 
+    /// Rename header names according to `mapping`, a list of (old, new)
+    /// pairs. Names not in `mapping` are kept.
+    pub async fn with_renamed_headers(&mut self, mapping: &[(&str, &str)]) -> Result<()> {
+        let headers = self.headers().await?;
+        let mut renamed: StringRecord = headers
+            .iter()
+            .map(|name| match mapping.iter().find(|&&(old, _)| old == name) {
+                Some(&(_, new)) => new,
+                None => name,
+            })
+            .collect();
+        renamed.set_position(headers.position().cloned());
+        self.set_headers(renamed);
+        Ok(())
+    }
+
     fn set_headers_impl(
         &mut self,
         headers: result::Result<StringRecord, ByteRecord>,
@@ -864,14 +2934,231 @@ where
     /// Read a single row into the given record. Returns false when no more
     /// records could be read.
     pub async fn read_record(&mut self, record: &mut StringRecord) -> Result<bool> {
-        let result = record.read(self).await;
+        future::poll_fn(|cx| self.poll_read_record(cx, record)).await
+    }
+
+    /// Attempt to read a single row into the given record. See
+    /// `poll_read_byte_record` for the requirements on polling.
+    pub fn poll_read_record(
+        &mut self,
+        cx: &mut Context,
+        record: &mut StringRecord,
+    ) -> Poll<Result<bool>> {
+        let result = ready!(record.poll_read(cx, self));
         // We need to trim again because trimming string records includes
         // Unicode whitespace. (ByteRecord trimming only includes ASCII
         // whitespace.)
         if self.state.trim.should_trim_fields() {
             record.trim();
         }
-        result
+        Poll::Ready(result)
+    }
+
+    /// Attempt to read a single row into the given record and its raw text,
+    /// as found in the source data, into `raw`.
+    fn poll_read_record_with_raw(
+        &mut self,
+        cx: &mut Context,
+        raw: &mut String,
+        record: &mut StringRecord,
+    ) -> Poll<Result<bool>> {
+        self.state.capture_raw = true;
+        let result = ready!(self.poll_read_record(cx, record));
+        self.state.capture_raw = false;
+        let ok = result?;
+        raw.clear();
+        if ok {
+            let is_term = |b: &u8| match self.state.terminator {
+                Terminator::Any(t) => *b == t,
+                _ => *b == b'\r' || *b == b'\n',
+            };
+            let line = &self.state.raw[..];
+            let start = line.iter().position(|b| !is_term(b)).unwrap_or(line.len());
+            let end = line.iter().rposition(|b| !is_term(b)).map_or(start, |i| i + 1);
+            match std::str::from_utf8(&line[start..end]) {
+                Ok(line) => raw.push_str(line),
+                Err(err) => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Utf8 {
+                        pos: record.position().cloned(),
+                        err: new_utf8_error(0, err.valid_up_to()),
+                    })))
+                }
+            }
+        }
+        Poll::Ready(Ok(ok))
+    }
+
+    /// Read a single row into the given byte record, and the span of each
+    /// of its fields in the source data into `spans`.
+    pub async fn read_byte_record_with_spans(
+        &mut self,
+        record: &mut ByteRecord,
+        spans: &mut Vec<(u64, u64)>,
+    ) -> Result<bool> {
+        future::poll_fn(|cx| self.poll_read_byte_record_with_spans(cx, record, spans)).await
+    }
+
+    fn poll_read_byte_record_with_spans(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+        spans: &mut Vec<(u64, u64)>,
+    ) -> Poll<Result<bool>> {
+        self.state.capture_raw = true;
+        let result = ready!(self.poll_read_byte_record(cx, record));
+        self.state.capture_raw = false;
+        let ok = result?;
+        spans.clear();
+        if ok && self.state.track_quoted.is_some() {
+            // The spans are already known, and `span_core` is past them.
+            spans.extend_from_slice(&self.state.spans);
+        } else if ok {
+            let start = record.position().map_or(0, Position::byte);
+            let raw = &self.state.raw[..];
+            match self.state.fixed_width {
+                Some(ref widths) => {
+                    let mut len = raw.len();
+                    if len > 0 && raw[len - 1] == b'\n' {
+                        len -= 1;
+                    }
+                    if len > 0 && raw[len - 1] == b'\r' {
+                        len -= 1;
+                    }
+                    let mut offset = 0;
+                    for width in widths {
+                        let end = (offset + width).min(len);
+                        let field_start = offset.min(end);
+                        spans.push((start + field_start as u64, (end - field_start) as u64));
+                        offset += width;
+                    }
+                }
+                None => field_spans(&mut self.span_core, raw, start, self.state.terminator, spans),
+            }
+        }
+        Poll::Ready(Ok(ok))
+    }
+
+    /// Write all remaining records to `out` as JSON objects, one per line.
+    ///
+    #[cfg(feature = "serde_json")]
+    pub async fn write_ndjson<W: io::AsyncWrite + std::marker::Unpin>(
+        &mut self,
+        mut out: W,
+        numbers: bool,
+    ) -> Result<()> {
+        use serde_json::Value;
+
+        let headers = if self.state.has_headers {
+            let headers = self.headers().await?;
+            // Repeated names would overwrite each other's values.
+            if let Some((name, fields)) =
+                duplicate_headers(headers.as_byte_record()).into_iter().next()
+            {
+                return Err(Error::new(ErrorKind::DuplicateHeader {
+                    pos: headers.position().cloned(),
+                    name,
+                    fields: fields.into_iter().map(|i| i as u64).collect(),
+                }));
+            }
+            Some(headers.clone())
+        } else {
+            None
+        };
+        let mut record = StringRecord::new();
+        while self.read_record(&mut record).await? {
+            // The object is written by hand to keep the members in column
+            // order.
+            let mut line = String::from("{");
+            for (i, field) in record.iter().enumerate() {
+                if i > 0 {
+                    line.push(',');
+                }
+                let key = match headers.as_ref().and_then(|h| h.get(i)) {
+                    Some(name) => Value::from(name),
+                    None => Value::from(i.to_string()),
+                };
+                let number = if numbers { canonical_number(field) } else { None };
+                let value = match number {
+                    Some(n) => Value::Number(n),
+                    None => Value::from(field),
+                };
+                line.push_str(&key.to_string());
+                line.push(':');
+                line.push_str(&value.to_string());
+            }
+            line.push_str("}\n");
+            out.write_all(line.as_bytes()).await?;
+        }
+        out.flush().await?;
+        Ok(())
+    }
+
+    /// Infer the type of every column from up to `sample_rows` records.
+    ///
+    /// The sampled records are kept and yielded again by the next reads.
+    pub async fn infer_schema(&mut self, sample_rows: usize) -> Result<Vec<InferredType>> {
+        let width = self.byte_headers().await?.len();
+        let mut columns = vec![ColumnInference::default(); width];
+        let mut sampled = std::mem::take(&mut self.state.replay);
+        let mut rows = 0;
+        for record in sampled.iter().take(sample_rows) {
+            infer_record(&mut columns, record);
+            rows += 1;
+        }
+        let mut record = ByteRecord::new();
+        while rows < sample_rows {
+            match self.read_byte_record(&mut record).await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(err) => {
+                    self.state.replay = sampled;
+                    return Err(err);
+                }
+            }
+            infer_record(&mut columns, &record);
+            sampled.push_back(record.clone());
+            rows += 1;
+        }
+        self.state.replay = sampled;
+        Ok(columns.into_iter().map(ColumnInference::finish).collect())
+    }
+
+    /// Compute statistics about the given numeric columns over all remaining
+    /// records.
+    pub async fn summarize(&mut self, cols: &[usize]) -> Result<Vec<ColumnSummary>> {
+        let mut summaries: Vec<ColumnSummary> =
+            cols.iter().map(|&col| ColumnSummary::new(col)).collect();
+        let mut record = ByteRecord::new();
+        while self.read_byte_record(&mut record).await? {
+            for summary in summaries.iter_mut() {
+                let field = record.get(summary.column).unwrap_or(b"");
+                if !summary.add(field) && self.state.error_on_non_numeric {
+                    return Err(Error::new(ErrorKind::NonNumeric {
+                        pos: record.position().cloned(),
+                        field: summary.column as u64,
+                        value: String::from_utf8_lossy(field).into_owned(),
+                    }));
+                }
+            }
+        }
+        Ok(summaries)
+    }
+
+    /// Whether the header row is not followed by any data record. A record
+    /// read to find out is kept and yielded again by the next read.
+    pub async fn is_header_only(&mut self) -> Result<bool> {
+        if !self.state.has_headers || self.byte_headers().await?.is_empty() {
+            return Ok(false);
+        }
+        if !self.state.replay.is_empty() {
+            return Ok(false);
+        }
+        let mut record = ByteRecord::new();
+        if !self.read_byte_record(&mut record).await? {
+            return Ok(true);
+        }
+        self.state.replay.push_back(record);
+        Ok(false)
     }
 
     /// Read a single row into the given byte record. Returns false when no
@@ -880,99 +3167,785 @@ where
         &mut self,
         record: &mut ByteRecord,
     ) -> Result<bool> {
+        future::poll_fn(|cx| self.poll_read_byte_record(cx, record)).await
+    }
+
+    /// Attempt to read a single row into the given byte record.
+    ///
+    /// Returns `Poll::Pending` when the underlying reader has no data ready,
+    /// in which case the waker in `cx` is woken once it has. The partially
+    /// read record is kept by the reader, so the read may be resumed with
+    /// any record; `record` is only written once the read completes.
+    pub fn poll_read_byte_record(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        // Records replayed after `infer_schema` were measured already.
+        if !self.state.track_timing || !self.state.replay.is_empty() {
+            return self.poll_read_byte_record_untimed(cx, record);
+        }
+        let start = Instant::now();
+        let byte = self.state.cur_pos.byte();
+        let result = self.poll_read_byte_record_untimed(cx, record);
+        let timing = &mut self.state.timing;
+        timing.elapsed += start.elapsed();
+        timing.bytes += self.state.cur_pos.byte().saturating_sub(byte);
+        if let Poll::Ready(Ok(true)) = result {
+            timing.records += 1;
+        }
+        result
+    }
+
+    /// Hand out a record read by a stream, replacing it with a record from
+    /// the pool, or hand out a copy if there is no pool.
+    fn yield_byte_record(&self, record: &mut ByteRecord) -> ByteRecord {
+        match self.state.record_pool {
+            Some(ref pool) => {
+                let fresh = pool.get_with_capacity(record.as_slice().len());
+                std::mem::replace(record, fresh)
+            }
+            None => record.clone(),
+        }
+    }
+
+    /// Like `yield_byte_record`, for records as strings.
+    fn yield_string_record(&self, record: &mut StringRecord) -> StringRecord {
+        match self.state.record_pool {
+            Some(ref pool) => {
+                let fresh = pool.get_with_capacity(record.as_byte_record().as_slice().len());
+                std::mem::replace(record, StringRecord::from_byte_record_lossy(fresh))
+            }
+            None => record.clone(),
+        }
+    }
+
+    /// Return the parsing throughput measured so far.
+    pub fn timing(&self) -> ParseTiming {
+        self.state.timing
+    }
+
+    /// Return the warnings collected so far.
+    pub fn warnings(&self) -> &[Warning] {
+        match self.state.warnings {
+            Some(ref warnings) => warnings,
+            None => &[],
+        }
+    }
+
+    /// Keep a warning about the record at `pos`, if warnings are collected.
+    pub(crate) fn warn(&mut self, pos: Option<&Position>, kind: WarningKind) {
+        self.state.warn(pos, kind);
+    }
+
+    /// Discard the records left to skip at the start of the data, then read
+    /// the next record.
+    fn poll_read_byte_record_untimed(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        while self.state.skip_records > 0 && !self.state.seeked {
+            if self.state.headers.is_none() {
+                // The header row is kept, but without one the first row is
+                // discarded too.
+                self.state.skipping = !self.state.has_headers;
+                let result = self.poll_read_headers(cx);
+                self.state.skipping = false;
+                ready!(result)?;
+            }
+            self.state.skipping = true;
+            let result = self.poll_read_byte_record_unskipped(cx, record);
+            self.state.skipping = false;
+            if !ready!(result)? {
+                return Poll::Ready(Ok(false));
+            }
+            self.state.skip_records -= 1;
+        }
+        self.poll_read_byte_record_unskipped(cx, record)
+    }
+
+    fn poll_read_byte_record_unskipped(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        if let Some(replayed) = self.state.replay.pop_front() {
+            *record = replayed;
+            return Poll::Ready(Ok(true));
+        }
         if !self.state.seeked && !self.state.has_headers && !self.state.first {
             // If the caller indicated "no headers" and we haven't yielded the
             // first record yet, then we should yield our header row if we have
             // one.
             if let Some(ref headers) = self.state.headers {
                 self.state.first = true;
-                record.clone_from(&headers.byte_record);
-                if self.state.trim.should_trim_fields() {
-                    record.trim();
+                match self.state.first_row.take() {
+                    Some(first_row) => *record = first_row,
+                    None => record.clone_from(&headers.byte_record),
                 }
-                return Ok(!record.is_empty());
+                self.postprocess_fields(record)?;
+                return Poll::Ready(Ok(!record.is_empty()));
             }
         }
-        let ok = self.read_byte_record_impl(record).await?;
-        self.state.first = true;
         if !self.state.seeked && self.state.headers.is_none() {
-            self.set_headers_impl(Err(record.clone()));
+            let ok = ready!(self.poll_read_byte_record_impl(cx, record))?;
+            self.state.first = true;
+            self.set_headers_from_first_row(record.clone());
+            self.state.first_row = None;
+            if !self.state.has_headers {
+                self.postprocess_fields(record)?;
+                return Poll::Ready(Ok(ok));
+            }
             // If the end user indicated that we have headers, then we should
             // never return the first row. Instead, we should attempt to
             // read and return the next one.
-            if self.state.has_headers {
-                let result = self.read_byte_record_impl(record).await;
-                if self.state.trim.should_trim_fields() {
-                    record.trim();
+            self.state.check_header_len = true;
+        }
+        let result = ready!(self.poll_read_byte_record_impl(cx, record));
+        if self.state.check_header_len {
+            self.state.check_header_len = false;
+            // A mismatch right after the header row is reported
+            // separately, as either of the rows may be the broken one.
+            let result = result.map_err(|err| match err.into_kind() {
+                ErrorKind::UnequalLengths { pos, expected_len, len } => {
+                    Error::new(ErrorKind::HeaderLengthMismatch {
+                        pos,
+                        header_len: expected_len,
+                        len,
+                    })
+                }
+                kind => Error::new(kind),
+            });
+            let ok = result?;
+            self.postprocess_fields(record)?;
+            return Poll::Ready(Ok(ok));
+        }
+        let ok = result?;
+        self.state.first = true;
+        self.postprocess_fields(record)?;
+        Poll::Ready(Ok(ok))
+    }
+
+    /// Apply the per-field options (trimming, newline normalization,
+    /// whitespace collapsing, base64 decoding, transformation, pattern checks) to a record that
+    /// is about to be returned to the caller.
+    fn postprocess_fields(&mut self, record: &mut ByteRecord) -> Result<()> {
+        if self.state.trim.should_trim_fields() {
+            record.trim();
+        }
+        if self.state.normalize_newlines {
+            record.normalize_newlines();
+        }
+        if self.state.collapse_whitespace {
+            record.collapse_whitespace();
+        }
+        #[cfg(feature = "base64")]
+        if !self.state.base64_columns.is_empty() {
+            let out = &mut self.state.transformed;
+            out.clear();
+            for (i, field) in record.iter().enumerate() {
+                if !self.state.base64_columns.contains(&i) {
+                    out.push_field(field);
+                    continue;
+                }
+                match base64::decode(field) {
+                    Ok(decoded) => out.push_field(&decoded),
+                    Err(err) => {
+                        return Err(Error::new(ErrorKind::Base64 {
+                            pos: record.position().cloned(),
+                            field: i as u64,
+                            err,
+                        }))
+                    }
+                }
+            }
+            out.set_position(record.position().cloned());
+            out.set_quoted(record.take_quoted());
+            std::mem::swap(record, out);
+        }
+        if let Some(ref transform) = self.state.transform_field {
+            let mut transform = transform.0.lock().unwrap();
+            let out = &mut self.state.transformed;
+            out.clear();
+            for (i, field) in record.iter().enumerate() {
+                out.push_field(&transform(i, field));
+            }
+            out.set_position(record.position().cloned());
+            out.set_quoted(record.take_quoted());
+            std::mem::swap(record, out);
+        }
+        #[cfg(feature = "regex")]
+        for &(i, ref pattern) in &self.state.column_patterns {
+            let field = match record.get(i) {
+                Some(field) => field,
+                None => continue,
+            };
+            let matched = match std::str::from_utf8(field) {
+                Ok(field) => pattern.is_match(field),
+                Err(_) => false,
+            };
+            if !matched {
+                return Err(Error::new(ErrorKind::PatternMismatch {
+                    pos: record.position().cloned(),
+                    field_index: i as u64,
+                    value: String::from_utf8_lossy(field).into_owned(),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a byte record from the underlying CSV reader, without accounting
+    /// for headers, and keep only its selected columns.
+    fn poll_read_byte_record_impl(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        let ok = ready!(self.poll_read_byte_record_footed(cx, record))?;
+        if ok {
+            self.state.select_columns(record)?;
+        }
+        Poll::Ready(Ok(ok))
+    }
+
+    /// Read a byte record from the underlying CSV reader, without accounting
+    /// for headers.
+    ///
+    /// When a footer is expected, every record is held back until the next
+    /// one is read, and the last one is verified as the footer.
+    fn poll_read_byte_record_footed(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        if self.state.expect_footer.is_none() {
+            return self.poll_read_byte_record_unheld(cx, record);
+        }
+        loop {
+            if !ready!(self.poll_read_byte_record_unheld(cx, record))? {
+                break;
+            }
+            let sum = self.state.record_sum.unwrap_or_else(|| self.state.checksum.value());
+            let read = std::mem::take(record);
+            if let Some((held, _)) = self.state.held.replace((read, sum)) {
+                *record = held;
+                return Poll::Ready(self.release_held(record));
+            }
+        }
+        // In multi-table mode, the last record of a table is not the footer.
+        if self.state.table_done {
+            if let Some((held, _)) = self.state.held.take() {
+                *record = held;
+                return Poll::Ready(self.release_held(record));
+            }
+            return Poll::Ready(Ok(false));
+        }
+        Poll::Ready(self.verify_footer().map(|()| false))
+    }
+
+    fn release_held(&mut self, record: &ByteRecord) -> Result<bool> {
+        self.state.released += 1;
+        self.state.check_field_count(record)?;
+        Ok(true)
+    }
+
+    /// Verify the held record as the footer, once the end of the data is
+    /// reached.
+    fn verify_footer(&mut self) -> Result<()> {
+        if self.state.footer_checked {
+            return Ok(());
+        }
+        self.state.footer_checked = true;
+        let spec = self.state.expect_footer.as_ref().unwrap();
+        let (pos, msg) = match self.state.held.take() {
+            Some((footer, sum)) => {
+                // Header rows are not counted as records.
+                let records =
+                    self.state.released.saturating_sub(self.state.header_rows);
+                match spec.verify(&footer, records, sum) {
+                    None => return Ok(()),
+                    Some(msg) => (footer.position().cloned(), msg),
+                }
+            }
+            None => (None, "is missing".to_string()),
+        };
+        Err(Error::new(ErrorKind::FooterMismatch { pos, msg }))
+    }
+
+    fn poll_read_byte_record_unheld(
+        &mut self,
+        cx: &mut Context,
+        record: &mut ByteRecord,
+    ) -> Poll<Result<bool>> {
+        if self.state.sep_pending {
+            ready!(self.poll_sep_directive(cx))?;
+        }
+        if !self.state.reading {
+            self.state.partial.clear();
+            self.state.partial.set_position(Some(self.state.cur_pos.clone()));
+            self.state.partial.set_quoted(None);
+            self.state.partial_len = (0, 0);
+            self.state.line.clear();
+            self.state.raw.clear();
+            let at_end = self.state.table_done || match self.state.eof {
+                ReaderEofState::Eof => true,
+                ReaderEofState::IOError => self.state.end_on_io_error,
+                ReaderEofState::NotEof => false,
+            };
+            if at_end {
+                std::mem::swap(record, &mut self.state.partial);
+                return Poll::Ready(Ok(false));
+            }
+            self.state.check_total_bytes(self.state.cur_pos.byte())?;
+            // In multi-table mode, a blank line after the first row of
+            // a table ends it.
+            if self.state.multi_table
+                && self.state.headers.is_some()
+                && self.state.fixed_width.is_none()
+                && ready!(self.poll_blank_line(cx))? == Some(true)
+            {
+                self.state.table_done = true;
+                std::mem::swap(record, &mut self.state.partial);
+                return Poll::Ready(Ok(false));
+            }
+            self.state.reading = true;
+            self.state.record_sum = None;
+        }
+        let result = if self.state.fixed_width.is_some() {
+            ready!(self.poll_read_fixed_width_record(cx))
+        } else {
+            ready!(self.poll_read_delimited_record(cx))
+        };
+        #[cfg(feature = "timeout")]
+        {
+            if let Err(ErrorKind::IdleTimeout) = result.as_ref().map_err(Error::kind) {
+                // Keep the partial record, so the read can be resumed.
+                return Poll::Ready(result);
+            }
+        }
+        self.state.reading = false;
+        std::mem::swap(record, &mut self.state.partial);
+        if let Ok(true) = result {
+            if let Some((open, close)) = self.state.field_wrappers {
+                record.strip_wrappers(open, close);
+            }
+            if let Some(quote) = self.state.track_quoted {
+                self.mark_quoted(quote, record);
+            }
+            self.state.add_record(record)?;
+        }
+        Poll::Ready(result)
+    }
+
+    /// Record which fields of `record`, just read, were quoted.
+    fn mark_quoted(&mut self, quote: u8, record: &mut ByteRecord) {
+        let start = record.position().map_or(0, Position::byte);
+        let raw = &self.state.raw[..];
+        let spans = &mut self.state.spans;
+        spans.clear();
+        field_spans(&mut self.span_core, raw, start, self.state.terminator, spans);
+        let quoted = |&(at, len): &(u64, u64)| len > 0 && raw[(at - start) as usize] == quote;
+        let flags = if self.state.collapse_delimiters {
+            // Empty fields were dropped, whether quoted or not.
+            spans
+                .iter()
+                .filter(|span| !(span.1 == 0 || (span.1 == 2 && quoted(span))))
+                .map(quoted)
+                .collect()
+        } else {
+            spans.iter().map(quoted).collect()
+        };
+        record.set_quoted(Some(flags));
+    }
+
+    /// Look for a `sep=` line at the start of the data or table. If there is
+    /// one, it is skipped and the parsers are rebuilt with the delimiter it
+    /// declares. Otherwise, the bytes consumed while looking are left in
+    /// `pushback`, and the parsers are rebuilt with the configured delimiter.
+    fn poll_sep_directive(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        let delimiter = loop {
+            let next = {
+                let buf = ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?;
+                buf.first().cloned()
+            };
+            let seen = &self.state.pushback;
+            let matched = match (seen.len(), next) {
+                (0..=3, Some(b)) => b == b"sep="[seen.len()],
+                (4, Some(b)) => b != b'\r' && b != b'\n',
+                (5, Some(b)) => b == b'\r' || b == b'\n',
+                (6, Some(b)) => seen[5] == b'\r' && b == b'\n',
+                _ => false,
+            };
+            if matched {
+                Pin::new(&mut self.rdr).consume(1);
+                self.state.pushback.push(next.unwrap());
+                if self.state.pushback.len() < 7 {
+                    continue;
                 }
-                return result;
             }
-        } else if self.state.trim.should_trim_fields() {
-            record.trim();
+            // A directive needs a delimiter and, unless the data ends
+            // there, a line terminator.
+            let seen = &self.state.pushback;
+            if seen.len() == 6 || seen.len() == 7 || (seen.len() == 5 && next.is_none()) {
+                break Some(seen[4]);
+            }
+            break None;
+        };
+        self.state.sep_pending = false;
+        let settings = self.state.sep_directive.as_ref().unwrap();
+        let mut line = self.core.line();
+        *self.core = settings.build(delimiter.unwrap_or(settings.delimiter));
+        *self.span_core = settings.build(delimiter.unwrap_or(settings.delimiter));
+        if let Some(ref mut strict) = self.state.strict {
+            strict.set_delimiter(delimiter.unwrap_or(settings.delimiter));
         }
-        Ok(ok)
+        if delimiter.is_some() {
+            line += 1;
+            let byte = self.state.cur_pos.byte() + self.state.pushback.len() as u64;
+            self.state.cur_pos.set_byte(byte).set_line(line);
+            self.state.pushback.clear();
+        }
+        self.core.set_line(line);
+        Poll::Ready(Ok(()))
     }
 
-    /// Read a byte record from the underlying CSV reader, without accounting
-    /// for headers.
-    #[inline(always)]
-    async fn read_byte_record_impl(
-        &mut self,
-        record: &mut ByteRecord,
-    ) -> Result<bool> {
+    /// Continue parsing delimited data into the partial record.
+    fn poll_read_delimited_record(&mut self, cx: &mut Context) -> Poll<Result<bool>> {
         use csv_core::ReadRecordResult::*;
 
-        record.clear();
-        record.set_position(Some(self.state.cur_pos.clone()));
-        match self.state.eof {
-            ReaderEofState::Eof => return Ok(false),
-            ReaderEofState::IOError => {
-                if self.state.end_on_io_error { return Ok(false) }
-            },
-            ReaderEofState::NotEof => {}
+        if self.state.strict_lookahead {
+            // The record is complete, but ended with a `\r` at the end of
+            // the buffer.
+            let next = {
+                let buf = ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?;
+                buf.first().cloned()
+            };
+            self.state.strict_lookahead = false;
+            if let Some(ref mut strict) = self.state.strict {
+                strict.lookahead(next);
+            }
+            self.check_strict()?;
+            return Poll::Ready(Ok(true));
+        }
+        if self.state.oversized.is_some() {
+            ready!(self.poll_discard_record(cx))?;
+            return Poll::Ready(Err(self.state.oversized.take().unwrap()));
         }
-        let (mut outlen, mut endlen) = (0, 0);
         loop {
+            let pushed = std::mem::take(&mut self.state.pushback);
             let (res, nin, nout, nend) = {
-                if let Err(err) = FillBuf::new(&mut self.rdr).await {
-                    self.state.eof = ReaderEofState::IOError;
-                    return Err(err.into());
-                }
-                let (fields, ends) = record.as_parts();
-                self.core.read_record(
-                    self.rdr.buffer(),
+                let buf = if pushed.is_empty() {
+                    ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?
+                } else {
+                    &pushed[..]
+                };
+                let (outlen, endlen) = self.state.partial_len;
+                let (fields, ends) = self.state.partial.as_parts();
+                let (res, nin, nout, nend) = self.core.read_record(
+                    buf,
                     &mut fields[outlen..],
                     &mut ends[endlen..],
-                )
+                );
+                if self.state.capture_raw || self.state.track_quoted.is_some() {
+                    self.state.raw.extend_from_slice(&buf[..nin]);
+                }
+                self.state.add_checksum(&buf[..nin]);
+                if let Some(ref mut strict) = self.state.strict {
+                    strict.update(&buf[..nin]);
+                    if res == Record && strict.needs_lookahead() && nin < buf.len() {
+                        strict.lookahead(Some(buf[nin]));
+                    }
+                }
+                if self.state.resumable {
+                    if self.state.pending.is_empty() {
+                        self.state.pending_pos = self.state.cur_pos.clone();
+                    }
+                    self.state.pending.extend_from_slice(&buf[..nin]);
+                }
+                if nin > 0 {
+                    self.state.last_byte = buf[nin - 1];
+                }
+                (res, nin, nout, nend)
             };
-            Pin::new(&mut self.rdr).consume(nin);
+            if pushed.is_empty() {
+                Pin::new(&mut self.rdr).consume(nin);
+            } else {
+                self.state.pushback = pushed[nin..].to_vec();
+            }
             let byte = self.state.cur_pos.byte();
             self.state
                 .cur_pos
                 .set_byte(byte + nin as u64)
                 .set_line(self.core.line());
-            outlen += nout;
-            endlen += nend;
+            self.state.check_total_bytes(self.state.cur_pos.byte())?;
+            self.state.partial_len.0 += nout;
+            self.state.partial_len.1 += nend;
+            let size = self
+                .state
+                .check_field_sizes(self.state.partial_len.1 - nend)
+                .and_then(|()| self.state.check_record_size());
+            if let Err(err) = size {
+                if res == Record || res == End {
+                    self.state.pending.clear();
+                    return Poll::Ready(Err(err));
+                }
+                // The error is reported once the rest of the record is
+                // discarded, so the next read starts at the next record.
+                self.state.oversized = Some(err);
+                return self.poll_read_delimited_record(cx);
+            }
             match res {
                 InputEmpty => continue,
                 OutputFull => {
-                    record.expand_fields();
+                    self.state.partial.expand_fields();
                     continue;
                 }
                 OutputEndsFull => {
-                    record.expand_ends();
+                    self.state.partial.expand_ends();
                     continue;
                 }
                 Record => {
-                    record.set_len(endlen);
-                    self.state.add_record(record)?;
-                    return Ok(true);
+                    self.state.partial.set_len(self.state.partial_len.1);
+                    if self.state.collapse_delimiters {
+                        self.state.partial.remove_empty_fields();
+                        if self.state.partial.is_empty() {
+                            // Only delimiters, which is a blank line.
+                            self.state.partial.set_position(Some(self.state.cur_pos.clone()));
+                            self.state.partial_len = (0, 0);
+                            continue;
+                        }
+                    }
+                    self.state.pending.clear();
+                    let lookahead = match self.state.strict {
+                        Some(ref strict) => strict.needs_lookahead(),
+                        None => false,
+                    };
+                    if lookahead {
+                        self.state.strict_lookahead = true;
+                        return self.poll_read_delimited_record(cx);
+                    }
+                    self.check_strict()?;
+                    return Poll::Ready(Ok(true));
                 }
                 End => {
                     self.state.eof = ReaderEofState::Eof;
-                    return Ok(false);
+                    self.state.pending.clear();
+                    if let Some(ref mut strict) = self.state.strict {
+                        strict.finish();
+                    }
+                    self.check_strict()?;
+                    return Poll::Ready(Ok(false));
+                }
+            }
+        }
+    }
+
+    /// Consume the rest of the record being read, without keeping its
+    /// fields.
+    fn poll_discard_record(&mut self, cx: &mut Context) -> Poll<Result<()>> {
+        use csv_core::ReadRecordResult::*;
+
+        let mut fields = [0u8; 1024];
+        let mut ends = [0usize; 64];
+        loop {
+            let pushed = std::mem::take(&mut self.state.pushback);
+            let (res, nin) = {
+                let buf = if pushed.is_empty() {
+                    ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?
+                } else {
+                    &pushed[..]
+                };
+                let (res, nin, _, _) = self.core.read_record(buf, &mut fields, &mut ends);
+                self.state.add_checksum(&buf[..nin]);
+                if let Some(ref mut strict) = self.state.strict {
+                    strict.update(&buf[..nin]);
+                    if res == Record && strict.needs_lookahead() && nin < buf.len() {
+                        strict.lookahead(Some(buf[nin]));
+                    }
+                }
+                if nin > 0 {
+                    self.state.last_byte = buf[nin - 1];
+                }
+                (res, nin)
+            };
+            if pushed.is_empty() {
+                Pin::new(&mut self.rdr).consume(nin);
+            } else {
+                self.state.pushback = pushed[nin..].to_vec();
+            }
+            let byte = self.state.cur_pos.byte();
+            self.state
+                .cur_pos
+                .set_byte(byte + nin as u64)
+                .set_line(self.core.line());
+            self.state.check_total_bytes(self.state.cur_pos.byte())?;
+            match res {
+                InputEmpty | OutputFull | OutputEndsFull => continue,
+                Record | End => {
+                    if res == End {
+                        self.state.eof = ReaderEofState::Eof;
+                    }
+                    self.state.pending.clear();
+                    if let Some(ref mut strict) = self.state.strict {
+                        strict.take_violation();
+                    }
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+    }
+
+    /// Report the first violation of RFC 4180 found in `strict` mode since
+    /// the last record.
+    fn check_strict(&mut self) -> Result<()> {
+        let reason = match self.state.strict {
+            Some(ref mut strict) => strict.take_violation(),
+            None => None,
+        };
+        match reason {
+            Some(reason) => Err(Error::new(ErrorKind::Strict {
+                pos: self.state.partial.position().cloned(),
+                reason,
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Consume a blank line at the start of a record. Returns `None` at the
+    /// end of the input, and otherwise whether a blank line was consumed.
+    fn poll_blank_line(&mut self, cx: &mut Context) -> Poll<Result<Option<bool>>> {
+        loop {
+            let byte = {
+                let buf = ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?;
+                match buf.first() {
+                    Some(&byte) => byte,
+                    None => return Poll::Ready(Ok(None)),
+                }
+            };
+            let (is_term, crlf) = match self.state.terminator {
+                Terminator::Any(t) => (byte == t, false),
+                _ => (byte == b'\r' || byte == b'\n', true),
+            };
+            if !is_term {
+                return Poll::Ready(Ok(Some(false)));
+            }
+            // The `\n` of a `\r\n` pair still belongs to the previous line.
+            let ends_crlf = crlf && byte == b'\n' && self.state.last_byte == b'\r';
+            Pin::new(&mut self.rdr).consume(1);
+            self.state.add_checksum(&[byte]);
+            self.state.last_byte = byte;
+            if byte == b'\n' {
+                self.core.set_line(self.core.line() + 1);
+            }
+            let pos = self.state.cur_pos.byte();
+            self.state.cur_pos.set_byte(pos + 1).set_line(self.core.line());
+            self.state.check_total_bytes(pos + 1)?;
+            if !ends_crlf {
+                return Poll::Ready(Ok(Some(true)));
+            }
+        }
+    }
+
+    /// Skip the rest of the current table and any blank lines after it.
+    /// Returns false when there are no more tables.
+    pub async fn next_table(&mut self) -> Result<bool> {
+        if self.state.table_started {
+            let mut record = ByteRecord::new();
+            loop {
+                match self.read_byte_record(&mut record).await {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(err) if self.is_done() => return Err(err),
+                    Err(_) => continue,
+                }
+            }
+            self.state.headers = None;
+            self.state.first = false;
+            self.state.first_field_count = None;
+            self.state.table_done = false;
+            self.state.sep_pending = self.state.sep_directive.is_some();
+        }
+        self.state.table_started = true;
+        future::poll_fn(|cx| self.poll_skip_blank_lines(cx)).await
+    }
+
+    fn poll_skip_blank_lines(&mut self, cx: &mut Context) -> Poll<Result<bool>> {
+        if self.is_done() {
+            return Poll::Ready(Ok(false));
+        }
+        loop {
+            match ready!(self.poll_blank_line(cx))? {
+                Some(true) => continue,
+                Some(false) => return Poll::Ready(Ok(true)),
+                None => return Poll::Ready(Ok(false)),
+            }
+        }
+    }
+
+    /// Continue reading a fixed-width line into the partial record.
+    fn poll_read_fixed_width_record(&mut self, cx: &mut Context) -> Poll<Result<bool>> {
+        loop {
+            let (nin, complete) = {
+                let buf = ready!(poll_fill_buf(&mut self.rdr, &mut self.state, cx))?;
+                if self.state.resumable {
+                    if self.state.pending.is_empty() {
+                        self.state.pending_pos = self.state.cur_pos.clone();
+                    }
+                    let end = buf.iter().position(|&b| b == b'\n').map_or(buf.len(), |i| i + 1);
+                    self.state.pending.extend_from_slice(&buf[..end]);
                 }
+                let (nin, complete) = match buf.iter().position(|&b| b == b'\n') {
+                    Some(i) => {
+                        self.state.line.extend_from_slice(&buf[..=i]);
+                        (i + 1, true)
+                    }
+                    None => {
+                        self.state.line.extend_from_slice(buf);
+                        (buf.len(), buf.is_empty())
+                    }
+                };
+                self.state.add_checksum(&buf[..nin]);
+                (nin, complete)
+            };
+            Pin::new(&mut self.rdr).consume(nin);
+            let read = self.state.cur_pos.byte() + self.state.line.len() as u64;
+            self.state.check_total_bytes(read)?;
+            if !complete {
+                continue;
+            }
+            self.state.pending.clear();
+            if self.state.line.is_empty() {
+                self.state.eof = ReaderEofState::Eof;
+                return Poll::Ready(Ok(false));
+            }
+            let line = &mut self.state.line;
+            if self.state.capture_raw {
+                self.state.raw.clear();
+                self.state.raw.extend_from_slice(line);
+            }
+            let byte = self.state.cur_pos.byte();
+            let line_no = self.state.cur_pos.line();
+            self.state.cur_pos.set_byte(byte + line.len() as u64).set_line(line_no + 1);
+            if line.last() == Some(&b'\n') {
+                line.pop();
             }
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if line.is_empty() {
+                self.state.partial.set_position(Some(self.state.cur_pos.clone()));
+                continue;
+            }
+            let mut start = 0;
+            for width in self.state.fixed_width.as_ref().unwrap() {
+                let end = (start + width).min(line.len());
+                self.state.partial.push_field(&line[start.min(end)..end]);
+                start += width;
+            }
+            line.clear();
+            return Poll::Ready(Ok(true));
         }
     }
 
@@ -983,10 +3956,35 @@ where
         &self.state.cur_pos
     }
 
+    /// Capture what is needed to resume parsing with another reader.
+    pub fn parser_state(&self) -> ParserState {
+        let headers = match self.state.headers {
+            Some(ref headers) if self.state.has_headers => Some(headers.byte_record.clone()),
+            _ => None,
+        };
+        let position = if self.state.pending.is_empty() {
+            self.state.cur_pos.clone()
+        } else {
+            self.state.pending_pos.clone()
+        };
+        ParserState { pending: self.state.pending.clone(), position, headers }
+    }
+
+    /// Continue parsing from a state captured by `parser_state`. The
+    /// pending bytes of the state must be read before any other data.
+    fn restore_state(&mut self, state: ParserState) {
+        if let Some(headers) = state.headers {
+            self.set_byte_headers(headers);
+        }
+        self.state.first = true;
+        self.core.set_line(state.position.line());
+        self.state.cur_pos = state.position;
+    }
+
     /// Returns true if and only if this reader has been exhausted.
     ///
     pub fn is_done(&self) -> bool {
-        self.state.eof != ReaderEofState::NotEof
+        self.state.eof != ReaderEofState::NotEof && self.state.replay.is_empty()
     }
 
     /// Returns true if and only if this reader has been configured to
@@ -1014,6 +4012,66 @@ where
     }
 }
 
+/// A reader over a single table of a multi-table CSV document.
+///
+/// This is returned by `AsyncReader::next_table`. Every table has its own
+/// header row, which is handled according to the `has_headers` setting of
+/// the reader it was created from.
+///
+/// The lifetime parameter `'r` refers to the lifetime of the underlying
+/// CSV `Reader`.
+#[derive(Debug)]
+pub struct TableReader<'r, R> {
+    rdr: &'r mut AsyncReaderImpl<R>,
+}
+
+impl<'r, R> TableReader<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin + 'r,
+{
+    fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
+        TableReader { rdr }
+    }
+
+    /// Returns a reference to the first row of this table.
+    pub async fn headers(&mut self) -> Result<&StringRecord> {
+        self.rdr.headers().await
+    }
+
+    /// Returns a reference to the first row of this table as raw bytes.
+    pub async fn byte_headers(&mut self) -> Result<&ByteRecord> {
+        self.rdr.byte_headers().await
+    }
+
+    /// Read a single row of this table into the given record. Returns false
+    /// at the end of the table.
+    pub async fn read_record(&mut self, record: &mut StringRecord) -> Result<bool> {
+        self.rdr.read_record(record).await
+    }
+
+    /// Read a single row of this table into the given byte record. Returns
+    /// false at the end of the table.
+    pub async fn read_byte_record(&mut self, record: &mut ByteRecord) -> Result<bool> {
+        self.rdr.read_byte_record(record).await
+    }
+
+    /// Returns a borrowed stream over the records of this table as strings.
+    pub fn records(&mut self) -> StringRecordsStream<'_, R> {
+        StringRecordsStream::new(self.rdr)
+    }
+
+    /// Returns a borrowed stream over the records of this table as raw
+    /// bytes.
+    pub fn byte_records(&mut self) -> ByteRecordsStream<'_, R> {
+        ByteRecordsStream::new(self.rdr)
+    }
+
+    /// Return the current position of the underlying CSV reader.
+    pub fn position(&self) -> &Position {
+        self.rdr.position()
+    }
+}
+
 #[cfg(not(feature = "tokio"))]
 impl<R: io::AsyncRead + io::AsyncSeek + std::marker::Unpin> AsyncReaderImpl<R> {
     /// Seeks the underlying reader to the position given.
@@ -1021,11 +4079,20 @@ impl<R: io::AsyncRead + io::AsyncSeek + std::marker::Unpin> AsyncReaderImpl<R> {
     pub async fn seek(&mut self, pos: Position) -> Result<()> {
         self.byte_headers().await?;
         self.state.seeked = true;
-        if pos.byte() == self.state.cur_pos.byte() {
+        // Bytes pushed back were consumed from the underlying reader, which
+        // is then ahead of the current position.
+        if pos.byte() == self.state.cur_pos.byte() && self.state.pushback.is_empty() {
+            // A stream dropped in the middle of a record may have left the
+            // parser inside a field, so the next read has to start afresh.
+            self.core.reset();
+            self.discard_read_ahead();
+            self.core.set_line(pos.line());
+            self.state.cur_pos = pos;
             return Ok(());
         }
         self.rdr.seek(io::SeekFrom::Start(pos.byte())).await?;
         self.core.reset();
+        self.discard_read_ahead();
         self.core.set_line(pos.line());
         self.state.cur_pos = pos;
         self.state.eof = ReaderEofState::NotEof;
@@ -1043,11 +4110,104 @@ impl<R: io::AsyncRead + io::AsyncSeek + std::marker::Unpin> AsyncReaderImpl<R> {
         self.state.seeked = true;
         self.rdr.seek(seek_from).await?;
         self.core.reset();
+        self.discard_read_ahead();
         self.core.set_line(pos.line());
         self.state.cur_pos = pos;
         self.state.eof = ReaderEofState::NotEof;
         Ok(())
     }
+
+    /// Drop the data read ahead of the old position, i.e. the records kept
+    /// by `is_header_only` and `infer_schema`, bytes pushed back, and the
+    /// bytes of a partial record.
+    ///
+    /// The footer is then looked for afresh, counting the records read from
+    /// the new position on, and a table that has ended is read again from
+    /// the new position up to the next blank line. Strict checking starts
+    /// over at the new position.
+    fn discard_read_ahead(&mut self) {
+        self.state.reading = false;
+        self.state.table_done = false;
+        self.state.strict_lookahead = false;
+        if let Some(ref mut strict) = self.state.strict {
+            strict.restart();
+        }
+        self.state.replay.clear();
+        self.state.pushback.clear();
+        self.state.pending.clear();
+        self.state.oversized = None;
+        self.state.held = None;
+        self.state.released = 0;
+        self.state.header_rows = 0;
+        self.state.footer_checked = false;
+        self.state.checksum = Adler32::default();
+        self.state.record_sum = None;
+        self.state.last_byte = 0;
+    }
+
+    /// Reads the last `n` records, by parsing forward from a window at the
+    /// end of the data that grows until it holds enough records.
+    pub async fn tail(&mut self, n: usize) -> Result<Vec<StringRecord>> {
+        use futures::io::AsyncBufReadExt;
+
+        self.byte_headers().await?;
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let mut records = VecDeque::with_capacity(n);
+        let terminator = self.state.terminator;
+        let is_terminator = |b: u8| match terminator {
+            Terminator::Any(t) => b == t,
+            _ => b == b'\r' || b == b'\n',
+        };
+        let end = self.rdr.seek(io::SeekFrom::End(0)).await?;
+        let mut window = 128 * (n as u64 + 1);
+        loop {
+            let start = end.saturating_sub(window);
+            if start == 0 {
+                self.seek_raw(io::SeekFrom::Start(0), Position::new()).await?;
+                if self.state.has_headers {
+                    let mut headers = ByteRecord::new();
+                    self.read_byte_record(&mut headers).await?;
+                }
+            } else {
+                // The window starts after the first line terminator at or
+                // after `start - 1`.
+                self.rdr.seek(io::SeekFrom::Start(start - 1)).await?;
+                let mut boundary = start - 1;
+                loop {
+                    let buf = self.rdr.fill_buf().await?;
+                    if buf.is_empty() {
+                        break;
+                    }
+                    let (len, found) = match buf.iter().position(|&b| is_terminator(b)) {
+                        Some(i) => (i + 1, true),
+                        None => (buf.len(), false),
+                    };
+                    self.rdr.consume_unpin(len);
+                    boundary += len as u64;
+                    if found {
+                        break;
+                    }
+                }
+                let mut pos = Position::new();
+                pos.set_byte(boundary);
+                self.seek_raw(io::SeekFrom::Start(boundary), pos).await?;
+            }
+            records.clear();
+            let mut record = StringRecord::new();
+            while self.read_record(&mut record).await? {
+                if records.len() == n {
+                    records.pop_front();
+                }
+                records.push_back(record.clone());
+            }
+            if records.len() == n || start == 0 {
+                return Ok(records.into_iter().collect());
+            }
+            window *= 2;
+        }
+    }
 }
 
 #[cfg(feature = "tokio")]
@@ -1086,124 +4246,586 @@ impl<R: io::AsyncRead + io::AsyncSeek + std::marker::Unpin> AsyncReaderImpl<R> {
 
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
-
-async fn read_record_borrowed<'r, R>(
+
+/// A borrowed stream of records as strings.
+///
+/// The lifetime parameter `'r` refers to the lifetime of the underlying
+/// CSV `Reader`.
+pub struct StringRecordsStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    rdr: &'r mut AsyncReaderImpl<R>,
+    rec: StringRecord,
+    done: bool,
+}
+
+impl<'r, R> StringRecordsStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
+        Self { rdr, rec: StringRecord::new(), done: false }
+    }
+}
+
+impl<'r, R> Stream for StringRecordsStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    type Item = Result<StringRecord>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_record(cx, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok(this.rdr.yield_string_record(&mut this.rec)))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An item of the stream returned by `AsyncReader::records_sectioned`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectionEvent {
+    /// The records following this event have `field_count` fields. This
+    /// is always the first item.
+    NewSection {
+        /// The number of fields of the records in the section.
+        field_count: usize,
+    },
+    /// A record of the current section.
+    Record(StringRecord),
+}
+
+/// An item of a stream that delivers the header row in-band, ahead of the
+/// records.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordOrHeader {
+    /// The header row. This is always the first item.
+    Header(StringRecord),
+    /// A record following the header row.
+    Record(StringRecord),
+}
+
+/// A borrowed stream yielding the header row, followed by all records as
+/// strings.
+///
+/// The lifetime parameter `'r` refers to the lifetime of the underlying
+/// CSV `Reader`.
+pub struct StringRecordsWithHeaderStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    rdr: &'r mut AsyncReaderImpl<R>,
+    rec: StringRecord,
+    header_done: bool,
+    done: bool,
+}
+
+impl<'r, R> StringRecordsWithHeaderStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
+        Self { rdr, rec: StringRecord::new(), header_done: false, done: false }
+    }
+}
+
+impl<'r, R> Stream for StringRecordsWithHeaderStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    type Item = Result<RecordOrHeader>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if !this.header_done {
+            let result = ready!(this.rdr.poll_read_headers(cx))
+                .and_then(|()| this.rdr.loaded_headers().cloned());
+            this.header_done = true;
+            return Poll::Ready(Some(result.map(RecordOrHeader::Header)));
+        }
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_record(cx, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok(RecordOrHeader::Record(this.rec.clone())))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A borrowed stream of records as strings, each paired with its raw text.
+///
+/// The lifetime parameter `'r` refers to the lifetime of the underlying
+/// CSV `Reader`.
+pub struct StringRecordsWithRawStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    rdr: &'r mut AsyncReaderImpl<R>,
+    rec: StringRecord,
+    raw: String,
+    done: bool,
+}
+
+impl<'r, R> StringRecordsWithRawStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
+        Self { rdr, rec: StringRecord::new(), raw: String::new(), done: false }
+    }
+}
+
+impl<'r, R> Stream for StringRecordsWithRawStream<'r, R>
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    type Item = Result<(String, StringRecord)>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_record_with_raw(cx, &mut this.raw, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok((this.raw.clone(), this.rec.clone())))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+/// Returns a borrowed stream of runs of consecutive records with the same
+/// value in column `col`.
+fn group_by_column<'r, R>(
     rdr: &'r mut AsyncReaderImpl<R>,
-    mut rec: StringRecord,
-) -> (Option<Result<StringRecord>>, &'r mut AsyncReaderImpl<R>, StringRecord)
+    col: usize,
+) -> impl Stream<Item = Result<(String, Vec<StringRecord>)>> + Unpin + 'r
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(Ok(rec.clone())),
-        Ok(false) => None,
-    };
+    use futures::stream::{self, StreamExt};
 
-    (result, rdr, rec)
+    let key_of = move |record: &StringRecord| record.get(col).unwrap_or("").to_string();
+    let group: Option<(String, Vec<StringRecord>)> = None;
+    let state = (StringRecordsStream::new(rdr).fuse(), group);
+    Box::pin(stream::unfold(state, move |(mut records, mut group)| async move {
+        loop {
+            match records.next().await {
+                Some(Ok(record)) => {
+                    let key = key_of(&record);
+                    match group {
+                        Some((ref current, ref mut members)) if *current == key => {
+                            members.push(record);
+                        }
+                        Some(done) => {
+                            return Some((Ok(done), (records, Some((key, vec![record])))));
+                        }
+                        None => group = Some((key, vec![record])),
+                    }
+                }
+                Some(Err(err)) => return Some((Err(err), (records, group))),
+                None => return group.map(|done| (Ok(done), (records, None))),
+            }
+        }
+    }))
 }
 
-/// A borrowed stream of records as strings.
-///
-/// The lifetime parameter `'r` refers to the lifetime of the underlying
-/// CSV `Reader`.
-pub struct StringRecordsStream<'r, R>
+/// Returns a borrowed stream of batches of up to `batch` records, each
+/// deserialized in parallel on the rayon thread pool.
+#[cfg(all(feature = "with_serde", feature = "rayon"))]
+fn par_deserialize<'r, R, D>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    batch: usize,
+) -> impl Stream<Item = Result<Vec<D>>> + Unpin + 'r
+where
+    R: io::AsyncRead + std::marker::Unpin,
+    D: DeserializeOwned + Send + 'static,
+{
+    use futures::channel::oneshot;
+    use futures::stream;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    assert!(batch > 0, "batch size must be at least 1");
+    let by_name = rdr.deserializes_by_name();
+    let failed: Option<Error> = None;
+    Box::pin(stream::unfold((rdr, failed, false), move |(rdr, mut failed, mut done)| async move {
+        // An error ending the previous batch is reported after it.
+        if let Some(err) = failed.take() {
+            return Some((Err(err), (rdr, None, done)));
+        }
+        if done {
+            return None;
+        }
+        let headers = if by_name {
+            match rdr.headers().await {
+                Ok(headers) => Some(headers.clone()),
+                Err(err) => return Some((Err(err), (rdr, None, true))),
+            }
+        } else {
+            None
+        };
+        let mut records = Vec::with_capacity(batch);
+        while records.len() < batch {
+            let mut record = StringRecord::new();
+            match rdr.read_record(&mut record).await {
+                Ok(true) => records.push(record),
+                Ok(false) => {
+                    done = true;
+                    break;
+                }
+                Err(err) => {
+                    failed = Some(err);
+                    break;
+                }
+            }
+        }
+        if records.is_empty() {
+            return failed.map(|err| (Err(err), (rdr, None, done)));
+        }
+        let (tx, rx) = oneshot::channel();
+        rayon::spawn(move || {
+            let result = records
+                .into_par_iter()
+                .map(|record| record.deserialize(headers.as_ref()))
+                .collect();
+            let _ = tx.send(result);
+        });
+        let result = rx.await.expect("deserializing a batch panicked");
+        Some((result, (rdr, failed, done)))
+    }))
+}
+
+/// Returns a borrowed stream of Arrow record batches of up to `batch`
+/// records.
+#[cfg(feature = "arrow")]
+fn record_batches<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    schema: arrow_schema::Schema,
+    batch: usize,
+) -> impl Stream<Item = Result<arrow_array::RecordBatch>> + Unpin + 'r
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                        Output = (
-                            Option<Result<StringRecord>>,
-                            &'r mut AsyncReaderImpl<R>,
-                            StringRecord,
-                        ),
-                    > + 'r,
-            >,
-        >,
-    >,
+    use futures::stream;
+
+    assert!(batch > 0, "batch size must be at least 1");
+    let schema = Arc::new(schema);
+    let failed: Option<Error> = None;
+    Box::pin(stream::unfold((rdr, failed, false), move |(rdr, mut failed, mut done)| {
+        let schema = schema.clone();
+        async move {
+            // An error ending the previous batch is reported after it.
+            if let Some(err) = failed.take() {
+                return Some((Err(err), (rdr, None, done)));
+            }
+            if done {
+                return None;
+            }
+            let mut records = Vec::with_capacity(batch);
+            while records.len() < batch {
+                let mut record = ByteRecord::new();
+                match rdr.read_byte_record(&mut record).await {
+                    Ok(true) => records.push(record),
+                    Ok(false) => {
+                        done = true;
+                        break;
+                    }
+                    Err(err) => {
+                        failed = Some(err);
+                        break;
+                    }
+                }
+            }
+            if records.is_empty() {
+                return failed.map(|err| (Err(err), (rdr, None, done)));
+            }
+            Some((arrow::record_batch(&schema, &records), (rdr, failed, done)))
+        }
+    }))
 }
 
-impl<'r, R> StringRecordsStream<'r, R>
+/// Returns a borrowed stream of records, announcing every change in their
+/// number of fields with a `SectionEvent::NewSection`.
+fn records_sectioned<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+) -> impl Stream<Item = Result<SectionEvent>> + Unpin + 'r
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
-        Self {
-            fut: Some(Pin::from(Box::new(read_record_borrowed(
-                rdr,
-                StringRecord::new(),
-            )))),
+    use futures::stream;
+
+    let state: (_, Option<usize>, Option<StringRecord>) = (rdr, None, None);
+    Box::pin(stream::unfold(state, |(rdr, field_count, pending)| async move {
+        if let Some(record) = pending {
+            return Some((Ok(SectionEvent::Record(record)), (rdr, field_count, None)));
+        }
+        if rdr.state.has_headers {
+            if let Err(err) = rdr.byte_headers().await {
+                return Some((Err(err), (rdr, field_count, None)));
+            }
+        }
+        // Any record may start a new section, so none is checked against
+        // the length of the record before.
+        rdr.state.first_field_count = None;
+        let mut record = StringRecord::new();
+        match rdr.read_record(&mut record).await {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => return Some((Err(err), (rdr, field_count, None))),
+        }
+        if field_count == Some(record.len()) {
+            return Some((Ok(SectionEvent::Record(record)), (rdr, field_count, None)));
         }
+        let event = SectionEvent::NewSection { field_count: record.len() };
+        Some((Ok(event), (rdr, Some(record.len()), Some(record))))
+    }))
+}
+
+/// Parses `field` as a JSON number, if it is written exactly the way the
+/// number is, so converting it loses nothing.
+#[cfg(feature = "serde_json")]
+fn canonical_number(field: &str) -> Option<serde_json::Number> {
+    use serde_json::Number;
+
+    let n = if let Ok(n) = field.parse::<u64>() {
+        Number::from(n)
+    } else if let Ok(n) = field.parse::<i64>() {
+        Number::from(n)
+    } else {
+        Number::from_f64(field.parse().ok()?)?
+    };
+    if n.to_string() == field {
+        Some(n)
+    } else {
+        None
     }
 }
 
-impl<'r, R> Stream for StringRecordsStream<'r, R>
+/// Returns the indices of the columns with the given names in `headers`.
+fn header_indices(headers: &ByteRecord, names: &[String]) -> Result<Vec<usize>> {
+    names
+        .iter()
+        .map(|name| match headers.iter().position(|field| field == name.as_bytes()) {
+            Some(i) => Ok(i),
+            None => Err(Error::new(ErrorKind::UnknownHeader {
+                pos: headers.position().cloned(),
+                name: name.clone(),
+            })),
+        })
+        .collect()
+}
+
+/// Returns a borrowed stream of records narrowed down to the columns with
+/// the given header names, in their order.
+fn records_projected<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    names: &[&str],
+) -> impl Stream<Item = Result<StringRecord>> + Unpin + 'r
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    type Item = Result<StringRecord>;
+    use futures::stream;
 
-    fn poll_next(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context,
-    ) -> Poll<Option<Self::Item>> {
-        match self.fut.as_mut().unwrap().as_mut().poll(cx) {
-            Poll::Ready((result, rdr, rec)) => {
-                if result.is_some() {
-                    self.fut = Some(Pin::from(Box::new(
-                        read_record_borrowed(rdr, rec),
-                    )));
-                } else {
-                    self.fut = None;
+    let names: Vec<String> = names.iter().map(|&name| name.to_string()).collect();
+    let state: (_, _, Option<Vec<usize>>, bool) = (rdr, names, None, false);
+    Box::pin(stream::unfold(state, |(rdr, names, columns, done)| async move {
+        if done {
+            return None;
+        }
+        let columns = match columns {
+            Some(columns) => columns,
+            None => {
+                let resolved = match rdr.byte_headers().await {
+                    Ok(headers) => header_indices(headers, &names),
+                    Err(err) => Err(err),
+                };
+                match resolved {
+                    Ok(columns) => columns,
+                    // Without the columns, no record can be projected.
+                    Err(err) => return Some((Err(err), (rdr, names, None, true))),
                 }
+            }
+        };
+        let mut record = StringRecord::new();
+        match rdr.read_record(&mut record).await {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => return Some((Err(err), (rdr, names, Some(columns), false))),
+        }
+        let mut projected: StringRecord =
+            columns.iter().map(|&i| record.get(i).unwrap_or("")).collect();
+        projected.set_position(record.position().cloned());
+        Some((Ok(projected), (rdr, names, Some(columns), false)))
+    }))
+}
+
+/// Returns a borrowed stream of records, ending once `deadline` has passed.
+fn records_until<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    deadline: Instant,
+) -> impl Stream<Item = Result<StringRecord>> + Unpin + 'r
+where
+    R: io::AsyncRead + std::marker::Unpin
+{
+    use futures::stream;
+
+    Box::pin(stream::unfold(rdr, move |rdr| async move {
+        // The deadline is only checked between records, so a record is
+        // never left half read.
+        if Instant::now() >= deadline {
+            return None;
+        }
+        let mut record = StringRecord::new();
+        match rdr.read_record(&mut record).await {
+            Ok(true) => Some((Ok(record), rdr)),
+            Ok(false) => None,
+            Err(err) => Some((Err(err), rdr)),
+        }
+    }))
+}
+
+/// Returns a borrowed stream of the values `f` maps byte records to,
+/// skipping the records it maps to `None`.
+fn byte_records_filter_map<'r, R, F, T>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    f: F,
+) -> impl Stream<Item = Result<T>> + Unpin + 'r
+where
+    R: io::AsyncRead + std::marker::Unpin,
+    F: FnMut(&ByteRecord) -> Result<Option<T>> + 'r,
+    T: 'r,
+{
+    use futures::stream;
 
-                Poll::Ready(result)
+    // One record is reused throughout, so dropped rows cost nothing.
+    let state = (rdr, ByteRecord::new(), f);
+    Box::pin(stream::unfold(state, |(rdr, mut record, mut f)| async move {
+        loop {
+            match rdr.read_byte_record(&mut record).await {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(err) => return Some((Err(err), (rdr, record, f))),
+            }
+            match f(&record) {
+                Ok(Some(value)) => return Some((Ok(value), (rdr, record, f))),
+                Ok(None) => continue,
+                Err(err) => return Some((Err(err), (rdr, record, f))),
             }
-            Poll::Pending => Poll::Pending,
         }
-    }
+    }))
 }
 
-//-//////////////////////////////////////////////////////////////////////////////////////////////
-//-//////////////////////////////////////////////////////////////////////////////////////////////
+/// Returns a borrowed stream of the values `f` maps records to, threading
+/// the state `state` through all calls.
+fn scan_records<'r, R, S, T, F>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    state: S,
+    f: F,
+) -> impl Stream<Item = Result<T>> + Unpin + 'r
+where
+    R: io::AsyncRead + std::marker::Unpin,
+    S: 'r,
+    T: 'r,
+    F: FnMut(&mut S, StringRecord) -> Result<T> + 'r,
+{
+    use futures::stream;
+
+    Box::pin(stream::unfold((rdr, state, f), |(rdr, mut state, mut f)| async move {
+        let mut record = StringRecord::new();
+        let item = match rdr.read_record(&mut record).await {
+            Ok(true) => f(&mut state, record),
+            Ok(false) => return None,
+            Err(err) => Err(err),
+        };
+        Some((item, (rdr, state, f)))
+    }))
+}
+
+/// Returns a borrowed stream of the records, each with the hash of its
+/// fields.
+fn records_hashed<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+) -> impl Stream<Item = Result<(u64, StringRecord)>> + Unpin + 'r
+where
+    R: io::AsyncRead + std::marker::Unpin,
+{
+    use futures::stream;
+
+    Box::pin(stream::unfold(rdr, |rdr| async move {
+        let mut record = StringRecord::new();
+        match rdr.read_record(&mut record).await {
+            Ok(true) => Some((Ok((record.as_byte_record().content_hash(), record)), rdr)),
+            Ok(false) => None,
+            Err(err) => Some((Err(err), rdr)),
+        }
+    }))
+}
 
-async fn read_record<R>(
-    mut rdr: AsyncReaderImpl<R>,
-    mut rec: StringRecord,
-) -> (Option<Result<StringRecord>>, AsyncReaderImpl<R>, StringRecord)
+/// Returns a borrowed stream of the records with `min..=max` fields.
+fn records_with_arity<'r, R>(
+    rdr: &'r mut AsyncReaderImpl<R>,
+    min: usize,
+    max: usize,
+) -> impl Stream<Item = Result<StringRecord>> + Unpin + 'r
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(Ok(rec.clone())),
-        Ok(false) => None,
-    };
+    use futures::stream::StreamExt;
 
-    (result, rdr, rec)
+    Box::pin(StringRecordsStream::new(rdr).filter(move |result| {
+        future::ready(match result {
+            Ok(record) => min <= record.len() && record.len() <= max,
+            Err(_) => true,
+        })
+    }))
 }
 
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+
 /// An owned stream of records as strings.
 pub struct StringRecordsIntoStream<'r, R>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<StringRecord>>,
-                        AsyncReaderImpl<R>,
-                        StringRecord,
-                    ),
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: AsyncReaderImpl<R>,
+    rec: StringRecord,
+    done: bool,
+    _lifetime: PhantomData<&'r ()>,
 }
 
 impl<'r, R> StringRecordsIntoStream<'r, R>
@@ -1211,12 +4833,7 @@ where
     R: io::AsyncRead + std::marker::Unpin + 'r
 {
     fn new(rdr: AsyncReaderImpl<R>) -> Self {
-        Self {
-            fut: Some(Pin::from(Box::new(read_record(
-                rdr,
-                StringRecord::new(),
-            )))),
-        }
+        Self { rdr, rec: StringRecord::new(), done: false, _lifetime: PhantomData }
     }
 }
 
@@ -1230,18 +4847,17 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        match self.fut.as_mut().unwrap().as_mut().poll(cx) {
-            Poll::Ready((result, rdr, rec)) => {
-                if result.is_some() {
-                    self.fut =
-                        Some(Pin::from(Box::new(read_record(rdr, rec))));
-                } else {
-                    self.fut = None;
-                }
-
-                Poll::Ready(result)
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_record(cx, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok(this.rdr.yield_string_record(&mut this.rec)))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
             }
-            Poll::Pending => Poll::Pending,
+            Err(err) => Poll::Ready(Some(Err(err))),
         }
     }
 }
@@ -1249,22 +4865,6 @@ where
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
-async fn read_byte_record_borrowed<'r, R>(
-    rdr: &'r mut AsyncReaderImpl<R>,
-    mut rec: ByteRecord,
-) -> (Option<Result<ByteRecord>>, &'r mut AsyncReaderImpl<R>, ByteRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin,
-{
-    let result = match rdr.read_byte_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(Ok(rec.clone())),
-        Ok(false) => None,
-    };
-
-    (result, rdr, rec)
-}
-
 /// A borrowed stream of records as raw bytes.
 ///
 /// The lifetime parameter `'r` refers to the lifetime of the underlying
@@ -1273,19 +4873,9 @@ pub struct ByteRecordsStream<'r, R>
 where
     R: io::AsyncRead + std::marker::Unpin,
 {
-    fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                        Output = (
-                            Option<Result<ByteRecord>>,
-                            &'r mut AsyncReaderImpl<R>,
-                            ByteRecord,
-                        ),
-                    > + 'r,
-            >,
-        >,
-    >,
+    rdr: &'r mut AsyncReaderImpl<R>,
+    rec: ByteRecord,
+    done: bool,
 }
 
 impl<'r, R> ByteRecordsStream<'r, R>
@@ -1293,12 +4883,7 @@ where
     R: io::AsyncRead + std::marker::Unpin + 'r,
 {
     fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
-        Self {
-            fut: Some(Pin::from(Box::new(read_byte_record_borrowed(
-                rdr,
-                ByteRecord::new(),
-            )))),
-        }
+        Self { rdr, rec: ByteRecord::new(), done: false }
     }
 }
 
@@ -1312,19 +4897,17 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        match self.fut.as_mut().unwrap().as_mut().poll(cx) {
-            Poll::Ready((result, rdr, rec)) => {
-                if result.is_some() {
-                    self.fut = Some(Pin::from(Box::new(
-                        read_byte_record_borrowed(rdr, rec),
-                    )));
-                } else {
-                    self.fut = None;
-                }
-
-                Poll::Ready(result)
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_byte_record(cx, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok(this.rdr.yield_byte_record(&mut this.rec)))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
             }
-            Poll::Pending => Poll::Pending,
+            Err(err) => Poll::Ready(Some(Err(err))),
         }
     }
 }
@@ -1332,40 +4915,15 @@ where
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
-async fn read_byte_record<R>(
-    mut rdr: AsyncReaderImpl<R>,
-    mut rec: ByteRecord,
-) -> (Option<Result<ByteRecord>>, AsyncReaderImpl<R>, ByteRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin
-{
-    let result = match rdr.read_byte_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(Ok(rec.clone())),
-        Ok(false) => None,
-    };
-
-    (result, rdr, rec)
-}
-
 /// An owned stream of records as raw bytes.
 pub struct ByteRecordsIntoStream<'r, R>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<ByteRecord>>,
-                        AsyncReaderImpl<R>,
-                        ByteRecord,
-                    ),
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: AsyncReaderImpl<R>,
+    rec: ByteRecord,
+    done: bool,
+    _lifetime: PhantomData<&'r ()>,
 }
 
 impl<'r, R> ByteRecordsIntoStream<'r, R>
@@ -1373,12 +4931,7 @@ where
     R: io::AsyncRead + std::marker::Unpin + 'r
 {
     fn new(rdr: AsyncReaderImpl<R>) -> Self {
-        Self {
-            fut: Some(Pin::from(Box::new(read_byte_record(
-                rdr,
-                ByteRecord::new(),
-            )))),
-        }
+        Self { rdr, rec: ByteRecord::new(), done: false, _lifetime: PhantomData }
     }
 }
 
@@ -1392,18 +4945,17 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        match self.fut.as_mut().unwrap().as_mut().poll(cx) {
-            Poll::Ready((result, rdr, rec)) => {
-                if result.is_some() {
-                    self.fut =
-                        Some(Pin::from(Box::new(read_byte_record(rdr, rec))));
-                } else {
-                    self.fut = None;
-                }
-
-                Poll::Ready(result)
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.rdr.poll_read_byte_record(cx, &mut this.rec)) {
+            Ok(true) => Poll::Ready(Some(Ok(this.rdr.yield_byte_record(&mut this.rec)))),
+            Ok(false) => {
+                this.done = true;
+                Poll::Ready(None)
             }
-            Poll::Pending => Poll::Pending,
+            Err(err) => Poll::Ready(Some(Err(err))),
         }
     }
 }
@@ -1413,22 +4965,65 @@ where
 
 cfg_if::cfg_if! {
 if #[cfg(feature = "with_serde")] {
-    
-async fn deserialize_record_borrowed<'r, R, D: DeserializeOwned>(
-    rdr: &'r mut AsyncReaderImpl<R>,
+
+/// State shared by the streams of deserialized records.
+struct DeserializeState<D> {
+    /// Set until the header row has been read.
+    read_headers: bool,
     headers: Option<StringRecord>,
-    mut rec: StringRecord,
-) -> (Option<Result<D>>, &'r mut AsyncReaderImpl<R>, Option<StringRecord>, StringRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin
-{
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(rec.deserialize(headers.as_ref())),
-        Ok(false) => None,
-    };
+    rec: StringRecord,
+    /// The position before the record being read.
+    pos: Option<Position>,
+    done: bool,
+    _record: PhantomData<fn() -> D>,
+}
+
+impl<D: DeserializeOwned> DeserializeState<D> {
+    fn new(has_headers: bool) -> Self {
+        Self {
+            read_headers: has_headers,
+            headers: None,
+            rec: StringRecord::new(),
+            pos: None,
+            done: false,
+            _record: PhantomData,
+        }
+    }
 
-    (result, rdr, headers, rec)
+    fn poll_next<R>(
+        &mut self,
+        rdr: &mut AsyncReaderImpl<R>,
+        cx: &mut Context,
+    ) -> Poll<Option<(Result<D>, Position)>>
+    where
+        R: io::AsyncRead + std::marker::Unpin
+    {
+        if self.read_headers {
+            let result = ready!(rdr.poll_read_headers(cx))
+                .and_then(|()| rdr.loaded_headers().cloned());
+            self.read_headers = false;
+            match result {
+                Ok(headers) => self.headers = Some(headers),
+                Err(err) => return Poll::Ready(Some((Err(err), rdr.position().clone()))),
+            }
+        }
+        if self.done {
+            return Poll::Ready(None);
+        }
+        if self.pos.is_none() {
+            self.pos = Some(rdr.position().clone());
+        }
+        let result = ready!(rdr.poll_read_record(cx, &mut self.rec));
+        let pos = self.pos.take().unwrap();
+        match result {
+            Ok(true) => Poll::Ready(Some((self.rec.deserialize(self.headers.as_ref()), pos))),
+            Ok(false) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Err(err) => Poll::Ready(Some((Err(err), pos))),
+        }
+    }
 }
 
 /// A borrowed stream of deserialized records.
@@ -1439,32 +5034,8 @@ pub struct DeserializeRecordsStream<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    header_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Result<StringRecord>,
-                        &'r mut AsyncReaderImpl<R>,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
-    rec_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<D>>,
-                        &'r mut AsyncReaderImpl<R>,
-                        Option<StringRecord>,
-                        StringRecord,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: &'r mut AsyncReaderImpl<R>,
+    state: DeserializeState<D>,
 }
 
 impl<'r, R, D: DeserializeOwned + 'r> DeserializeRecordsStream<'r, R, D>
@@ -1472,22 +5043,8 @@ where
     R: io::AsyncRead + std::marker::Unpin
 {
     fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
-        let has_headers = rdr.has_headers();
-        if has_headers {
-            Self {
-                header_fut: Some(Pin::from(Box::new(
-                    async{ (rdr.headers().await.and_then(|h| Ok(h.clone())), rdr) }
-                ))),
-                rec_fut: None,
-            }
-        } else {
-            Self {
-                header_fut: None,
-                rec_fut: Some(Pin::from(Box::new(
-                    deserialize_record_borrowed(rdr, None, StringRecord::new())
-                ))),
-            }
-        }
+        let state = DeserializeState::new(rdr.deserializes_by_name());
+        Self { rdr, state }
     }
 }
 
@@ -1501,64 +5058,14 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        if let Some(header_fut) = &mut self.header_fut {
-            match header_fut.as_mut().poll(cx) {
-                Poll::Ready((Ok(headers), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_borrowed(rdr, Some(headers), StringRecord::new()),
-                    )));
-                    cx.waker().clone().wake();
-                    Poll::Pending
-                },
-                Poll::Ready((Err(err), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_borrowed(rdr, None, StringRecord::new()),
-                    )));
-                    Poll::Ready(Some(Err(err)))
-                },
-                Poll::Pending => Poll::Pending,
-            }
-        } else {
-            match self.rec_fut.as_mut().unwrap().as_mut().poll(cx) {
-                Poll::Ready((result, rdr, headers, rec)) => {
-                    if result.is_some() {
-                        self.rec_fut = Some(Pin::from(Box::new(
-                            deserialize_record_borrowed(rdr, headers, rec),
-                        )));
-                    } else {
-                        self.rec_fut = None;
-                    }
-                    Poll::Ready(result)
-                }
-                Poll::Pending => Poll::Pending,
-            }
-        }
+        let this = &mut *self;
+        this.state.poll_next(this.rdr, cx).map(|item| item.map(|(result, _)| result))
     }
 }
 
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
-async fn deserialize_record_with_pos_borrowed<'r, R, D: DeserializeOwned>(
-    rdr: &'r mut AsyncReaderImpl<R>,
-    headers: Option<StringRecord>,
-    mut rec: StringRecord,
-) -> (Option<Result<D>>, Position, &'r mut AsyncReaderImpl<R>, Option<StringRecord>, StringRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin
-{
-    let pos = rdr.position().clone();
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(rec.deserialize(headers.as_ref())),
-        Ok(false) => None,
-    };
-
-    (result, pos, rdr, headers, rec)
-}
-
 /// A borrowed stream of pairs: deserialized records and position in stream before reading record.
 ///
 /// The lifetime parameter `'r` refers to the lifetime of the underlying CSV `Reader`.
@@ -1567,33 +5074,8 @@ pub struct DeserializeRecordsStreamPos<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    header_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Result<StringRecord>,
-                        &'r mut AsyncReaderImpl<R>,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
-    rec_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<D>>,
-                        Position,
-                        &'r mut AsyncReaderImpl<R>,
-                        Option<StringRecord>,
-                        StringRecord,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: &'r mut AsyncReaderImpl<R>,
+    state: DeserializeState<D>,
 }
 
 impl<'r, R, D: DeserializeOwned + 'r> DeserializeRecordsStreamPos<'r, R, D>
@@ -1601,22 +5083,8 @@ where
     R: io::AsyncRead + std::marker::Unpin
 {
     fn new(rdr: &'r mut AsyncReaderImpl<R>) -> Self {
-        let has_headers = rdr.has_headers();
-        if has_headers {
-            Self {
-                header_fut: Some(Pin::from(Box::new(
-                    async{ (rdr.headers().await.and_then(|h| Ok(h.clone())), rdr) }
-                ))),
-                rec_fut: None,
-            }
-        } else {
-            Self {
-                header_fut: None,
-                rec_fut: Some(Pin::from(Box::new(
-                    deserialize_record_with_pos_borrowed(rdr, None, StringRecord::new())
-                ))),
-            }
-        }
+        let state = DeserializeState::new(rdr.deserializes_by_name());
+        Self { rdr, state }
     }
 }
 
@@ -1630,65 +5098,14 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        if let Some(header_fut) = &mut self.header_fut {
-            match header_fut.as_mut().poll(cx) {
-                Poll::Ready((Ok(headers), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_with_pos_borrowed(rdr, Some(headers), StringRecord::new()),
-                    )));
-                    cx.waker().clone().wake();
-                    Poll::Pending
-                },
-                Poll::Ready((Err(err), rdr)) => {
-                    self.header_fut = None;
-                    let pos = rdr.position().clone();
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_with_pos_borrowed(rdr, None, StringRecord::new()),
-                    )));
-                    Poll::Ready(Some((Err(err), pos)))
-                },
-                Poll::Pending => Poll::Pending,
-            }
-        } else {
-            match self.rec_fut.as_mut().unwrap().as_mut().poll(cx) {
-                Poll::Ready((result, pos, rdr, headers, rec)) => {
-                    if let Some(result) = result {
-                        self.rec_fut = Some(Pin::from(Box::new(
-                            deserialize_record_with_pos_borrowed(rdr, headers, rec),
-                        )));
-                        Poll::Ready(Some((result, pos)))
-                    } else {
-                        self.rec_fut = None;
-                        Poll::Ready(None)
-                    }
-                }
-                Poll::Pending => Poll::Pending,
-            }
-        }
+        let this = &mut *self;
+        this.state.poll_next(this.rdr, cx)
     }
 }
     
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
-async fn deserialize_record<R, D: DeserializeOwned>(
-    mut rdr: AsyncReaderImpl<R>,
-    headers: Option<StringRecord>,
-    mut rec: StringRecord,
-) -> (Option<Result<D>>, AsyncReaderImpl<R>, Option<StringRecord>, StringRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin
-{
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(rec.deserialize(headers.as_ref())),
-        Ok(false) => None,
-    };
-
-    (result, rdr, headers, rec)
-}
-
 /// A owned stream of deserialized records.
 ///
 /// The lifetime parameter `'r` refers to the lifetime of the underlying CSV `Reader`.
@@ -1697,55 +5114,18 @@ pub struct DeserializeRecordsIntoStream<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    header_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Result<StringRecord>,
-                        AsyncReaderImpl<R>,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
-    rec_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<D>>,
-                        AsyncReaderImpl<R>,
-                        Option<StringRecord>,
-                        StringRecord,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: AsyncReaderImpl<R>,
+    state: DeserializeState<D>,
+    _lifetime: PhantomData<&'r ()>,
 }
 
 impl<'r, R, D: DeserializeOwned + 'r> DeserializeRecordsIntoStream<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin + 'r
 {
-    fn new(mut rdr: AsyncReaderImpl<R>) -> Self {
-        let has_headers = rdr.has_headers();
-        if has_headers {
-            Self {
-                header_fut: Some(Pin::from(Box::new(
-                    async{ (rdr.headers().await.and_then(|h| Ok(h.clone())), rdr) }
-                ))),
-                rec_fut: None,
-            }
-        } else {
-            Self {
-                header_fut: None,
-                rec_fut: Some(Pin::from(Box::new(
-                    deserialize_record(rdr, None, StringRecord::new())
-                ))),
-            }
-        }
+    fn new(rdr: AsyncReaderImpl<R>) -> Self {
+        let state = DeserializeState::new(rdr.deserializes_by_name());
+        Self { rdr, state, _lifetime: PhantomData }
     }
 }
 
@@ -1759,64 +5139,14 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        if let Some(header_fut) = &mut self.header_fut {
-            match header_fut.as_mut().poll(cx) {
-                Poll::Ready((Ok(headers), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record(rdr, Some(headers), StringRecord::new()),
-                    )));
-                    cx.waker().clone().wake();
-                    Poll::Pending
-                },
-                Poll::Ready((Err(err), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record(rdr, None, StringRecord::new()),
-                    )));
-                    Poll::Ready(Some(Err(err)))
-                },
-                Poll::Pending => Poll::Pending,
-            }
-        } else {
-            match self.rec_fut.as_mut().unwrap().as_mut().poll(cx) {
-                Poll::Ready((result, rdr, headers, rec)) => {
-                    if result.is_some() {
-                        self.rec_fut = Some(Pin::from(Box::new(
-                            deserialize_record(rdr, headers, rec),
-                        )));
-                    } else {
-                        self.rec_fut = None;
-                    }
-                    Poll::Ready(result)
-                }
-                Poll::Pending => Poll::Pending,
-            }
-        }
+        let this = &mut *self;
+        this.state.poll_next(&mut this.rdr, cx).map(|item| item.map(|(result, _)| result))
     }
 }
 
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
-async fn deserialize_record_with_pos<R, D: DeserializeOwned>(
-    mut rdr: AsyncReaderImpl<R>,
-    headers: Option<StringRecord>,
-    mut rec: StringRecord,
-) -> (Option<Result<D>>, Position, AsyncReaderImpl<R>, Option<StringRecord>, StringRecord)
-where
-    R: io::AsyncRead + std::marker::Unpin
-{
-    let pos = rdr.position().clone();
-    let result = match rdr.read_record(&mut rec).await {
-        Err(err) => Some(Err(err)),
-        Ok(true) => Some(rec.deserialize(headers.as_ref())),
-        Ok(false) => None,
-    };
-
-    (result, pos, rdr, headers, rec)
-}
-
 /// A owned stream of pairs: deserialized records and position in stream before reading record.
 ///
 /// The lifetime parameter `'r` refers to the lifetime of the underlying CSV `Reader`.
@@ -1825,56 +5155,18 @@ pub struct DeserializeRecordsIntoStreamPos<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin
 {
-    header_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Result<StringRecord>,
-                        AsyncReaderImpl<R>,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
-    rec_fut: Option<
-        Pin<
-            Box<
-                dyn Future<
-                    Output = (
-                        Option<Result<D>>,
-                        Position,
-                        AsyncReaderImpl<R>,
-                        Option<StringRecord>,
-                        StringRecord,
-                    )
-                > + 'r,
-            >,
-        >,
-    >,
+    rdr: AsyncReaderImpl<R>,
+    state: DeserializeState<D>,
+    _lifetime: PhantomData<&'r ()>,
 }
 
 impl<'r, R, D: DeserializeOwned + 'r> DeserializeRecordsIntoStreamPos<'r, R, D>
 where
     R: io::AsyncRead + std::marker::Unpin + 'r
 {
-    fn new(mut rdr: AsyncReaderImpl<R>) -> Self {
-        let has_headers = rdr.has_headers();
-        if has_headers {
-            Self {
-                header_fut: Some(Pin::from(Box::new(
-                    async{ (rdr.headers().await.and_then(|h| Ok(h.clone())), rdr) }
-                ))),
-                rec_fut: None,
-            }
-        } else {
-            Self {
-                header_fut: None,
-                rec_fut: Some(Pin::from(Box::new(
-                    deserialize_record_with_pos(rdr, None, StringRecord::new())
-                ))),
-            }
-        }
+    fn new(rdr: AsyncReaderImpl<R>) -> Self {
+        let state = DeserializeState::new(rdr.deserializes_by_name());
+        Self { rdr, state, _lifetime: PhantomData }
     }
 }
 
@@ -1888,44 +5180,9 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Self::Item>> {
-        if let Some(header_fut) = &mut self.header_fut {
-            match header_fut.as_mut().poll(cx) {
-                Poll::Ready((Ok(headers), rdr)) => {
-                    self.header_fut = None;
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_with_pos(rdr, Some(headers), StringRecord::new()),
-                    )));
-                    cx.waker().clone().wake();
-                    Poll::Pending
-                },
-                Poll::Ready((Err(err), rdr)) => {
-                    self.header_fut = None;
-                    let pos = rdr.position().clone();
-                    self.rec_fut = Some(Pin::from(Box::new(
-                        deserialize_record_with_pos(rdr, None, StringRecord::new()),
-                    )));
-                    Poll::Ready(Some((Err(err), pos)))
-                },
-                Poll::Pending => Poll::Pending,
-            }
-        } else {
-            match self.rec_fut.as_mut().unwrap().as_mut().poll(cx) {
-                Poll::Ready((result, pos, rdr, headers, rec)) => {
-                    if let Some(result) = result {
-                        self.rec_fut = Some(Pin::from(Box::new(
-                            deserialize_record_with_pos(rdr, headers, rec),
-                        )));
-                        Poll::Ready(Some((result, pos)))
-                    } else {
-                        self.rec_fut = None;
-                        Poll::Ready(None)
-                    }
-                }
-                Poll::Pending => Poll::Pending,
-            }
-        }
+        let this = &mut *self;
+        this.state.poll_next(&mut this.rdr, cx)
     }
 }
 
 }} // fi #[cfg(feature = "with_serde")]
-
@@ -0,0 +1,183 @@
+use crate::Terminator;
+
+/// Where the checker is within the syntax of a record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    /// At the start of a field.
+    StartField,
+    /// Within an unquoted field, holding only whitespace so far.
+    Blank,
+    /// Within an unquoted field.
+    Unquoted,
+    /// Within a quoted field.
+    Quoted,
+    /// After a quote within a quoted field, which either closes the field
+    /// or escapes another quote.
+    QuoteInQuoted,
+    /// After the closing quote of a quoted field.
+    Closed,
+    /// After a `\r` ending a record, which must be followed by `\n`.
+    CarriageReturn,
+    /// At a position seeked to, which may be right after the `\r` ending
+    /// the previous record.
+    Seeked,
+}
+
+/// Checks that the bytes of delimited records follow RFC 4180, while they
+/// are fed to the parser.
+///
+/// The checker is fed every byte the parser consumes, across records, and
+/// remembers the first violation of the current record. This is a check
+/// only: the parser still reads non-compliant data in its lenient way.
+#[derive(Clone, Debug)]
+pub(crate) struct StrictChecker {
+    delimiter: u8,
+    quote: u8,
+    /// The record terminator, or `None` for CRLF.
+    terminator: Option<u8>,
+    state: State,
+    violation: Option<String>,
+}
+
+impl StrictChecker {
+    pub(crate) fn new(delimiter: u8, quote: u8, terminator: Terminator) -> StrictChecker {
+        let terminator = match terminator {
+            Terminator::Any(b) => Some(b),
+            _ => None,
+        };
+        StrictChecker {
+            delimiter,
+            quote,
+            terminator,
+            state: State::StartField,
+            violation: None,
+        }
+    }
+
+    /// Check records separated by `delimiter` from now on.
+    pub(crate) fn set_delimiter(&mut self, delimiter: u8) {
+        self.delimiter = delimiter;
+    }
+
+    /// Check from a position seeked to. Positions of records in CRLF data
+    /// point at the `\n` of the previous terminator, so a leading `\n` is
+    /// accepted there.
+    #[cfg(not(feature = "tokio"))]
+    pub(crate) fn restart(&mut self) {
+        self.state = State::Seeked;
+        self.violation = None;
+    }
+
+    /// Check the next bytes consumed by the parser.
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.state = self.next(b);
+        }
+    }
+
+    /// Whether the last record ended with a `\r`, so the next byte must be
+    /// looked at to know if the record was properly terminated.
+    pub(crate) fn needs_lookahead(&self) -> bool {
+        self.state == State::CarriageReturn
+    }
+
+    /// Check the byte following a record ending with `\r`, without
+    /// consuming it, or `None` at the end of the data.
+    pub(crate) fn lookahead(&mut self, next: Option<u8>) {
+        if self.state == State::CarriageReturn && next != Some(b'\n') {
+            self.violate("record terminated by a bare \\r");
+            self.state = State::StartField;
+        }
+    }
+
+    /// Check that the data does not end in the middle of a terminator.
+    pub(crate) fn finish(&mut self) {
+        if self.state == State::CarriageReturn {
+            self.violate("record terminated by a bare \\r");
+        }
+        self.state = State::StartField;
+    }
+
+    /// Take the first violation found since the last call.
+    pub(crate) fn take_violation(&mut self) -> Option<String> {
+        self.violation.take()
+    }
+
+    fn violate(&mut self, reason: &str) {
+        if self.violation.is_none() {
+            self.violation = Some(reason.to_string());
+        }
+    }
+
+    fn next(&mut self, b: u8) -> State {
+        use self::State::*;
+
+        if self.state == CarriageReturn {
+            if b == b'\n' {
+                return StartField;
+            }
+            self.violate("record terminated by a bare \\r");
+            self.state = StartField;
+        }
+        if self.state == Seeked {
+            if b == b'\n' && self.terminator.is_none() {
+                return StartField;
+            }
+            self.state = StartField;
+        }
+        match self.state {
+            Quoted => {
+                if b == self.quote {
+                    QuoteInQuoted
+                } else {
+                    Quoted
+                }
+            }
+            QuoteInQuoted if b == self.quote => Quoted,
+            state => {
+                if b == self.delimiter {
+                    return StartField;
+                }
+                if let Some(next) = self.end_record(b) {
+                    return next;
+                }
+                match state {
+                    StartField if b == self.quote => Quoted,
+                    StartField | Blank if b == b' ' || b == b'\t' => Blank,
+                    Blank if b == self.quote => {
+                        self.violate("whitespace before a quoted field");
+                        Unquoted
+                    }
+                    QuoteInQuoted | Closed if b == b' ' || b == b'\t' => {
+                        self.violate("whitespace after a quoted field");
+                        Closed
+                    }
+                    QuoteInQuoted | Closed => {
+                        self.violate("quote not doubled within a quoted field");
+                        Closed
+                    }
+                    _ if b == self.quote => {
+                        self.violate("quote within an unquoted field");
+                        Unquoted
+                    }
+                    _ => Unquoted,
+                }
+            }
+        }
+    }
+
+    /// The state after `b`, if it ends a record outside of quotes.
+    fn end_record(&mut self, b: u8) -> Option<State> {
+        match self.terminator {
+            Some(t) if b == t => Some(State::StartField),
+            Some(_) => None,
+            None if b == b'\r' => Some(State::CarriageReturn),
+            None if b == b'\n' => {
+                self.violate("record terminated by a bare \\n");
+                Some(State::StartField)
+            }
+            None => None,
+        }
+    }
+}
+
@@ -5,6 +5,7 @@ use serde::Serialize;
 
 use crate::AsyncWriterBuilder;
 use crate::error::{IntoInnerError, Result};
+use crate::string_record::StringRecord;
 use super::mwtr_serde::MemWriter;
 
 impl AsyncWriterBuilder {
@@ -188,6 +189,11 @@ impl<W: AsyncWrite + Unpin> AsyncSerializer<W> {
     /// 2. If `has_headers` is `true` and the type contains field names, then
     ///    a header row is automatically generated.
     ///
+    /// 3. Fields are written in the order Serde visits them, which for derived
+    ///    `Serialize` implementations is the declaration order of struct
+    ///    fields. The header row and all records use the same order; fields
+    ///    are never sorted or otherwise reordered.
+    ///
     /// However, some container types cannot be serialized, and if
     /// `has_headers` is `true`, there are some additional restrictions on the
     /// types that can be serialized. See below for details.
@@ -275,6 +281,10 @@ impl<W: AsyncWrite + Unpin> AsyncSerializer<W> {
     /// | struct enum variant | `enum E { V { a: u8, b: bool } }` | `E::V { a: 5, b: true }` | *error* |
     /// | map | `BTreeMap<K, V>` | `BTreeMap::new()` | *error* |
     ///
+    /// With `AsyncWriterBuilder::tagged_enums` enabled, newtype, tuple and
+    /// struct enum variants are written as the variant name followed by the
+    /// payload, e.g. `E::A(5, true)` as `A,5,true`.
+    ///
     /// ## Structs
     ///
     /// Like the other containers, structs are flattened to their scalar
@@ -343,6 +353,88 @@ impl<W: AsyncWrite + Unpin> AsyncSerializer<W> {
         Ok(())
     }
 
+    /// Serialize a single record using Serde, with a header row given at
+    /// runtime.
+    ///
+    /// This is meant for records without field names, like vectors or
+    /// tuples, whose header row is only known at runtime. On the first call,
+    /// `headers` is written as the header row if `has_headers` is enabled,
+    /// and then `record` is serialized like by `serialize`, so its fields
+    /// need not be named. Later calls ignore `headers`. After `end_table`,
+    /// the next call writes `headers` again, starting the new table.
+    ///
+    /// Unless the writer is `flexible`, records must have as many fields as
+    /// the header row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncSerializer, StringRecord};
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let headers = StringRecord::from(vec!["city", "pop"]);
+    ///     let mut ser = AsyncSerializer::from_writer(vec![]);
+    ///     ser.serialize_with_headers(&headers, vec!["Boston", "4628910"]).await?;
+    ///     ser.serialize_with_headers(&headers, ("Concord", 42695)).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\nConcord,42695\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn serialize_with_headers<S: Serialize>(
+        &mut self,
+        headers: &StringRecord,
+        record: S,
+    ) -> Result<()> {
+        self.ser_wtr.serialize_with_headers(headers, record)?;
+        self.ser_wtr.flush()?;
+        self.asy_wtr.as_mut().unwrap().write_all(self.ser_wtr.data()).await?;
+        self.ser_wtr.clear();
+        Ok(())
+    }
+
+    /// End the current table by writing a blank line.
+    ///
+    /// When `has_headers` is enabled, the next serialized record starts the
+    /// new table with a header row of its own. This produces the multi-table
+    /// format read by `AsyncReaderBuilder::multi_table`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncSerializer;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct City { city: &'static str, pop: u64 }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Person { name: &'static str }
+    ///
+    /// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut ser = AsyncSerializer::from_writer(vec![]);
+    ///     ser.serialize(City { city: "Boston", pop: 4628910 }).await?;
+    ///     ser.end_table().await?;
+    ///     ser.serialize(Person { name: "Alice" }).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\n\nname\nAlice\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn end_table(&mut self) -> Result<()> {
+        self.ser_wtr.end_table()?;
+        self.ser_wtr.flush()?;
+        self.asy_wtr.as_mut().unwrap().write_all(self.ser_wtr.data()).await?;
+        self.ser_wtr.clear();
+        Ok(())
+    }
+
     /// Flushes the underlying asynchronous writer.
     pub async fn flush(&mut self) -> io::Result<()> {
         if let Some(ref mut asy_wtr) = self.asy_wtr {
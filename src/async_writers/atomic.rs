@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use tokio::fs::{self, File};
+use tokio::io::{self, AsyncWrite, AsyncWriteExt};
+
+/// Distinguishes temporary files created by the same process.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A file that is written under a temporary name and only appears at its
+/// target path once it is persisted.
+///
+/// This is the writer used by CSV writers created with
+/// [`AsyncWriterBuilder::from_path_atomic`](struct.AsyncWriterBuilder.html#method.from_path_atomic).
+/// The temporary file is created next to the target, so both are on the same
+/// file system and the final rename is atomic. If the file is dropped
+/// without being persisted, the temporary file is removed.
+///
+/// This is only available with the `tokio` feature.
+#[derive(Debug)]
+pub struct AtomicFile {
+    file: Option<File>,
+    temp: PathBuf,
+    target: PathBuf,
+    persisted: bool,
+}
+
+impl AtomicFile {
+    /// Create a temporary file next to `target`.
+    pub(crate) async fn create(target: &Path) -> io::Result<AtomicFile> {
+        let name = target.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "path does not name a file")
+        })?;
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp = target.with_file_name(temp_name);
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .await?;
+        Ok(AtomicFile {
+            file: Some(file),
+            temp,
+            target: target.to_path_buf(),
+            persisted: false,
+        })
+    }
+
+    /// The path the data is written to until the file is persisted.
+    pub fn temp_path(&self) -> &Path {
+        &self.temp
+    }
+
+    /// The path the file appears at once it is persisted.
+    pub fn target_path(&self) -> &Path {
+        &self.target
+    }
+
+    /// Flush all data to disk and rename the temporary file to the target
+    /// path, replacing any file already there.
+    ///
+    /// If this fails, the temporary file is removed and the target path is
+    /// left untouched.
+    pub async fn persist(mut self) -> io::Result<()> {
+        let mut file = self.file.take().expect("file is only taken when persisting");
+        file.flush().await?;
+        file.sync_all().await?;
+        drop(file);
+        fs::rename(&self.temp, &self.target).await?;
+        self.persisted = true;
+        Ok(())
+    }
+
+    fn file(&mut self) -> Pin<&mut File> {
+        Pin::new(self.file.as_mut().expect("file is only taken when persisting"))
+    }
+}
+
+impl AsyncWrite for AtomicFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().file().poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().file().poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().file().poll_shutdown(cx)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // Close the file before removing it, which some platforms require.
+        drop(self.file.take());
+        if !self.persisted {
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
@@ -1,11 +1,18 @@
+use std::pin::Pin;
 use std::result;
+use std::task::{Context, Poll};
 
 use futures::io::{self, AsyncWrite};
 
-use crate::AsyncWriterBuilder;
+use crate::{AsyncWriterBuilder, QuoteStyle};
 use crate::byte_record::ByteRecord;
-use crate::error::Result;
-use super::AsyncWriterImpl;
+use futures::sink::Sink;
+use futures::stream::Stream;
+
+use crate::error::{Error, IntoInnerError, Result};
+use crate::string_record::StringRecord;
+use super::{AsyncWriterImpl, ByteRecordSink};
+use super::tee::TeeWriter;
 
 impl AsyncWriterBuilder {
     /// Build a CSV writer from this configuration that writes data to `wtr`.
@@ -42,6 +49,14 @@ impl AsyncWriterBuilder {
     pub fn from_writer<W: AsyncWrite + Unpin>(&self, wtr: W) -> AsyncWriter<W> {
         AsyncWriter::new(self, wtr)
     }
+
+    /// Build a CSV writer from this configuration that writes the same data
+    /// to each of `wtrs`.
+    ///
+    /// See [`AsyncWriter::tee`](struct.AsyncWriter.html#method.tee).
+    pub fn create_tee_writer<W: AsyncWrite + Unpin>(&self, wtrs: Vec<W>) -> AsyncWriter<TeeWriter<W>> {
+        AsyncWriter::new(self, TeeWriter::new(wtrs))
+    }
 }
 
 /// A already configured CSV writer.
@@ -67,6 +82,12 @@ impl AsyncWriterBuilder {
 /// terminators instead of `\r\n` as specified by RFC 4180. Use the
 /// `terminator` method on `AsyncWriterBuilder` to set the terminator to `\r\n` if
 /// it's desired.
+///
+/// Rust values implementing serde's `Serialize`, like structs and tuples,
+/// are written with an [`AsyncSerializer`](struct.AsyncSerializer.html)
+/// instead, created by `AsyncWriterBuilder::create_serializer`. It writes the
+/// fields of a struct in declaration order, and its header row from their
+/// names.
 #[derive(Debug)]
 pub struct AsyncWriter<W: AsyncWrite + Unpin>(AsyncWriterImpl<W>);
 
@@ -137,6 +158,42 @@ impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
         self.0.write_record(record).await
     }
 
+    /// Write a single record, quoting it with `style` instead of the
+    /// quoting style of the writer.
+    ///
+    /// This lets single rows be quoted differently, e.g. a header row in
+    /// quotes followed by unquoted data rows. Options quoting fields
+    /// regardless of the style, like `quote_if`, still apply. The record is
+    /// never held back by a `sort_window`. If fields of a record were
+    /// already written with `write_field`, they are completed with the
+    /// writer's own quoting style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncWriter, QuoteStyle};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.write_record_with_style(&["city", "pop"], QuoteStyle::Always).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "\"city\",\"pop\"\nBoston,4628910\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn write_record_with_style<I, T>(&mut self, record: I, style: QuoteStyle) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.0.write_record_with_style(record, style).await
+    }
+
     /// Write a single `ByteRecord`.
     ///
     /// This method accepts a borrowed `ByteRecord` and writes its contents
@@ -172,14 +229,91 @@ impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
         self.0.write_byte_record(record).await
     }
 
+    /// Write a single `ByteRecord`, quoting the fields that were quoted in
+    /// the data it was read from.
+    ///
+    /// This is like `write_byte_record`, except that the fields for which
+    /// `ByteRecord::was_quoted` returns `Some(true)` are always written in
+    /// quotes, regardless of the quoting style. Other fields are quoted as
+    /// usual. Together with `AsyncReaderBuilder::track_quoted`, this lets data
+    /// be rewritten with its original quoting, e.g. keeping a quoted empty
+    /// field `""` apart from an unquoted one. A record without quoting
+    /// information is written like with `write_byte_record`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReaderBuilder, AsyncWriterBuilder, ByteRecord};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "\"name\",note\nBoston,\"\"\n";
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .has_headers(false)
+    ///         .track_quoted(true)
+    ///         .create_reader(data.as_bytes());
+    ///     let mut wtr = AsyncWriterBuilder::new().create_writer(vec![]);
+    ///     let mut record = ByteRecord::new();
+    ///     while rdr.read_byte_record(&mut record).await? {
+    ///         wtr.write_byte_record_preserving(&record).await?;
+    ///     }
+    ///
+    ///     let written = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(written, data);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn write_byte_record_preserving(&mut self, record: &ByteRecord) -> Result<()> {
+        self.0.write_byte_record_preserving(record).await
+    }
+
+    /// Write all records of a stream, e.g. one made by
+    /// `AsyncReader::into_byte_records`.
+    ///
+    /// Records are written to the internal buffer as they arrive, which is
+    /// written out whenever it is full and while waiting for the stream, so
+    /// copying many records does not wait on each of them. Writing stops at
+    /// the first error, whether it comes from the stream or from writing.
+    ///
+    /// Like other writes, this does not flush the underlying writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncReader, AsyncWriterBuilder};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let data = "city,pop\nBoston,4628910\nConcord,42695\n";
+    ///     let rdr = AsyncReader::from_reader(data.as_bytes());
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .delimiter(b';')
+    ///         .create_writer(vec![]);
+    ///     wtr.write_records(rdr.into_byte_records()).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "Boston;4628910\nConcord;42695\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write_records<S>(&mut self, records: S) -> Result<()>
+    where
+        S: Stream<Item = Result<ByteRecord>> + Unpin,
+    {
+        self.0.write_records(records).await
+    }
+
     /// Write a single field.
     ///
     /// One should prefer using `write_record` over this method. It is provided
     /// for cases where writing a field at a time is more convenient than
     /// writing a record at a time.
     ///
-    /// Note that if this API is used, `write_record` should be called with an
-    /// empty iterator to write a record terminator.
+    /// Note that if this API is used, `end_record` (or `write_record` with an
+    /// empty iterator) should be called to write a record terminator.
     ///
     /// # Example
     ///
@@ -209,26 +343,358 @@ impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
         self.0.write_field(field).await
     }
 
+    /// Write a record terminator, ending the record whose fields were
+    /// written with `write_field`.
+    ///
+    /// If no fields had been written, then a single empty field is written
+    /// before the terminator. Unlike `write_record` with an empty iterator,
+    /// this is never held back by a `sort_window`, which does not order
+    /// records written a field at a time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     for row in 1..3 {
+    ///         for column in 1..4 {
+    ///             wtr.write_field((row * column).to_string()).await?;
+    ///         }
+    ///         wtr.end_record().await?;
+    ///     }
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "1,2,3\n2,4,6\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn end_record(&mut self) -> Result<()> {
+        self.0.end_record().await
+    }
+
+    /// Read JSON objects, one per line (NDJSON), from `input` and write each
+    /// of them as a CSV record.
+    ///
+    /// The keys of the first object, in their order, become the header row,
+    /// which is written unless `has_headers` was disabled on the builder.
+    /// Field values are written in header order. Strings are written as they
+    /// are, `null` as an empty field, and numbers and booleans in their JSON
+    /// notation.
+    ///
+    /// Objects whose keys differ from those of the first object result in an
+    /// `ErrorKind::Json` error, unless the writer is `flexible`. Then missing
+    /// keys are written as empty fields and additional keys are appended.
+    ///
+    /// Arrays and nested objects are written JSON encoded when
+    /// `encode_nested` is enabled. Otherwise they result in an error.
+    ///
+    /// This method is available only with the `serde_json` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let input = r#"{"city":"Boston","pop":4628910}
+    /// {"city":"Concord","pop":42695}
+    /// "#;
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.from_ndjson(input.as_bytes(), false).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\nConcord,42695\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "serde_json")]
+    #[inline]
+    pub async fn from_ndjson<R: io::AsyncRead + Unpin>(
+        &mut self,
+        input: R,
+        encode_nested: bool,
+    ) -> Result<()> {
+        self.0.write_ndjson(input, encode_nested).await
+    }
+
+    /// Write `text` as a comment, e.g. to record where the data came from.
+    ///
+    /// Every line of `text` is written as a line of its own, started with
+    /// the character set by `AsyncWriterBuilder::comment`, so a reader with
+    /// the same `comment` option skips them. A record whose fields were
+    /// written with `write_field` is ended first.
+    ///
+    /// If no comment character was set, an I/O error of kind `InvalidInput`
+    /// is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .comment(Some(b'#'))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_comment("source: census\nyear: 2020").await?;
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "#source: census\n#year: 2020\ncity,pop\nBoston,4628910\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn write_comment(&mut self, text: &str) -> Result<()> {
+        self.0.write_comment(text).await
+    }
+
+    /// End the current table by writing a blank line.
+    ///
+    /// Records written afterwards start a new table, so they may differ in
+    /// length from the records before, and a new header row should be
+    /// written first. This produces the multi-table format read by
+    /// `AsyncReaderBuilder::multi_table`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///     wtr.end_table().await?;
+    ///     wtr.write_record(&["name"]).await?;
+    ///     wtr.write_record(&["Alice"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\n\nname\nAlice\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn end_table(&mut self) -> Result<()> {
+        self.0.end_table().await
+    }
+
+    /// Write a footer row describing the records written so far.
+    ///
+    /// The footer holds the number of data records written, not counting the
+    /// header row, and optionally a label and a checksum of all bytes written
+    /// before it. Its layout is set with `AsyncWriterBuilder::footer`, and
+    /// by default it only holds the record count. The footer row is not
+    /// checked against the length of the other records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///     wtr.write_footer().await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\n1\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn write_footer(&mut self) -> Result<()> {
+        self.0.write_footer().await
+    }
+
     /// Flush the contents of the internal buffer to the underlying writer.
     ///
     /// If there was a problem writing to the underlying writer, then an error
     /// is returned.
     ///
-    /// This finction is also called by writer destructor.
+    /// This function is also called by writer destructor, see `close`.
     #[inline]
     pub async fn flush(&mut self) -> io::Result<()> {
         self.0.flush().await
     }
 
+    /// Flush the writer like `flush`, then close the underlying writer.
+    ///
+    /// Since dropping cannot wait for the underlying writer, a writer
+    /// dropped with buffered data flushes it by blocking the thread,
+    /// ignoring errors, and in debug builds prints a warning about it.
+    /// Closing the writer before dropping it avoids this and reports errors.
+    /// Nothing should be written after closing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///     wtr.close().await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "a,b,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn close(&mut self) -> Result<()> {
+        self.0.close().await
+    }
+
+    /// Turn this writer into a sink of `ByteRecord`s.
+    ///
+    /// `AsyncWriter` itself is a sink of `StringRecord`s. The returned sink
+    /// writes byte records like `write_byte_record`, so byte oriented
+    /// pipelines can use `forward` or `send_all` without UTF-8 conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use futures::sink::SinkExt;
+    /// use csv_async::{AsyncWriter, ByteRecord};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut sink = AsyncWriter::from_writer(vec![]).into_byte_sink();
+    ///     sink.send(ByteRecord::from(vec![&b"a"[..], &b"b\xFF"[..]])).await?;
+    ///
+    ///     assert_eq!(sink.into_inner().await?, b"a,b\xFF\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn into_byte_sink(self) -> ByteRecordSink<W> {
+        ByteRecordSink::new(self.0)
+    }
+
     /// Flush the contents of the internal buffer and return the underlying writer.
-    /// 
+    ///
+    /// If flushing fails, the returned error holds this writer along with
+    /// the I/O error, so that the buffered data is not lost and writing can
+    /// be retried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///
+    ///     let data = wtr.into_inner().await?;
+    ///     assert_eq!(data, b"a,b,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
     pub async fn into_inner(
         self,
-    ) -> result::Result<W, io::Error> {
-        match self.0.into_inner().await {
-            Ok(w) => Ok(w),
-            Err(err) => Err(err.into_error()),
-        }
+    ) -> result::Result<W, IntoInnerError<AsyncWriter<W>>> {
+        self.0.into_inner().await.map_err(|err| err.map_writer(AsyncWriter))
+    }
+}
+
+/// Writes each `StringRecord` sent to it as a record, like
+/// `write_record`, so that a stream of records can be written with
+/// `forward` or `send_all`.
+///
+/// Sending a record writes it to the internal buffer, which is written out
+/// once it is full, when the sink is flushed, and when it is closed. Closing
+/// the sink also closes the underlying writer.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use futures::stream::StreamExt;
+/// use csv_async::{AsyncReader, AsyncWriter};
+///
+/// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+/// async fn example() -> Result<(), Box<dyn Error>> {
+///     let data = "city,pop\nBoston,4628910\nConcord,42695\n";
+///     let mut rdr = AsyncReader::from_reader(data.as_bytes());
+///     let mut wtr = AsyncWriter::from_writer(vec![]);
+///     wtr.write_record(rdr.headers().await?).await?;
+///     rdr.records().forward(&mut wtr).await?;
+///
+///     assert_eq!(wtr.into_inner().await?, data.as_bytes());
+///     Ok(())
+/// }
+/// ```
+impl<W: AsyncWrite + Unpin> Sink<StringRecord> for AsyncWriter<W> {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, record: StringRecord) -> Result<()> {
+        self.0.start_send(record.as_byte_record())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_close(cx)
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<TeeWriter<W>> {
+    /// Build a CSV writer with a default configuration that writes the same
+    /// data to each of `wtrs`.
+    ///
+    /// Every record is written to all writers, and flushing the CSV writer
+    /// flushes all of them. The first error from any writer is returned, as
+    /// an I/O error wrapping a [`TeeError`](struct.TeeError.html) that tells
+    /// which writer failed. `into_inner` returns a
+    /// [`TeeWriter`](struct.TeeWriter.html), whose `into_inner` returns the
+    /// writers in their original order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriter;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::tee(vec![vec![], vec![]]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///     wtr.write_record(&["x", "y", "z"]).await?;
+    ///
+    ///     let outputs = wtr.into_inner().await?.into_inner();
+    ///     assert_eq!(outputs[0], b"a,b,c\nx,y,z\n");
+    ///     assert_eq!(outputs[1], b"a,b,c\nx,y,z\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn tee(wtrs: Vec<W>) -> AsyncWriter<TeeWriter<W>> {
+        AsyncWriterBuilder::new().create_tee_writer(wtrs)
     }
 }
 
@@ -243,6 +709,7 @@ mod tests {
     use crate::byte_record::ByteRecord;
     use crate::error::ErrorKind;
     use crate::string_record::StringRecord;
+    use crate::{FooterSpec, NewlineMode, QuoteStyle, Terminator};
 
     use super::{AsyncWriter, AsyncWriterBuilder};
 
@@ -446,4 +913,1079 @@ mod tests {
             assert_eq!(got, ">a,b\n<>c,d\n<!>e,f\n<!");
         });
     }
+
+    #[test]
+    fn fixed_width() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[4, 3, 6]))
+                .create_writer(vec![]);
+            wtr.write_record(&["ab", "c", "defg"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["wxyz", "", "12"])).await.unwrap();
+
+            assert_eq!(wtr_as_string(wtr).await, "ab  c  defg  \nwxyz   12    \n");
+        });
+    }
+
+    #[test]
+    fn fixed_width_right_aligned() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[3, 5]))
+                .fixed_width_alignment(crate::Alignment::Right)
+                .fixed_width_padding(b'.')
+                .terminator(crate::Terminator::CRLF)
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "bc"]).await.unwrap();
+            wtr.write_record(&["", "defgh"]).await.unwrap();
+
+            assert_eq!(wtr_as_string(wtr).await, "..a...bc\r\n...defgh\r\n");
+        });
+    }
+
+    #[test]
+    fn fixed_width_too_wide() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[2, 2]))
+                .create_writer(vec![]);
+            let err = wtr.write_record(&["ab", "cde"]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::FieldTooWide { field, width, len } => {
+                    assert_eq!(field, 1);
+                    assert_eq!(width, 2);
+                    assert_eq!(len, 3);
+                }
+                ref x => panic!("expected FieldTooWide error, got '{:?}'", x),
+            }
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[2, 2]))
+                .fixed_width_truncate(true)
+                .create_writer(vec![]);
+            wtr.write_record(&["ab", "cde"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "abcd\n");
+        });
+    }
+
+    #[test]
+    fn fixed_width_wrong_field_count() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[2, 2]))
+                .create_writer(vec![]);
+            let err = wtr.write_record(&["a"]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::UnequalLengths { expected_len: 2, len: 1, .. } => {}
+                ref x => panic!("expected UnequalLengths error, got '{:?}'", x),
+            }
+        });
+    }
+
+    #[test]
+    fn fixed_width_after_rejected_record() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .fixed_width(Some(&[3, 3]))
+                .create_writer(vec![]);
+            let err = wtr.write_record(&["ab", "toolong"]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::FieldTooWide { field: 1, .. } => {}
+                ref x => panic!("expected FieldTooWide error, got '{:?}'", x),
+            }
+            let err = wtr.write_record(&["a", "b", "c"]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::UnequalLengths { expected_len: 2, len: 3, .. } => {}
+                ref x => panic!("expected UnequalLengths error, got '{:?}'", x),
+            }
+            wtr.write_record(&["x", "y"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "x  y  \n");
+        });
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn from_ndjson() {
+        task::block_on(async {
+            let input = "{\"a\":\"x, y\",\"b\":1.5,\"c\":null}\n\n{\"a\":\"z\",\"b\":true,\"c\":[1,2]}\n";
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            let err = wtr.from_ndjson(input.as_bytes(), false).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::Json { line: 3, .. } => {}
+                ref x => panic!("expected Json error, got '{:?}'", x),
+            }
+
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.from_ndjson(input.as_bytes(), true).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+
+            let mut rdr = crate::AsyncReader::from_reader(&data[..]);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["a", "b", "c"]);
+            let mut record = StringRecord::new();
+            assert!(rdr.read_record(&mut record).await.unwrap());
+            assert_eq!(record, vec!["x, y", "1.5", ""]);
+            assert!(rdr.read_record(&mut record).await.unwrap());
+            assert_eq!(record, vec!["z", "true", "[1,2]"]);
+            assert!(!rdr.read_record(&mut record).await.unwrap());
+        });
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn from_ndjson_key_order() {
+        task::block_on(async {
+            let input = "{\"zeta\":1,\"alpha\":2,\"mid\":3}\n{\"alpha\":4,\"mid\":5,\"zeta\":6}\n";
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.from_ndjson(input.as_bytes(), false).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "zeta,alpha,mid\n1,2,3\n6,4,5\n");
+        });
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn from_ndjson_differing_keys() {
+        task::block_on(async {
+            let input = "{\"a\":1,\"b\":2}\n{\"b\":3,\"c\":4}\n";
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            let err = wtr.from_ndjson(input.as_bytes(), false).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::Json { line: 2, .. } => {}
+                ref x => panic!("expected Json error, got '{:?}'", x),
+            }
+
+            let mut wtr = AsyncWriterBuilder::new().flexible(true).create_writer(vec![]);
+            wtr.from_ndjson(input.as_bytes(), false).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,b\n1,2\n,3,4\n");
+        });
+    }
+
+    #[test]
+    fn end_table_round_trip() {
+        task::block_on(async {
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            wtr.write_record(&["1", "2"]).await.unwrap();
+            wtr.end_table().await.unwrap();
+            wtr.write_record(&["x", "y", "z"]).await.unwrap();
+            wtr.write_record(&["3", "4", "5"]).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, b"a,b\n1,2\n\nx,y,z\n3,4,5\n");
+
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .multi_table(true)
+                .create_reader(&data[..]);
+            let mut tables = vec![];
+            while let Some(mut table) = rdr.next_table().await.unwrap() {
+                let headers = table.headers().await.unwrap().clone();
+                let mut rec = StringRecord::new();
+                let mut rows = vec![];
+                while table.read_record(&mut rec).await.unwrap() {
+                    rows.push(rec.clone());
+                }
+                tables.push((headers, rows));
+            }
+            assert_eq!(tables, vec![
+                (StringRecord::from(vec!["a", "b"]), vec![StringRecord::from(vec!["1", "2"])]),
+                (StringRecord::from(vec!["x", "y", "z"]), vec![StringRecord::from(vec!["3", "4", "5"])]),
+            ]);
+        });
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64_columns_round_trip() {
+        task::block_on(async {
+            let blobs: Vec<Vec<u8>> = vec![
+                (0..=255).collect(),
+                vec![],
+                b"a,\"b\"\n\r\0".to_vec(),
+            ];
+            let mut wtr = AsyncWriterBuilder::new()
+                .base64_columns(&[1])
+                .create_writer(vec![]);
+            wtr.write_record(&["id", "blob"]).await.unwrap();
+            for (i, blob) in blobs.iter().enumerate() {
+                let id = i.to_string();
+                wtr.write_byte_record(&ByteRecord::from(vec![id.as_bytes(), &blob[..]]))
+                    .await
+                    .unwrap();
+            }
+            let data = wtr.into_inner().await.unwrap();
+            assert!(data.starts_with(b"id,blob\n0,AAECAwQF"));
+
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .base64_columns(&[1])
+                .create_reader(&data[..]);
+            let mut rec = ByteRecord::new();
+            let mut got = vec![];
+            while rdr.read_byte_record(&mut rec).await.unwrap() {
+                got.push(rec[1].to_vec());
+            }
+            assert_eq!(got, blobs);
+        });
+    }
+
+    #[test]
+    fn newline_in_field() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new().create_writer(vec![]);
+            wtr.write_record(&["a", "b\nc"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,\"b\nc\"\n");
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .newline_in_field(NewlineMode::Replace(b' '))
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "b\nc\r\nd\re"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["f\ng", "h"])).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,b c d e\nf g,h\n");
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .newline_in_field(NewlineMode::Error)
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            let err = wtr.write_record(&["c", "d\ne"]).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::NewlineInField { field } => assert_eq!(field, 1),
+                ref kind => panic!("unexpected error: {:?}", kind),
+            }
+            // Nothing of the rejected record is written.
+            wtr.write_record(&["f", "g"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,b\nf,g\n");
+        });
+    }
+
+    #[test]
+    fn write_footer() {
+        task::block_on(async {
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            wtr.write_record(&["1", "2"]).await.unwrap();
+            wtr.write_record(&["3", "4"]).await.unwrap();
+            wtr.write_record(&["5", "6"]).await.unwrap();
+            wtr.write_footer().await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,b\n1,2\n3,4\n5,6\n3\n");
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .footer(FooterSpec { label: Some("END".to_string()), checksum: true })
+                .create_writer(vec![]);
+            wtr.write_record(&["a"]).await.unwrap();
+            wtr.write_record(&["1"]).await.unwrap();
+            wtr.write_footer().await.unwrap();
+            // Adler-32 of "a\n1\n".
+            assert_eq!(wtr_as_string(wtr).await, "a\n1\nEND,1,021200a7\n");
+        });
+    }
+
+    #[test]
+    fn write_footer_multi_table_round_trip() {
+        task::block_on(async {
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            wtr.write_record(&["1", "2"]).await.unwrap();
+            wtr.end_table().await.unwrap();
+            wtr.write_record(&["x", "y", "z"]).await.unwrap();
+            wtr.write_record(&["3", "4", "5"]).await.unwrap();
+            wtr.write_footer().await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, b"a,b\n1,2\n\nx,y,z\n3,4,5\n2\n");
+
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .multi_table(true)
+                .expect_footer(FooterSpec::default())
+                .create_reader(&data[..]);
+            let mut rows = vec![];
+            while let Some(mut table) = rdr.next_table().await.unwrap() {
+                let mut rec = StringRecord::new();
+                while table.read_record(&mut rec).await.unwrap() {
+                    rows.push(rec.clone());
+                }
+            }
+            assert_eq!(rows, vec![vec!["1", "2"], vec!["3", "4", "5"]]);
+        });
+    }
+
+    #[test]
+    fn quote_if() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .quote_if(Box::new(|_, field| field.starts_with(b"0")))
+                .create_writer(vec![]);
+            wtr.write_record(&["id", "zip", "note"]).await.unwrap();
+            wtr.write_record(&["1", "02101", "0 \"a\""]).await.unwrap();
+            wtr.write_record(&["007", "10001", "a,b"]).await.unwrap();
+            wtr.write_record(&["2", "", ""]).await.unwrap();
+            assert_eq!(
+                wtr_as_string(wtr).await,
+                "id,zip,note\n1,\"02101\",\"0 \"\"a\"\"\"\n\"007\",10001,\"a,b\"\n2,,\n"
+            );
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .quote_style(QuoteStyle::Never)
+                .quote_if(Box::new(|i, _| i == 0))
+                .create_writer(vec![]);
+            wtr.write_record(&["x"]).await.unwrap();
+            wtr.write_record(&[""]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "\"x\"\n\"\"\n");
+        });
+    }
+
+    #[test]
+    fn tee() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .has_headers(true)
+                .buffer_capacity(4)
+                .create_tee_writer(vec![vec![], vec![]]);
+            wtr.write_record(&["name", "comment"]).await.unwrap();
+            wtr.write_record(&["a", "needs \"quotes\", here"]).await.unwrap();
+            wtr.write_record(&["b", ""]).await.unwrap();
+            wtr.flush().await.unwrap();
+
+            let outputs = wtr.into_inner().await.unwrap().into_inner();
+            assert_eq!(outputs.len(), 2);
+            let expected = "name,comment\na,\"needs \"\"quotes\"\", here\"\nb,\n";
+            assert_eq!(String::from_utf8(outputs[0].clone()).unwrap(), expected);
+            assert_eq!(outputs[0], outputs[1]);
+        });
+    }
+
+    #[test]
+    fn sort_window() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .sort_window(3, 1)
+                .create_writer(vec![]);
+            wtr.write_record(&["event", "time"]).await.unwrap();
+            for &(event, time) in [
+                ("b", "2"), ("a", "1"), ("d", "4"), ("c", "3"), ("f", "6"),
+                ("e", "5"), ("g", "7"), ("i", "9"), ("h", "8"),
+            ].iter() {
+                wtr.write_record(&[event, time]).await.unwrap();
+            }
+            assert_eq!(
+                wtr_as_string(wtr).await,
+                "event,time\na,1\nb,2\nc,3\nd,4\ne,5\nf,6\ng,7\nh,8\ni,9\n"
+            );
+
+            // Records too far from their place are only ordered within the
+            // window, and a flush writes all held records.
+            let mut wtr = AsyncWriterBuilder::new()
+                .has_headers(false)
+                .sort_window(3, 0)
+                .create_writer(vec![]);
+            for key in ["4", "5", "6", "1"].iter() {
+                wtr.write_byte_record(&ByteRecord::from(vec![*key])).await.unwrap();
+            }
+            wtr.flush().await.unwrap();
+            wtr.write_record(&["3"]).await.unwrap();
+            wtr.write_record(&["2"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "1\n4\n5\n6\n2\n3\n");
+        });
+    }
+
+    #[test]
+    fn row_numbers() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .row_numbers(Some("#"))
+                .create_writer(vec![]);
+            wtr.write_record(&["city", "pop"]).await.unwrap();
+            wtr.write_record(&["Boston", "4628910"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["Concord", "42695"])).await.unwrap();
+            wtr.write_field("Dover").await.unwrap();
+            wtr.write_field("32741").await.unwrap();
+            wtr.write_record(None::<&[u8]>).await.unwrap();
+            wtr.write_footer().await.unwrap();
+            assert_eq!(
+                wtr_as_string(wtr).await,
+                "#,city,pop\n1,Boston,4628910\n2,Concord,42695\n3,Dover,32741\n3\n"
+            );
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .has_headers(false)
+                .row_numbers(Some("#"))
+                .row_numbers_start(0)
+                .create_writer(vec![]);
+            wtr.write_record(&["a"]).await.unwrap();
+            wtr.write_record(&["b"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "0,a\n1,b\n");
+        });
+    }
+
+    #[test]
+    fn write_sep_directive() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .delimiter(b';')
+                .write_sep_directive(true)
+                .create_writer(vec![]);
+            wtr.write_record(&["city", "pop"]).await.unwrap();
+            wtr.write_record(&["Boston; MA", "4628910"]).await.unwrap();
+            wtr.write_record(&["Concord", "42,695"]).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(
+                String::from_utf8(data.clone()).unwrap(),
+                "sep=;\r\ncity;pop\n\"Boston; MA\";4628910\nConcord;42,695\n"
+            );
+
+            // Read it back, with the delimiter taken from the directive.
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .respect_sep_directive(true)
+                .create_reader(&data[..]);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["city", "pop"]);
+            let mut rec = StringRecord::new();
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["Boston; MA", "4628910"]);
+            assert!(rdr.read_record(&mut rec).await.unwrap());
+            assert_eq!(rec, vec!["Concord", "42,695"]);
+            assert!(!rdr.read_record(&mut rec).await.unwrap());
+
+            // Comma delimited data does not need the directive.
+            let mut wtr = AsyncWriterBuilder::new()
+                .write_sep_directive(true)
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,b\n");
+
+            // Nothing is written without rows.
+            let wtr = AsyncWriterBuilder::new()
+                .delimiter(b'\t')
+                .write_sep_directive(true)
+                .create_writer(vec![]);
+            assert_eq!(wtr_as_string(wtr).await, "");
+        });
+    }
+
+    #[test]
+    fn write_byte_record_preserving() {
+        task::block_on(async {
+            let data = "\"id\",name,\"note\"\n1,\"Boston\",\"\"\n2,,\"a \"\"b\"\"\"\n\"3\",\"x,y\",z\n";
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .has_headers(false)
+                .track_quoted(true)
+                .create_reader(data.as_bytes());
+            let mut wtr = AsyncWriterBuilder::new().create_writer(vec![]);
+            let mut record = ByteRecord::new();
+            while rdr.read_byte_record(&mut record).await.unwrap() {
+                wtr.write_byte_record_preserving(&record).await.unwrap();
+            }
+            assert_eq!(wtr_as_string(wtr).await, data);
+
+            // Without quoting information, only necessary quotes are written.
+            let mut wtr = AsyncWriterBuilder::new().create_writer(vec![]);
+            wtr.write_byte_record_preserving(&ByteRecord::from(vec!["a", "", "x,y"]))
+                .await
+                .unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,,\"x,y\"\n");
+
+            // Held records keep their quoting, and are numbered as usual.
+            let mut rdr = crate::AsyncReaderBuilder::new()
+                .track_quoted(true)
+                .create_reader(&b"k,v\n\"b\",2\na,\"\"\n"[..]);
+            let mut wtr = AsyncWriterBuilder::new()
+                .sort_window(2, 0)
+                .row_numbers(Some("#"))
+                .create_writer(vec![]);
+            wtr.write_byte_record(rdr.byte_headers().await.unwrap()).await.unwrap();
+            while rdr.read_byte_record(&mut record).await.unwrap() {
+                wtr.write_byte_record_preserving(&record).await.unwrap();
+            }
+            assert_eq!(wtr_as_string(wtr).await, "#,k,v\n1,a,\"\"\n2,\"b\",2\n");
+        });
+    }
+
+    #[test]
+    fn one_empty_field_round_trip() {
+        use crate::AsyncReaderBuilder;
+
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .quote_style(QuoteStyle::Necessary)
+                .create_writer(vec![]);
+            wtr.write_record(&[""]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec![""])).await.unwrap();
+            wtr.write_byte_record_preserving(&ByteRecord::from(vec![""])).await.unwrap();
+            wtr.write_field("").await.unwrap();
+            wtr.write_record(None::<&[u8]>).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, b"\"\"\n\"\"\n\"\"\n\"\"\n");
+
+            // Options taking other write paths quote it as well.
+            let mut wtr = AsyncWriterBuilder::new()
+                .newline_in_field(NewlineMode::Error)
+                .sort_window(2, 0)
+                .create_writer(vec![]);
+            wtr.write_record(&[""]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec![""])).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "\"\"\n\"\"\n");
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .create_reader(&data[..]);
+            let mut record = StringRecord::new();
+            let mut count = 0;
+            while rdr.read_record(&mut record).await.unwrap() {
+                assert_eq!(record.len(), 1);
+                assert_eq!(&record[0], "");
+                count += 1;
+            }
+            assert_eq!(count, 4);
+        });
+    }
+
+    #[test]
+    fn delimiter() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .delimiter(b';')
+                .create_writer(vec![]);
+            wtr.write_record(&["a;b", "c,d"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["x;y", "z,w"])).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "\"a;b\";c,d\n\"x;y\";z,w\n");
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .delimiter(b'\t')
+                .create_writer(vec![]);
+            wtr.write_field("a\tb").await.unwrap();
+            wtr.write_field("c d").await.unwrap();
+            wtr.write_record(None::<&[u8]>).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "\"a\tb\"\tc d\n");
+        });
+    }
+
+    #[test]
+    fn quote_style() {
+        task::block_on(async {
+            let record = ByteRecord::from(vec!["a", "1.5", "", "b,c"]);
+            let mut outputs = vec![];
+            for &style in &[
+                QuoteStyle::Always,
+                QuoteStyle::Necessary,
+                QuoteStyle::NonNumeric,
+                QuoteStyle::Never,
+            ] {
+                let mut wtr = AsyncWriterBuilder::new()
+                    .quote_style(style)
+                    .create_writer(vec![]);
+                wtr.write_record(&record).await.unwrap();
+                wtr.write_byte_record(&record).await.unwrap();
+                outputs.push(wtr_as_string(wtr).await);
+            }
+            assert_eq!(outputs, vec![
+                "\"a\",\"1.5\",\"\",\"b,c\"\n".repeat(2),
+                "a,1.5,,\"b,c\"\n".repeat(2),
+                "\"a\",1.5,\"\",\"b,c\"\n".repeat(2),
+                "a,1.5,,b,c\n".repeat(2),
+            ]);
+        });
+    }
+
+    #[test]
+    fn terminator() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .terminator(Terminator::CRLF)
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "b\nc"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["x", "y"])).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a,\"b\nc\"\r\nx,y\r\n");
+
+            // ASCII delimited text uses the unit and record separators.
+            let mut wtr = AsyncWriterBuilder::new()
+                .delimiter(b'\x1F')
+                .terminator(Terminator::Any(b'\x1E'))
+                .create_writer(vec![]);
+            wtr.write_record(&["a", "b\nc"]).await.unwrap();
+            wtr.write_byte_record(&ByteRecord::from(vec!["x", "y\x1Ez"])).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "a\x1Fb\nc\x1Ex\x1F\"y\x1Ez\"\x1E");
+        });
+    }
+
+    #[test]
+    fn backslash_escape_round_trip() {
+        use crate::AsyncReaderBuilder;
+
+        task::block_on(async {
+            let record = ByteRecord::from(vec!["a\"b", "\"", "c"]);
+            let mut wtr = AsyncWriterBuilder::new()
+                .double_quote(false)
+                .escape(b'\\')
+                .create_writer(vec![]);
+            wtr.write_record(&record).await.unwrap();
+            wtr.write_byte_record(&record).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, &b"\"a\\\"b\",\"\\\"\",c\n".repeat(2)[..]);
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .has_headers(false)
+                .double_quote(false)
+                .escape(Some(b'\\'))
+                .create_reader(&data[..]);
+            let mut read = ByteRecord::new();
+            for _ in 0..2 {
+                assert!(rdr.read_byte_record(&mut read).await.unwrap());
+                assert_eq!(read, record);
+            }
+            assert!(!rdr.read_byte_record(&mut read).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn buffer_capacity() {
+        task::block_on(async {
+            for capacity in 0..4 {
+                let mut wtr = AsyncWriterBuilder::new()
+                    .buffer_capacity(capacity)
+                    .terminator(Terminator::CRLF)
+                    .create_writer(vec![]);
+                wtr.write_record(&["a\"b", "c,d", ""]).await.unwrap();
+                wtr.write_byte_record(&ByteRecord::from(vec!["x", "y", "z"])).await.unwrap();
+                wtr.write_field("").await.unwrap();
+                wtr.write_field("1").await.unwrap();
+                wtr.write_field("2").await.unwrap();
+                wtr.write_record(None::<&[u8]>).await.unwrap();
+                assert_eq!(
+                    wtr_as_string(wtr).await,
+                    "\"a\"\"b\",\"c,d\",\r\nx,y,z\r\n,1,2\r\n",
+                    "capacity {}",
+                    capacity,
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn write_byte_record_matches_write_record() {
+        task::block_on(async {
+            let records: Vec<ByteRecord> = (0..200)
+                .map(|i| ByteRecord::from(vec![
+                    i.to_string(),
+                    "\"".repeat(i % 7),
+                    format!("a,{}", "b".repeat(i % 23)),
+                    String::new(),
+                ]))
+                .collect();
+            let mut fast = AsyncWriterBuilder::new()
+                .buffer_capacity(64)
+                .create_writer(vec![]);
+            let mut slow = AsyncWriterBuilder::new()
+                .buffer_capacity(64)
+                .create_writer(vec![]);
+            for record in &records {
+                fast.write_byte_record(record).await.unwrap();
+                for field in record {
+                    slow.write_field(field).await.unwrap();
+                }
+                slow.write_record(None::<&[u8]>).await.unwrap();
+            }
+            let fast = wtr_as_string(fast).await;
+            assert_eq!(fast, wtr_as_string(slow).await);
+            assert!(fast.starts_with("0,,\"a,\",\n1,\"\"\"\",\"a,b\",\n"));
+        });
+    }
+
+    #[test]
+    fn end_record() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .flexible(true)
+                .sort_window(2, 0)
+                .create_writer(vec![]);
+            wtr.write_record(&["name", "n"]).await.unwrap();
+            wtr.write_record(&["b", "1"]).await.unwrap();
+            wtr.write_field("a").await.unwrap();
+            wtr.write_field("2").await.unwrap();
+            wtr.end_record().await.unwrap();
+            // Without fields, a single empty field is written.
+            wtr.end_record().await.unwrap();
+            // Only the record written with write_record was held back.
+            assert_eq!(wtr_as_string(wtr).await, "name,n\na,2\n\"\"\nb,1\n");
+        });
+    }
+
+    #[test]
+    fn into_inner_error_keeps_writer() {
+        task::block_on(async {
+            #[derive(Debug)]
+            struct FailFirstWrite(Vec<u8>, bool);
+
+            impl io::AsyncWrite for FailFirstWrite {
+                fn poll_write(
+                    mut self: Pin<&mut Self>,
+                    _: &mut Context,
+                    buf: &[u8]
+                ) -> Poll<Result<usize, io::Error>> {
+                    if std::mem::replace(&mut self.1, false) {
+                        return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+                    }
+                    self.0.extend_from_slice(buf);
+                    Poll::Ready(Ok(buf.len()))
+                }
+
+                fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                    Poll::Ready(Ok(()))
+                }
+
+                fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+                    self.poll_flush(cx)
+                }
+            }
+
+            let mut wtr = AsyncWriter::from_writer(FailFirstWrite(vec![], true));
+            wtr.write_record(&["a", "b"]).await.unwrap();
+            let err = wtr.into_inner().await.unwrap_err();
+            assert_eq!(err.error().kind(), io::ErrorKind::BrokenPipe);
+            // The buffered record was kept, so writing can be retried.
+            let mut wtr = err.into_writer();
+            wtr.write_record(&["c", "d"]).await.unwrap();
+            assert_eq!(wtr.into_inner().await.unwrap().0, b"a,b\nc,d\n");
+        });
+    }
+
+    #[test]
+    fn sink_string_records() {
+        use futures::sink::SinkExt;
+        use futures::stream::{self, StreamExt};
+
+        task::block_on(async {
+            // Takes at most 3 bytes at a time, and every other write waits.
+            #[derive(Debug, Default)]
+            struct SlowWriter {
+                data: Vec<u8>,
+                wait: bool,
+                closed: bool,
+            }
+
+            impl io::AsyncWrite for SlowWriter {
+                fn poll_write(
+                    mut self: Pin<&mut Self>,
+                    cx: &mut Context,
+                    buf: &[u8]
+                ) -> Poll<Result<usize, io::Error>> {
+                    self.wait = !self.wait;
+                    if self.wait {
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                    let n = std::cmp::min(3, buf.len());
+                    self.data.extend_from_slice(&buf[..n]);
+                    Poll::Ready(Ok(n))
+                }
+
+                fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                    Poll::Ready(Ok(()))
+                }
+
+                fn poll_close(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                    self.closed = true;
+                    Poll::Ready(Ok(()))
+                }
+            }
+
+            let records: Vec<StringRecord> = (0..50)
+                .map(|i| StringRecord::from(vec![i.to_string(), "x,y".repeat(i % 5)]))
+                .collect();
+            let mut expected = "sep=;\r\n".to_string();
+            for record in &records {
+                expected.push_str(&format!("{};{}\n", &record[0], &record[1]));
+            }
+            let mut wtr = AsyncWriterBuilder::new()
+                .buffer_capacity(8)
+                .delimiter(b';')
+                .write_sep_directive(true)
+                .create_writer(SlowWriter::default());
+            let mut input = stream::iter(records).map(Ok);
+            wtr.send_all(&mut input).await.unwrap();
+            SinkExt::<StringRecord>::close(&mut wtr).await.unwrap();
+            let inner = wtr.into_inner().await.unwrap();
+            assert!(inner.closed);
+            assert_eq!(String::from_utf8(inner.data).unwrap(), expected);
+
+            // Errors are returned from sending.
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.send(StringRecord::from(vec!["a", "b"])).await.unwrap();
+            let err = wtr.send(StringRecord::from(vec!["c"])).await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::UnequalLengths { expected_len: 2, len: 1, .. } => {}
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+        });
+    }
+
+    #[test]
+    fn byte_sink() {
+        use futures::sink::SinkExt;
+        use futures::stream::{self, StreamExt};
+
+        task::block_on(async {
+            let records: Vec<ByteRecord> = (0..100u8)
+                .map(|i| ByteRecord::from(vec![vec![i], b"a\"b".to_vec()]))
+                .collect();
+            let mut sink = AsyncWriterBuilder::new()
+                .buffer_capacity(16)
+                .create_writer(vec![])
+                .into_byte_sink();
+            let mut input = stream::iter(records.clone()).map(Ok);
+            sink.send_all(&mut input).await.unwrap();
+            let data = sink.into_inner().await.unwrap();
+
+            let mut expected = vec![];
+            let mut wtr = AsyncWriter::from_writer(&mut expected);
+            for record in &records {
+                wtr.write_byte_record(record).await.unwrap();
+            }
+            wtr.flush().await.unwrap();
+            drop(wtr);
+            assert_eq!(data, expected);
+        });
+    }
+
+    #[test]
+    fn write_records() {
+        use futures::stream::{self, Stream, StreamExt};
+
+        use crate::error::{Error, Result};
+
+        // Waits before every record.
+        struct SlowStream<S>(S, bool);
+
+        impl<S: Stream + Unpin> Stream for SlowStream<S> {
+            type Item = S::Item;
+
+            fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+                self.1 = !self.1;
+                if self.1 {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Pin::new(&mut self.0).poll_next(cx)
+            }
+        }
+
+        task::block_on(async {
+            let records: Vec<ByteRecord> = (0..300)
+                .map(|i| ByteRecord::from(vec![i.to_string(), "a\"b".repeat(i % 4)]))
+                .collect();
+            let mut wtr = AsyncWriterBuilder::new()
+                .buffer_capacity(32)
+                .create_writer(vec![]);
+            wtr.write_records(SlowStream(stream::iter(records.clone()).map(Ok), false))
+                .await
+                .unwrap();
+            let data = wtr_as_string(wtr).await;
+            let mut expected = AsyncWriter::from_writer(vec![]);
+            for record in &records {
+                expected.write_byte_record(record).await.unwrap();
+            }
+            assert_eq!(data, wtr_as_string(expected).await);
+
+            // Records before an error are written.
+            let input: Vec<Result<ByteRecord>> = vec![
+                Ok(ByteRecord::from(vec!["a"])),
+                Err(Error::new(ErrorKind::Seek)),
+                Ok(ByteRecord::from(vec!["b"])),
+            ];
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            let err = wtr.write_records(stream::iter(input)).await.unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::Seek));
+            assert_eq!(wtr_as_string(wtr).await, "a\n");
+        });
+    }
+
+    #[test]
+    fn auto_flush() {
+        use futures::sink::SinkExt;
+        use futures::stream::{self, StreamExt};
+
+        #[derive(Debug, Default)]
+        struct MarkFlush(Vec<u8>);
+
+        impl io::AsyncWrite for MarkFlush {
+            fn poll_write(
+                mut self: Pin<&mut Self>,
+                _: &mut Context,
+                buf: &[u8]
+            ) -> Poll<Result<usize, io::Error>> {
+                self.0.extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                self.0.push(b'!');
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+                self.poll_flush(cx)
+            }
+        }
+
+        fn marked(wtr: MarkFlush) -> String {
+            String::from_utf8(wtr.0).unwrap()
+        }
+
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .flush_after_records(Some(2))
+                .create_writer(MarkFlush::default());
+            for field in &["h", "a", "b", "c", "d"] {
+                wtr.write_record(&[field]).await.unwrap();
+            }
+            wtr.write_field("e").await.unwrap();
+            wtr.end_record().await.unwrap();
+            assert_eq!(marked(wtr.into_inner().await.unwrap()), "h\na\n!b\nc\n!d\ne\n!!");
+
+            let mut wtr = AsyncWriterBuilder::new()
+                .flush_after_bytes(Some(5))
+                .buffer_capacity(2)
+                .create_writer(MarkFlush::default());
+            for field in &["abc", "d", "e", "fghij"] {
+                wtr.write_byte_record(&ByteRecord::from(vec![field])).await.unwrap();
+            }
+            assert_eq!(marked(wtr.into_inner().await.unwrap()), "abc\nd\n!e\nfghij\n!!");
+
+            // The sort window keeps holding records.
+            let mut wtr = AsyncWriterBuilder::new()
+                .flush_after_records(Some(1))
+                .sort_window(2, 0)
+                .create_writer(MarkFlush::default());
+            for field in &["h", "c", "b", "a"] {
+                wtr.write_record(&[field]).await.unwrap();
+            }
+            assert_eq!(marked(wtr.into_inner().await.unwrap()), "h\n!a\n!b\nc\n!");
+
+            // Sinks and write_records flush as well.
+            let mut wtr = AsyncWriterBuilder::new()
+                .flush_after_records(Some(2))
+                .create_writer(MarkFlush::default());
+            let records = (0..5).map(|i| Ok(ByteRecord::from(vec![i.to_string()])));
+            wtr.write_records(stream::iter(records)).await.unwrap();
+            let mut sink = wtr.into_byte_sink();
+            let mut records = stream::iter(5..9).map(|i| Ok(ByteRecord::from(vec![i.to_string()])));
+            sink.send_all(&mut records).await.unwrap();
+            assert_eq!(
+                marked(sink.into_inner().await.unwrap()),
+                "0\n1\n!2\n3\n!4\n5\n!6\n7\n!8\n!!",
+            );
+        });
+    }
+
+    #[test]
+    fn close() {
+        use std::sync::{Arc, Mutex};
+
+        // Logs writes, flushes and closing.
+        #[derive(Debug, Default)]
+        struct Log(Arc<Mutex<String>>);
+
+        impl io::AsyncWrite for Log {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _: &mut Context,
+                buf: &[u8]
+            ) -> Poll<Result<usize, io::Error>> {
+                self.0.lock().unwrap().push_str(std::str::from_utf8(buf).unwrap());
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                self.0.lock().unwrap().push('!');
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<Result<(), io::Error>> {
+                self.0.lock().unwrap().push('.');
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        task::block_on(async {
+            let log = Arc::new(Mutex::new(String::new()));
+            let mut wtr = AsyncWriterBuilder::new()
+                .sort_window(2, 0)
+                .create_writer(Log(log.clone()));
+            wtr.write_record(&["h"]).await.unwrap();
+            wtr.write_record(&["b"]).await.unwrap();
+            wtr.write_record(&["a"]).await.unwrap();
+            wtr.close().await.unwrap();
+            drop(wtr);
+            // Dropping a closed writer does not flush it again.
+            assert_eq!(*log.lock().unwrap(), "h\na\nb\n!.");
+
+            // Dropping a writer still flushes it.
+            let log = Arc::new(Mutex::new(String::new()));
+            let mut wtr = AsyncWriter::from_writer(Log(log.clone()));
+            wtr.write_record(&["a"]).await.unwrap();
+            drop(wtr);
+            assert_eq!(*log.lock().unwrap(), "a\n!");
+        });
+    }
+
+    #[test]
+    fn write_comment() {
+        use crate::AsyncReaderBuilder;
+
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .comment(Some(b'%'))
+                .terminator(Terminator::CRLF)
+                .create_writer(vec![]);
+            wtr.write_comment("generated\r\n").await.unwrap();
+            wtr.write_record(&["name", "note"]).await.unwrap();
+            wtr.write_field("%x").await.unwrap();
+            wtr.write_field("a%b").await.unwrap();
+            wtr.write_comment("").await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, b"%generated\r\n%\r\nname,note\r\n\"%x\",\"a%b\"\r\n%\r\n");
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .comment(Some(b'%'))
+                .create_reader(&data[..]);
+            assert_eq!(rdr.headers().await.unwrap(), vec!["name", "note"]);
+            let mut record = StringRecord::new();
+            assert!(rdr.read_record(&mut record).await.unwrap());
+            assert_eq!(record, vec!["%x", "a%b"]);
+            assert!(!rdr.read_record(&mut record).await.unwrap());
+
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            let err = wtr.write_comment("note").await.unwrap_err();
+            match *err.kind() {
+                ErrorKind::Io(ref err) => assert_eq!(err.kind(), io::ErrorKind::InvalidInput),
+                ref kind => panic!("unexpected error kind: {:?}", kind),
+            }
+        });
+    }
+
+    #[test]
+    fn write_record_with_style() {
+        task::block_on(async {
+            let mut wtr = AsyncWriterBuilder::new()
+                .quote_style(QuoteStyle::Never)
+                .create_writer(vec![]);
+            wtr.write_record_with_style(&["city", "pop"], QuoteStyle::Always).await.unwrap();
+            wtr.write_record(&["Boston", "4628910"]).await.unwrap();
+            wtr.write_record_with_style(&["a b", ""], QuoteStyle::NonNumeric).await.unwrap();
+            wtr.write_record(&["a b", ""]).await.unwrap();
+            assert_eq!(
+                wtr_as_string(wtr).await,
+                "\"city\",\"pop\"\nBoston,4628910\n\"a b\",\"\"\na b,\n"
+            );
+
+            // A record started with write_field keeps the writer's style.
+            let mut wtr = AsyncWriter::from_writer(vec![]);
+            wtr.write_field("x").await.unwrap();
+            wtr.write_record_with_style(&["y"], QuoteStyle::Always).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "x,y\n");
+
+            // Held records are not reordered around it.
+            let mut wtr = AsyncWriterBuilder::new()
+                .has_headers(false)
+                .sort_window(2, 0)
+                .create_writer(vec![]);
+            wtr.write_record(&["b"]).await.unwrap();
+            wtr.write_record_with_style(&["z"], QuoteStyle::Always).await.unwrap();
+            wtr.write_record(&["a"]).await.unwrap();
+            assert_eq!(wtr_as_string(wtr).await, "\"z\"\na\nb\n");
+        });
+    }
 }
@@ -0,0 +1,194 @@
+use bytes::BytesMut;
+use csv_core::{Writer as CoreWriter, WriteResult};
+use tokio_util::codec::Encoder;
+
+use crate::byte_record::ByteRecord;
+use crate::error::{Error, ErrorKind, Result};
+use super::AsyncWriterBuilder;
+
+impl AsyncWriterBuilder {
+    /// Build a [`CsvEncoder`](struct.CsvEncoder.html) writing CSV data with
+    /// this configuration.
+    ///
+    /// Only the options of the CSV format are used: `delimiter`, `quote`,
+    /// `escape`, `double_quote`, `quote_style`, `terminator` and `flexible`.
+    ///
+    /// This is only available with the `tokio-codec` feature.
+    pub fn create_encoder(&self) -> CsvEncoder {
+        CsvEncoder {
+            core: self.builder.build(),
+            buf: vec![0; 1024],
+            flexible: self.flexible,
+            first_field_count: None,
+        }
+    }
+}
+
+/// A [`tokio_util`](https://docs.rs/tokio-util) codec encoding `ByteRecord`s
+/// as CSV data.
+///
+/// This plugs CSV writing into a `FramedWrite`, e.g. to send records over a
+/// socket. Every record is written as a row, so a header row is sent like
+/// any other record. Unless the encoder is `flexible`, all records must have
+/// the same number of fields.
+///
+/// This is only available with the `tokio-codec` feature.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use futures::sink::SinkExt;
+/// use tokio_util::codec::FramedWrite;
+/// use csv_async::{AsyncWriterBuilder, ByteRecord};
+///
+/// # fn main() { tokio1::runtime::Runtime::new().unwrap().block_on(async {example().await.unwrap()}); }
+/// async fn example() -> Result<(), Box<dyn Error>> {
+///     let encoder = AsyncWriterBuilder::new().delimiter(b';').create_encoder();
+///     let mut frames = FramedWrite::new(vec![], encoder);
+///     frames.send(ByteRecord::from(vec!["city", "pop"])).await?;
+///     frames.send(ByteRecord::from(vec!["Boston", "4628910"])).await?;
+///     assert_eq!(frames.get_ref().as_slice(), b"city;pop\nBoston;4628910\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CsvEncoder {
+    core: CoreWriter,
+    /// Scratch space for the encoded record.
+    buf: Vec<u8>,
+    flexible: bool,
+    first_field_count: Option<u64>,
+}
+
+impl Default for CsvEncoder {
+    fn default() -> CsvEncoder {
+        AsyncWriterBuilder::new().create_encoder()
+    }
+}
+
+impl CsvEncoder {
+    /// Encode `record` at the end of `dst`.
+    fn encode_record(&mut self, record: &ByteRecord, dst: &mut BytesMut) -> Result<()> {
+        let len = record.len() as u64;
+        if !self.flexible {
+            match self.first_field_count {
+                None => self.first_field_count = Some(len),
+                Some(expected_len) if expected_len != len => {
+                    return Err(Error::new(ErrorKind::UnequalLengths {
+                        pos: None,
+                        expected_len,
+                        len,
+                    }));
+                }
+                Some(_) => {}
+            }
+        }
+        let mut used = 0;
+        for (i, field) in record.iter().enumerate() {
+            if i > 0 {
+                used += self.write_with(used, |core, out| core.delimiter(out));
+            }
+            let mut field = field;
+            loop {
+                let (res, nin, nout) = self.core.field(field, &mut self.buf[used..]);
+                field = &field[nin..];
+                used += nout;
+                match res {
+                    WriteResult::InputEmpty => break,
+                    WriteResult::OutputFull => self.grow(),
+                }
+            }
+        }
+        used += self.write_with(used, |core, out| core.terminator(out));
+        dst.extend_from_slice(&self.buf[..used]);
+        Ok(())
+    }
+
+    /// Write to the scratch space after `used` bytes with `write`, growing
+    /// the scratch space until the output fits, and return its length.
+    fn write_with<F>(&mut self, used: usize, mut write: F) -> usize
+    where
+        F: FnMut(&mut CoreWriter, &mut [u8]) -> (WriteResult, usize),
+    {
+        loop {
+            match write(&mut self.core, &mut self.buf[used..]) {
+                (WriteResult::InputEmpty, nout) => return nout,
+                (WriteResult::OutputFull, _) => self.grow(),
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        let len = self.buf.len();
+        self.buf.resize(len * 2, 0);
+    }
+}
+
+impl Encoder<ByteRecord> for CsvEncoder {
+    type Error = Error;
+
+    fn encode(&mut self, record: ByteRecord, dst: &mut BytesMut) -> Result<()> {
+        self.encode_record(&record, dst)
+    }
+}
+
+impl<'a> Encoder<&'a ByteRecord> for CsvEncoder {
+    type Error = Error;
+
+    fn encode(&mut self, record: &'a ByteRecord, dst: &mut BytesMut) -> Result<()> {
+        self.encode_record(record, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use futures::sink::SinkExt;
+    use tokio_util::codec::{Encoder, FramedWrite};
+
+    use crate::byte_record::ByteRecord;
+    use crate::error::ErrorKind;
+    use crate::QuoteStyle;
+    use super::{AsyncWriterBuilder, CsvEncoder};
+
+    #[test]
+    fn encode_records() {
+        let mut encoder = CsvEncoder::default();
+        let mut dst = BytesMut::new();
+        encoder.encode(ByteRecord::from(vec!["a", "b,c", "d\"e"]), &mut dst).unwrap();
+        encoder.encode(&ByteRecord::from(vec!["1", "", "3"]), &mut dst).unwrap();
+        assert_eq!(&dst[..], &b"a,\"b,c\",\"d\"\"e\"\n1,,3\n"[..]);
+        let err = encoder.encode(ByteRecord::from(vec!["x"]), &mut dst).unwrap_err();
+        match *err.kind() {
+            ErrorKind::UnequalLengths { expected_len: 3, len: 1, .. } => {}
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+
+        // A single empty field is quoted to tell it apart from an empty row.
+        let mut encoder = AsyncWriterBuilder::new().flexible(true).create_encoder();
+        let mut dst = BytesMut::new();
+        encoder.encode(ByteRecord::from(vec![""]), &mut dst).unwrap();
+        encoder.encode(ByteRecord::from(vec!["a", "b"]), &mut dst).unwrap();
+        assert_eq!(&dst[..], &b"\"\"\na,b\n"[..]);
+    }
+
+    #[test]
+    fn framed_write() {
+        tokio1::runtime::Runtime::new().unwrap().block_on(async {
+            let encoder = AsyncWriterBuilder::new()
+                .quote_style(QuoteStyle::Always)
+                .create_encoder();
+            let mut frames = FramedWrite::new(vec![], encoder);
+            let long = "x".repeat(5000);
+            for i in 0..100 {
+                frames.send(ByteRecord::from(vec![i.to_string(), long.clone()])).await.unwrap();
+            }
+            SinkExt::<ByteRecord>::close(&mut frames).await.unwrap();
+            let data = frames.into_inner();
+            let expected = format!("\"99\",\"{}\"\n", long);
+            assert_eq!(data.len(), 100 * expected.len() - 10);
+            assert!(data.ends_with(expected.as_bytes()));
+        });
+    }
+}
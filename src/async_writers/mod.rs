@@ -1,24 +1,44 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
 use std::result;
+use std::sync::Arc;
+use std::task::{Context, Poll};
 
 use csv_core::WriterBuilder as CoreWriterBuilder;
 use csv_core::{self, WriteResult, Writer as CoreWriter};
+use futures::future::{self, FutureExt};
+use futures::ready;
+use futures::sink::Sink;
+use futures::stream::Stream;
 cfg_if::cfg_if! {
 if #[cfg(feature = "tokio")] {
     use tokio::io::{self, AsyncWrite, AsyncWriteExt};
+    #[cfg(feature = "serde_json")]
+    use tokio::io::AsyncBufReadExt;
 } else {
     use futures::io::{self, AsyncWrite, AsyncWriteExt};
+    #[cfg(feature = "serde_json")]
+    use futures::io::AsyncBufReadExt;
 }}
     
 
-use crate::{QuoteStyle, Terminator};
+use crate::{Alignment, NewlineMode, QuoteStyle, Terminator};
 use crate::byte_record::ByteRecord;
+use crate::footer::{Adler32, FooterSpec};
 use crate::error::{Error, ErrorKind, IntoInnerError, Result};
 
 #[cfg(feature = "with_serde")]
 pub mod mwtr_serde;
 
+pub mod tee;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+
 cfg_if::cfg_if! {
 if #[cfg(feature = "tokio")] {
+    pub mod atomic;
     pub mod awtr_tokio;
 } else {
     pub mod awtr_futures;
@@ -34,6 +54,10 @@ pub mod aser_tokio;
 //-// Builder
 //-//////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The smallest buffer the core writer always makes progress with: a quote
+/// followed by a delimiter, an escaped quote or a CRLF terminator.
+const MIN_BUFFER_CAPACITY: usize = 2;
+
 /// Builds a CSV writer with various configuration knobs.
 ///
 /// This builder can be used to tweak the field delimiter, record terminator
@@ -45,6 +69,26 @@ pub struct AsyncWriterBuilder {
     capacity: usize,
     flexible: bool,
     has_headers: bool,
+    fixed_width: Option<Vec<usize>>,
+    padding: u8,
+    alignment: Alignment,
+    truncate: bool,
+    newline_in_field: NewlineMode,
+    quote_if: Option<QuotePredicate>,
+    footer: FooterSpec,
+    sort_window: Option<(usize, usize)>,
+    row_numbers: Option<String>,
+    row_numbers_start: u64,
+    write_sep_directive: bool,
+    flush_after_bytes: Option<usize>,
+    flush_after_records: Option<u64>,
+    comment: Option<u8>,
+    #[cfg(feature = "with_serde")]
+    tagged_enums: bool,
+    #[cfg(feature = "with_serde")]
+    none_as: Vec<u8>,
+    #[cfg(feature = "base64")]
+    base64_columns: Vec<usize>,
 }
 
 impl Default for AsyncWriterBuilder {
@@ -54,6 +98,26 @@ impl Default for AsyncWriterBuilder {
             capacity: 8 * (1 << 10),
             flexible: false,
             has_headers: true,
+            fixed_width: None,
+            padding: b' ',
+            alignment: Alignment::Left,
+            truncate: false,
+            newline_in_field: NewlineMode::Keep,
+            quote_if: None,
+            footer: FooterSpec::default(),
+            sort_window: None,
+            row_numbers: None,
+            row_numbers_start: 1,
+            write_sep_directive: false,
+            flush_after_bytes: None,
+            flush_after_records: None,
+            comment: None,
+            #[cfg(feature = "with_serde")]
+            tagged_enums: false,
+            #[cfg(feature = "with_serde")]
+            none_as: Vec::new(),
+            #[cfg(feature = "base64")]
+            base64_columns: Vec::new(),
         }
     }
 }
@@ -123,10 +187,10 @@ impl AsyncWriterBuilder {
     }
     /// Whether to write a header row before writing any other row.
     ///
-    /// When this is enabled and the `serialize` method is used to write data
-    /// with something that contains field names (i.e., a struct), then a
-    /// header row is written containing the field names before any other row
-    /// is written.
+    /// When this is enabled and the `serialize` method of an
+    /// `AsyncSerializer` is used to write data with something that contains
+    /// field names (i.e., a struct), then a header row is written containing
+    /// the field names before any other row is written.
     ///
     /// This option has no effect when using other methods to write rows. That
     /// is, if you don't use `serialize`, then you must write your header row
@@ -134,76 +198,76 @@ impl AsyncWriterBuilder {
     ///
     /// This is enabled by default.
     ///
-    // / # Example: with headers
-    // /
-    // / This shows how the header will be automatically written from the field
-    // / names of a struct.
-    // /
-    // / ```
-    // / use std::error::Error;
-    // /
-    // / use csv::WriterBuilder;
-    // / use serde::Serialize;
-    // /
-    // / #[derive(Serialize)]
-    // / struct Row<'a> {
-    // /     city: &'a str,
-    // /     country: &'a str,
-    // /     // Serde allows us to name our headers exactly,
-    // /     // even if they don't match our struct field names.
-    // /     #[serde(rename = "popcount")]
-    // /     population: u64,
-    // / }
-    // /
-    // / # fn main() { example().unwrap(); }
-    // / fn example() -> Result<(), Box<dyn Error>> {
-    // /     let mut wtr = WriterBuilder::new().from_writer(vec![]);
-    // /     wtr.serialize(Row {
-    // /         city: "Boston",
-    // /         country: "United States",
-    // /         population: 4628910,
-    // /     })?;
-    // /     wtr.serialize(Row {
-    // /         city: "Concord",
-    // /         country: "United States",
-    // /         population: 42695,
-    // /     })?;
-    // /
-    // /     let data = String::from_utf8(wtr.into_inner()?)?;
-    // /     assert_eq!(data, "\
-    // / city,country,popcount
-    // / Boston,United States,4628910
-    // / Concord,United States,42695
-    // / ");
-    // /     Ok(())
-    // / }
-    // / ```
-    // /
-    // / # Example: without headers
-    // /
-    // / This shows that serializing things that aren't structs (in this case,
-    // / a tuple struct) won't result in a header row being written. This means
-    // / you usually don't need to set `has_headers(false)` unless you
-    // / explicitly want to both write custom headers and serialize structs.
-    // /
-    // / ```
-    // / use std::error::Error;
-    // / use csv::WriterBuilder;
-    // /
-    // / # fn main() { example().unwrap(); }
-    // / fn example() -> Result<(), Box<dyn Error>> {
-    // /     let mut wtr = WriterBuilder::new().from_writer(vec![]);
-    // /     wtr.serialize(("Boston", "United States", 4628910))?;
-    // /     wtr.serialize(("Concord", "United States", 42695))?;
-    // /
-    // /     let data = String::from_utf8(wtr.into_inner()?)?;
-    // /     assert_eq!(data, "\
-    // / Boston,United States,4628910
-    // / Concord,United States,42695
-    // / ");
-    // /     Ok(())
-    // / }
-    // / ```
+    /// # Example: with headers
+    ///
+    /// This shows how the header will be automatically written from the field
+    /// names of a struct.
+    ///
+    /// ```
+    /// use std::error::Error;
+    ///
+    /// use csv_async::AsyncWriterBuilder;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row<'a> {
+    ///     city: &'a str,
+    ///     country: &'a str,
+    ///     // Serde allows us to name our headers exactly,
+    ///     // even if they don't match our struct field names.
+    ///     #[serde(rename = "popcount")]
+    ///     population: u64,
+    /// }
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut ser = AsyncWriterBuilder::new().create_serializer(vec![]);
+    ///     ser.serialize(Row {
+    ///         city: "Boston",
+    ///         country: "United States",
+    ///         population: 4628910,
+    ///     }).await?;
+    ///     ser.serialize(Row {
+    ///         city: "Concord",
+    ///         country: "United States",
+    ///         population: 42695,
+    ///     }).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "\
+    /// city,country,popcount
+    /// Boston,United States,4628910
+    /// Concord,United States,42695
+    /// ");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Example: without headers
+    ///
+    /// This shows that serializing things that aren't structs (in this case,
+    /// a tuple) won't result in a header row being written. This means
+    /// you usually don't need to set `has_headers(false)` unless you
+    /// explicitly want to both write custom headers and serialize structs.
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut ser = AsyncWriterBuilder::new().create_serializer(vec![]);
+    ///     ser.serialize(("Boston", "United States", 4628910)).await?;
+    ///     ser.serialize(("Concord", "United States", 42695)).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "\
+    /// Boston,United States,4628910
+    /// Concord,United States,42695
+    /// ");
+    ///     Ok(())
+    /// }
+    /// ```
     pub fn has_headers(&mut self, yes: bool) -> &mut AsyncWriterBuilder {
         self.has_headers = yes;
         self
@@ -285,6 +349,92 @@ impl AsyncWriterBuilder {
         self
     }
 
+    /// Whether Serde serialization writes enum variants with data as
+    /// a variant name column followed by the payload.
+    ///
+    /// When disabled (which is the default), a newtype variant is written as
+    /// just its payload, and tuple and struct variants can not be serialized.
+    ///
+    /// When enabled, newtype, tuple and struct variants are written as the
+    /// variant name, followed by the fields of the payload. This is the shape
+    /// the deserializer expects for externally tagged enums, so such records
+    /// can be read back into the enum, e.g. `Result<T, E>`.
+    ///
+    /// There is no schema shared by the variants. Each record has as many
+    /// fields as its variant, so writing variants of different sizes requires
+    /// `flexible`. When headers are enabled, the header row is derived from
+    /// the first record only: the enum name (as the tag column) followed by
+    /// the field names of a struct payload. If the payload is not a struct,
+    /// no header row is written. Tagged enums in struct fields can not be
+    /// serialized while a header row is being derived.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut ser = AsyncWriterBuilder::new()
+    ///         .tagged_enums(true)
+    ///         .create_serializer(vec![]);
+    ///     ser.serialize(Ok::<u32, String>(5)).await?;
+    ///     ser.serialize(Err::<u32, String>("out of range".to_string())).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "Ok,5\nErr,out of range\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "with_serde")]
+    pub fn tagged_enums(&mut self, yes: bool) -> &mut AsyncWriterBuilder {
+        self.tagged_enums = yes;
+        self
+    }
+
+    /// The field written by Serde serialization for `None` values.
+    ///
+    /// By default, `None` is written as an empty field. This sets another
+    /// token to write instead, like `NULL` or `NA`. The token is quoted
+    /// according to the quoting style like any other field.
+    ///
+    /// This only affects `AsyncSerializer`. Note that reading the data back
+    /// into `Option` fields requires the token to be handled, as only empty
+    /// fields are deserialized as `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     city: &'static str,
+    ///     pop: Option<u64>,
+    /// }
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut ser = AsyncWriterBuilder::new()
+    ///         .none_as("NULL")
+    ///         .create_serializer(vec![]);
+    ///     ser.serialize(Row { city: "Boston", pop: Some(4628910) }).await?;
+    ///     ser.serialize(Row { city: "Concord", pop: None }).await?;
+    ///
+    ///     let data = String::from_utf8(ser.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\nConcord,NULL\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "with_serde")]
+    pub fn none_as(&mut self, token: &str) -> &mut AsyncWriterBuilder {
+        self.none_as = token.as_bytes().to_vec();
+        self
+    }
+
     /// The record terminator to use when writing CSV.
     ///
     /// A record terminator can be any single byte. The default is `\n`.
@@ -464,6 +614,40 @@ impl AsyncWriterBuilder {
         self.builder.escape(escape);
         self
     }
+
+    /// The comment character that starts the lines written by
+    /// `write_comment`.
+    ///
+    /// When set, fields containing the comment character are quoted, so
+    /// that a reader using the same `comment` option does not take a record
+    /// starting with it for a comment.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .comment(Some(b'#'))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_comment("exported by example").await?;
+    ///     wtr.write_record(&["#1", "a"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "#exported by example\n\"#1\",a\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut AsyncWriterBuilder {
+        self.builder.comment(comment);
+        self.comment = comment;
+        self
+    }
     
     /// Returns buffer capacity.
     #[deprecated(
@@ -476,132 +660,1078 @@ impl AsyncWriterBuilder {
 
     /// Set the capacity (in bytes) of the internal buffer used in the CSV
     /// writer. This defaults to a reasonable setting.
+    ///
+    /// The buffer is written to the underlying writer whenever it is full,
+    /// so large buffers mean fewer (and larger) writes. The capacity is at
+    /// least 2 bytes, which is the most the writer needs to make progress.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .buffer_capacity(4 * (1 << 20))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "a,b,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
     pub fn buffer_capacity(&mut self, capacity: usize) -> &mut AsyncWriterBuilder {
         self.capacity = capacity;
         self
     }
-}
-
-//-//////////////////////////////////////////////////////////////////////////////////////////////
-//-// Writer
-//-//////////////////////////////////////////////////////////////////////////////////////////////
-
-#[derive(Debug)]
-struct WriterState {
-    /// Whether inconsistent record lengths are allowed.
-    flexible: bool,
-    /// The number of fields writtein in the first record. This is compared
-    /// with `fields_written` on all subsequent records to check for
-    /// inconsistent record lengths.
-    first_field_count: Option<u64>,
-    /// The number of fields written in this record. This is used to report
-    /// errors for inconsistent record lengths if `flexible` is disabled.
-    fields_written: u64,
-    /// This is set immediately before flushing the buffer and then unset
-    /// immediately after flushing the buffer. This avoids flushing the buffer
-    /// twice if the inner writer panics.
-    panicked: bool,
-}
-
-/// A simple internal buffer for buffering writes.
-///
-/// We need this because the `csv_core` APIs want to write into a `&mut [u8]`,
-/// which is not available with the `std::io::BufWriter` API.
-#[derive(Debug)]
-struct Buffer {
-    /// The contents of the buffer.
-    buf: Vec<u8>,
-    /// The number of bytes written to the buffer.
-    len: usize,
-}
 
-impl Buffer {
-    /// Returns a slice of the buffer's current contents.
+    /// Flush automatically once at least this many bytes were written since
+    /// the last flush.
     ///
-    /// The slice returned may be empty.
-    #[inline]
-    fn readable(&self) -> &[u8] {
-        &self.buf[..self.len]
+    /// Normally, the internal buffer is written out only when it is full,
+    /// and the underlying writer is flushed only by `flush`. With this
+    /// option, the writer flushes both itself and the underlying writer
+    /// after writing a record that reaches the threshold, so a long-running
+    /// writer to a socket does not hold data indefinitely.
+    ///
+    /// Records held back by a `sort_window` are not written by an automatic
+    /// flush. This applies to `AsyncWriter` only. It is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .flush_after_bytes(Some(64 * 1024))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "a,b,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn flush_after_bytes(&mut self, bytes: Option<usize>) -> &mut AsyncWriterBuilder {
+        self.flush_after_bytes = bytes;
+        self
     }
 
-    /// Returns a mutable slice of the remaining space in this buffer.
+    /// Flush automatically after every `records` rows written, counting the
+    /// header row.
     ///
-    /// The slice returned may be empty.
-    #[inline]
-    fn writable(&mut self) -> &mut [u8] {
-        &mut self.buf[self.len..]
+    /// This flushes the writer and the underlying writer like `flush`,
+    /// except that records held back by a `sort_window` are not written.
+    /// It may be combined with `flush_after_bytes`, flushing when either
+    /// threshold is reached. This applies to `AsyncWriter` only. It is
+    /// disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .flush_after_records(Some(100))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["a", "b", "c"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "a,b,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn flush_after_records(&mut self, records: Option<u64>) -> &mut AsyncWriterBuilder {
+        self.flush_after_records = records;
+        self
     }
 
-    /// Indicates that `n` bytes have been written to this buffer.
-    #[inline]
-    fn written(&mut self, n: usize) {
-        self.len += n;
+    /// Write records as fixed-width columns instead of delimited fields.
+    ///
+    /// When set, every field is padded to the width (in bytes) of its column
+    /// and written without delimiters or quotes. Records are still ended with
+    /// the configured terminator. Each record must have exactly as many
+    /// fields as there are widths.
+    ///
+    /// Fields longer than their column result in an `ErrorKind::FieldTooWide`
+    /// error, unless `fixed_width_truncate` is enabled.
+    ///
+    /// This mode applies to `AsyncWriter` only. It is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{Alignment, AsyncWriterBuilder};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .fixed_width(Some(&[5, 4]))
+    ///         .fixed_width_alignment(Alignment::Right)
+    ///         .fixed_width_padding(b'0')
+    ///         .from_writer(vec![]);
+    ///     wtr.write_record(&["abc", "12"]).await?;
+    ///     wtr.write_record(&["x", "345"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "00abc0012\n0000x0345\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn fixed_width(&mut self, widths: Option<&[usize]>) -> &mut AsyncWriterBuilder {
+        self.fixed_width = widths.map(|w| w.to_vec());
+        self
     }
 
-    /// Clear the buffer.
-    #[inline]
-    fn clear(&mut self) {
-        self.len = 0;
+    /// The byte used to pad fields in fixed-width mode.
+    ///
+    /// The default is `b' '`.
+    pub fn fixed_width_padding(&mut self, padding: u8) -> &mut AsyncWriterBuilder {
+        self.padding = padding;
+        self
     }
-}
-
-/// CSV async writer internal implementation used by both record writer and serializer.
-/// 
-#[derive(Debug)]
-pub struct AsyncWriterImpl<W: AsyncWrite + Unpin> {
-    core: CoreWriter,
-    wtr: Option<W>,
-    buf: Buffer,
-    state: WriterState,
-}
 
-impl<W: AsyncWrite + Unpin> Drop for AsyncWriterImpl<W> {
-    fn drop(&mut self) {
-        if self.wtr.is_some() && !self.state.panicked {
-            // We ignore result of flush() call while dropping
-            // Well known problem.
-            // If you care about flush result call it explicitly 
-            // before AsyncWriter goes out of scope,
-            // second flush() call should be no op.
-            let _ = futures::executor::block_on(self.flush());
-        }
+    /// The alignment of fields within their columns in fixed-width mode.
+    ///
+    /// The default is `Alignment::Left`.
+    pub fn fixed_width_alignment(&mut self, alignment: Alignment) -> &mut AsyncWriterBuilder {
+        self.alignment = alignment;
+        self
     }
-}
 
-impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
-    fn new(builder: &AsyncWriterBuilder, wtr: W) -> AsyncWriterImpl<W> {
-        AsyncWriterImpl {
-            core: builder.builder.build(),
-            wtr: Some(wtr),
-            buf: Buffer { buf: vec![0; builder.capacity], len: 0 },
-            state: WriterState {
-                flexible: builder.flexible,
-                first_field_count: None,
-                fields_written: 0,
-                panicked: false,
-            },
-        }
+    /// Whether to truncate fields that do not fit into their column in
+    /// fixed-width mode.
+    ///
+    /// Fields are cut at the column width in bytes, which may split
+    /// a multi-byte UTF-8 character. When disabled (the default), writing
+    /// such a field returns an error.
+    pub fn fixed_width_truncate(&mut self, yes: bool) -> &mut AsyncWriterBuilder {
+        self.truncate = yes;
+        self
     }
 
-    /// Write a single record.
+    /// Encode the fields in the given columns as base64 when writing records.
     ///
-    pub async fn write_record<I, T>(&mut self, record: I) -> Result<()>
-    where
-        I: IntoIterator<Item = T>,
-        T: AsRef<[u8]>,
-    {
-        for field in record.into_iter() {
-            self.write_field_impl(field).await?;
-        }
-        self.write_terminator().await
-    }
-
-    /// Write a single `ByteRecord`.
+    /// Columns are identified by their zero based index. This applies to
+    /// `write_record`, `write_byte_record` and `write_field`, so arbitrary
+    /// binary data can be written to these columns. It is the counterpart of
+    /// `AsyncReaderBuilder::base64_columns`, using the standard alphabet with
+    /// padding.
+    ///
+    /// When `has_headers` is enabled (the default), the first record (and
+    /// the first record after `end_table`) is taken to be the header row and
+    /// is written as is.
+    ///
+    /// This is only available with the `base64` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .base64_columns(&[1])
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["name", "blob"]).await?;
+    ///     wtr.write_record(&[&b"foo"[..], &[0xde, 0xad, 0xbe, 0xef][..]]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "name,blob\nfoo,3q2+7w==\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn base64_columns(&mut self, columns: &[usize]) -> &mut AsyncWriterBuilder {
+        self.base64_columns = columns.to_vec();
+        self
+    }
+
+    /// Set how fields containing line breaks are written.
+    ///
+    /// By default (`NewlineMode::Keep`), such fields are written as they
+    /// are, quoted, which results in records spanning several lines. Naive
+    /// consumers may be confused by that, so line breaks (`\r\n`, `\r` or
+    /// `\n`) can be replaced with a byte of choice instead, or fields with
+    /// line breaks refused with a `NewlineInField` error naming the field.
+    ///
+    /// This applies to all fields, including those of the header row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncWriterBuilder, NewlineMode};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .newline_in_field(NewlineMode::Replace(b' '))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["city", "address"]).await?;
+    ///     wtr.write_record(&["Boston", "1 Main St\r\nSuite 2"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,address\nBoston,1 Main St Suite 2\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn newline_in_field(&mut self, mode: NewlineMode) -> &mut AsyncWriterBuilder {
+        self.newline_in_field = mode;
+        self
+    }
+
+    /// Quote every field for which `predicate` returns `true`.
+    ///
+    /// The predicate is called with the zero based index of the field
+    /// within its record and the bytes of the field. When it returns `true`,
+    /// the field is quoted regardless of the `quote_style`, even
+    /// `QuoteStyle::Never`. Otherwise, the `quote_style` decides as usual.
+    ///
+    /// This applies to all fields, including those of the header row, but
+    /// not to fixed-width output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .quote_if(Box::new(|_, field| field.starts_with(b"0")))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["city", "zip"]).await?;
+    ///     wtr.write_record(&["Boston", "02101"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,zip\nBoston,\"02101\"\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn quote_if(
+        &mut self,
+        predicate: Box<QuoteFn>,
+    ) -> &mut AsyncWriterBuilder {
+        self.quote_if = Some(QuotePredicate(Arc::from(predicate)));
+        self
+    }
+
+    /// Set the layout of the footer row written by `AsyncWriter::write_footer`.
+    ///
+    /// The footer counts the data records written, i.e. all records but
+    /// header rows (see `has_headers`). A footer is only written when
+    /// `write_footer` is called.
+    ///
+    /// By default, the footer only contains the record count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{AsyncWriterBuilder, FooterSpec};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .footer(FooterSpec { label: Some("TOTAL".to_string()), checksum: false })
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///     wtr.write_record(&["Concord", "42695"]).await?;
+    ///     wtr.write_footer().await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "city,pop\nBoston,4628910\nConcord,42695\nTOTAL,2\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn footer(&mut self, spec: FooterSpec) -> &mut AsyncWriterBuilder {
+        self.footer = spec;
+        self
+    }
+
+    /// Reorder records by the given column within a window of `size`
+    /// records.
+    ///
+    /// This is meant for streams of records that are only slightly out of
+    /// order, like log lines gathered from several sources. Records written
+    /// with `write_record` or `write_byte_record` are held back until `size`
+    /// records are waiting, and then the one with the smallest value in
+    /// `column` is written each time another record comes in. Values are
+    /// compared as bytes, records without the column compare as an empty
+    /// value, and records with equal values keep their order.
+    ///
+    /// This is not a full sort: a record is only ordered against the records
+    /// held with it, so output is fully sorted only if no record is more
+    /// than `size` records away from its sorted place. Calling `flush`,
+    /// `end_table` or `write_footer` writes all held records, and records
+    /// written afterwards are not ordered against them.
+    ///
+    /// The header row is never reordered. Records written with
+    /// `write_field`, or through a serializer, are not held back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .sort_window(2, 0)
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["time", "event"]).await?;
+    ///     wtr.write_record(&["10:02", "b"]).await?;
+    ///     wtr.write_record(&["10:01", "a"]).await?;
+    ///     wtr.write_record(&["10:03", "c"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "time,event\n10:01,a\n10:02,b\n10:03,c\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sort_window(&mut self, size: usize, column: usize) -> &mut AsyncWriterBuilder {
+        self.sort_window = Some((size, column));
+        self
+    }
+
+    /// Prepend a column of row numbers to every record.
+    ///
+    /// When set to `Some(header)`, every record written, whether with
+    /// `write_record`, `write_byte_record`, `write_field` or through a
+    /// serializer, starts with a field holding its row number, and the
+    /// header row starts with `header`. Rows are numbered from 1 by default,
+    /// see `row_numbers_start`. The footer row is not numbered.
+    ///
+    /// The number column counts as the first column for all options
+    /// referring to columns by index, like `fixed_width` or `quote_if`.
+    ///
+    /// This is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .row_numbers(Some("#"))
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///     wtr.write_record(&["Concord", "42695"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "#,city,pop\n1,Boston,4628910\n2,Concord,42695\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn row_numbers(&mut self, header: Option<&str>) -> &mut AsyncWriterBuilder {
+        self.row_numbers = header.map(str::to_string);
+        self
+    }
+
+    /// The number of the first row when `row_numbers` is set, e.g. 0 for
+    /// zero based numbering.
+    ///
+    /// This is 1 by default.
+    pub fn row_numbers_start(&mut self, start: u64) -> &mut AsyncWriterBuilder {
+        self.row_numbers_start = start;
+        self
+    }
+
+    /// Whether to start the data with a `sep=` line declaring the delimiter.
+    ///
+    /// Spreadsheet applications like Excel guess the delimiter of CSV data
+    /// from the locale, unless the data starts with a line like `sep=;`.
+    /// When enabled and the delimiter is not a comma, the line
+    /// `sep=<delimiter>\r\n` is written before the first row, whether it is
+    /// a header row or not. The line is not part of the footer checksum. See
+    /// `AsyncReaderBuilder::respect_sep_directive` for reading it back.
+    ///
+    /// This is disabled by default, and has no effect on fixed-width data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::AsyncWriterBuilder;
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriterBuilder::new()
+    ///         .delimiter(b';')
+    ///         .write_sep_directive(true)
+    ///         .create_writer(vec![]);
+    ///     wtr.write_record(&["city", "pop"]).await?;
+    ///     wtr.write_record(&["Boston", "4628910"]).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "sep=;\r\ncity;pop\nBoston;4628910\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write_sep_directive(&mut self, yes: bool) -> &mut AsyncWriterBuilder {
+        self.write_sep_directive = yes;
+        self
+    }
+}
+
+/// A predicate deciding which fields are quoted, shared by the writers
+/// created from one builder.
+#[derive(Clone)]
+struct QuotePredicate(Arc<QuoteFn>);
+
+type QuoteFn = dyn Fn(usize, &[u8]) -> bool + Send + Sync;
+
+impl fmt::Debug for QuotePredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("QuotePredicate(..)")
+    }
+}
+
+/// The column of row numbers prepended to records, shared by the writers.
+#[derive(Debug)]
+struct RowNumbers {
+    /// The name of the column in the header row.
+    header: String,
+    /// The number of the next data row.
+    next: u64,
+}
+
+impl RowNumbers {
+    fn new(builder: &AsyncWriterBuilder) -> Option<RowNumbers> {
+        builder.row_numbers.as_ref().map(|header| RowNumbers {
+            header: header.clone(),
+            next: builder.row_numbers_start,
+        })
+    }
+
+    /// The first field of the next record, counting it if it is a data row.
+    fn field(&mut self, header: bool) -> String {
+        let field = self.peek(header);
+        if !header {
+            self.next += 1;
+        }
+        field
+    }
+
+    /// The first field of the next record, without counting it.
+    fn peek(&self, header: bool) -> String {
+        if header {
+            return self.header.clone();
+        }
+        self.next.to_string()
+    }
+}
+
+/// The `sep=` line to write before the first row, which is empty unless
+/// `write_sep_directive` is set and the delimiter of `core` is not a comma.
+fn sep_directive(builder: &AsyncWriterBuilder, core: &CoreWriter) -> Vec<u8> {
+    let delimiter = core.get_delimiter();
+    if !builder.write_sep_directive || delimiter == b',' || builder.fixed_width.is_some() {
+        return Vec::new();
+    }
+    let mut line = b"sep=".to_vec();
+    line.push(delimiter);
+    line.extend_from_slice(b"\r\n");
+    line
+}
+
+/// A core writer configured like `core`, except for its quoting style.
+fn core_with_style(core: &CoreWriter, comment: Option<u8>, style: QuoteStyle) -> CoreWriter {
+    CoreWriterBuilder::new()
+        .delimiter(core.get_delimiter())
+        .terminator(core.get_terminator())
+        .quote(core.get_quote())
+        .escape(core.get_escape())
+        .double_quote(core.get_double_quote())
+        .comment(comment)
+        .quote_style(style.to_core())
+        .build()
+}
+
+/// Quote a field with the quoting settings of `core`, regardless of its
+/// quoting style.
+fn quote_field(core: &CoreWriter, field: &[u8]) -> Vec<u8> {
+    let quote = core.get_quote();
+    // In the worst case, every byte is escaped.
+    let mut quoted = vec![0; 2 * field.len() + 2];
+    quoted[0] = quote;
+    let (_, nin, nout) = csv_core::quote(
+        field,
+        &mut quoted[1..],
+        quote,
+        core.get_escape(),
+        core.get_double_quote(),
+    );
+    debug_assert_eq!(nin, field.len());
+    quoted.truncate(nout + 1);
+    quoted.push(quote);
+    quoted
+}
+
+/// Apply a `NewlineMode` to the field with the given index.
+/// The members of a JSON object, in the order they are written in.
+#[cfg(feature = "serde_json")]
+struct JsonObject(Vec<(String, serde_json::Value)>);
+
+#[cfg(feature = "serde_json")]
+impl JsonObject {
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().map(|(k, _)| k)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<'de> serde::Deserialize<'de> for JsonObject {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+        struct ObjectVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ObjectVisitor {
+            type Value = JsonObject;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> result::Result<JsonObject, A::Error> {
+                let mut members: Vec<(String, serde_json::Value)> = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, serde_json::Value>()? {
+                    // A repeated key keeps its first place and its last value.
+                    match members.iter_mut().find(|(k, _)| *k == key) {
+                        Some(member) => member.1 = value,
+                        None => members.push((key, value)),
+                    }
+                }
+                Ok(JsonObject(members))
+            }
+        }
+
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+fn handle_newlines(mode: NewlineMode, index: u64, field: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if mode == NewlineMode::Keep || !field.iter().any(|&b| b == b'\r' || b == b'\n') {
+        return Ok(Cow::Borrowed(field));
+    }
+    let replacement = match mode {
+        NewlineMode::Replace(byte) => byte,
+        _ => return Err(Error::new(ErrorKind::NewlineInField { field: index })),
+    };
+    let mut replaced = Vec::with_capacity(field.len());
+    let mut bytes = field.iter().peekable();
+    while let Some(&b) = bytes.next() {
+        match b {
+            b'\r' => {
+                if bytes.peek() == Some(&&b'\n') {
+                    bytes.next();
+                }
+                replaced.push(replacement);
+            }
+            b'\n' => replaced.push(replacement),
+            _ => replaced.push(b),
+        }
+    }
+    Ok(Cow::Owned(replaced))
+}
+
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-// Writer
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+struct WriterState {
+    /// Whether inconsistent record lengths are allowed.
+    flexible: bool,
+    /// Whether a header row is written before other rows, when the writer
+    /// knows the field names.
+    has_headers: bool,
+    /// The number of fields writtein in the first record. This is compared
+    /// with `fields_written` on all subsequent records to check for
+    /// inconsistent record lengths.
+    first_field_count: Option<u64>,
+    /// The number of fields written in this record. This is used to report
+    /// errors for inconsistent record lengths if `flexible` is disabled.
+    fields_written: u64,
+    /// This is set immediately before flushing the buffer and then unset
+    /// immediately after flushing the buffer. This avoids flushing the buffer
+    /// twice if the inner writer panics.
+    panicked: bool,
+    /// Column layout when writing fixed-width records.
+    fixed_width: Option<FixedWidth>,
+    /// How fields containing line breaks are written.
+    newline_in_field: NewlineMode,
+    /// Decides which fields are quoted regardless of the quoting style.
+    quote_if: Option<QuotePredicate>,
+    /// Whether the first field of this record was quoted by `quote_if`,
+    /// bypassing the core writer.
+    quoted_first: bool,
+    /// Indices of the fields written base64 encoded.
+    #[cfg(feature = "base64")]
+    base64_columns: Vec<usize>,
+    /// Whether the record being written is a header row, which is never
+    /// base64 encoded nor counted as a data record.
+    header_pending: bool,
+    /// The layout of the footer row.
+    footer: FooterSpec,
+    /// The number of data records written.
+    records: u64,
+    /// The checksum of all bytes written, if the footer includes it.
+    checksum: Adler32,
+    /// The `sep=` line still to be written before the first row.
+    sep_directive: Vec<u8>,
+    /// Records held back to be written in order.
+    sort_window: Option<SortWindow>,
+    /// The column of row numbers prepended to records.
+    row_numbers: Option<RowNumbers>,
+    /// Whether the buffer grows instead of being flushed when full, so that
+    /// records are written without waiting for the underlying writer.
+    grow_buf: bool,
+    /// The number of bytes written since the last flush that trigger an
+    /// automatic flush.
+    flush_after_bytes: Option<usize>,
+    /// The number of rows written since the last flush that trigger an
+    /// automatic flush.
+    flush_after_records: Option<u64>,
+    /// The number of bytes written to the underlying writer since the last
+    /// flush.
+    unflushed_bytes: usize,
+    /// The number of rows written since the last flush.
+    unflushed_records: u64,
+    /// Whether the underlying writer was closed, so dropping the writer
+    /// does not flush it again.
+    closed: bool,
+    /// The character starting comment lines.
+    comment: Option<u8>,
+}
+
+/// Records held back by `AsyncWriterBuilder::sort_window`.
+#[derive(Debug)]
+struct SortWindow {
+    /// The number of records held back.
+    size: usize,
+    /// The index of the column records are ordered by.
+    column: usize,
+    /// The held records, in order.
+    records: VecDeque<ByteRecord>,
+}
+
+impl SortWindow {
+    /// Hold `record`, and return the first held record if there are more
+    /// than `size` of them.
+    fn push(&mut self, record: ByteRecord) -> Option<ByteRecord> {
+        let column = self.column;
+        let new_key = record.get(column).unwrap_or(b"");
+        let len = self.records.len();
+        let at = self
+            .records
+            .iter()
+            .position(|held| held.get(column).unwrap_or(b"") > new_key)
+            .unwrap_or(len);
+        self.records.insert(at, record);
+        if self.records.len() > self.size {
+            self.records.pop_front()
+        } else {
+            None
+        }
+    }
+}
+
+/// Configuration of the fixed-width output mode.
+#[derive(Debug)]
+struct FixedWidth {
+    /// Width of each column, in bytes.
+    widths: Vec<usize>,
+    /// Byte used to fill the unused part of a column.
+    padding: u8,
+    /// Side of the column the field data is aligned to.
+    alignment: Alignment,
+    /// Whether too long fields are cut instead of reported as an error.
+    truncate: bool,
+}
+
+/// A simple internal buffer for buffering writes.
+///
+/// We need this because the `csv_core` APIs want to write into a `&mut [u8]`,
+/// which is not available with the `std::io::BufWriter` API.
+#[derive(Debug)]
+struct Buffer {
+    /// The contents of the buffer.
+    buf: Vec<u8>,
+    /// The number of bytes written to the buffer.
+    len: usize,
+    /// The configured size of the buffer, which it shrinks back to when
+    /// cleared after growing.
+    capacity: usize,
+}
+
+impl Buffer {
+    /// Returns a slice of the buffer's current contents.
+    ///
+    /// The slice returned may be empty.
+    #[inline]
+    fn readable(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns a mutable slice of the remaining space in this buffer.
+    ///
+    /// The slice returned may be empty.
+    #[inline]
+    fn writable(&mut self) -> &mut [u8] {
+        &mut self.buf[self.len..]
+    }
+
+    /// Indicates that `n` bytes have been written to this buffer.
+    #[inline]
+    fn written(&mut self, n: usize) {
+        self.len += n;
+    }
+
+    /// Clear the buffer.
+    #[inline]
+    fn clear(&mut self) {
+        self.len = 0;
+        if self.buf.len() > self.capacity {
+            self.buf.truncate(self.capacity);
+            self.buf.shrink_to_fit();
+        }
+    }
+
+    /// Remove the first `n` bytes of the buffer's contents.
+    fn consume(&mut self, n: usize) {
+        self.buf.copy_within(n..self.len, 0);
+        self.len -= n;
+    }
+
+    /// Double the size of the buffer.
+    fn grow(&mut self) {
+        let len = self.buf.len();
+        self.buf.resize(2 * len, 0);
+    }
+}
+
+/// CSV async writer internal implementation used by both record writer and serializer.
+/// 
+#[derive(Debug)]
+pub struct AsyncWriterImpl<W: AsyncWrite + Unpin> {
+    core: CoreWriter,
+    wtr: Option<W>,
+    buf: Buffer,
+    state: WriterState,
+}
+
+impl<W: AsyncWrite + Unpin> Drop for AsyncWriterImpl<W> {
+    fn drop(&mut self) {
+        if self.wtr.is_some() && !self.state.panicked && !self.state.closed {
+            #[cfg(debug_assertions)]
+            if self.buf.len > 0
+                || matches!(self.state.sort_window, Some(ref w) if !w.records.is_empty())
+            {
+                eprintln!(
+                    "csv_async: a writer was dropped with unflushed data, which is flushed \
+                     by blocking the thread; call `flush` or `close` before dropping it"
+                );
+            }
+            // We ignore result of flush() call while dropping
+            // Well known problem.
+            // If you care about flush result call it explicitly 
+            // before AsyncWriter goes out of scope,
+            // second flush() call should be no op.
+            let _ = futures::executor::block_on(self.flush());
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
+    fn new(builder: &AsyncWriterBuilder, wtr: W) -> AsyncWriterImpl<W> {
+        let core = builder.builder.build();
+        let sep_directive = sep_directive(builder, &core);
+        AsyncWriterImpl {
+            core,
+            wtr: Some(wtr),
+            buf: Buffer {
+                buf: vec![0; builder.capacity.max(MIN_BUFFER_CAPACITY)],
+                len: 0,
+                capacity: builder.capacity.max(MIN_BUFFER_CAPACITY),
+            },
+            state: WriterState {
+                flexible: builder.flexible,
+                has_headers: builder.has_headers,
+                first_field_count: None,
+                fields_written: 0,
+                panicked: false,
+                fixed_width: builder.fixed_width.as_ref().map(|widths| FixedWidth {
+                    widths: widths.clone(),
+                    padding: builder.padding,
+                    alignment: builder.alignment,
+                    truncate: builder.truncate,
+                }),
+                newline_in_field: builder.newline_in_field,
+                quote_if: builder.quote_if.clone(),
+                quoted_first: false,
+                #[cfg(feature = "base64")]
+                base64_columns: builder.base64_columns.clone(),
+                header_pending: builder.has_headers,
+                footer: builder.footer.clone(),
+                records: 0,
+                checksum: Adler32::default(),
+                sep_directive,
+                sort_window: builder.sort_window.map(|(size, column)| SortWindow {
+                    size,
+                    column,
+                    records: VecDeque::new(),
+                }),
+                row_numbers: RowNumbers::new(builder),
+                grow_buf: false,
+                flush_after_bytes: builder.flush_after_bytes,
+                flush_after_records: builder.flush_after_records,
+                unflushed_bytes: 0,
+                unflushed_records: 0,
+                closed: false,
+                comment: builder.comment,
+            },
+        }
+    }
+
+    /// Write a single record.
+    ///
+    pub async fn write_record<I, T>(&mut self, record: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        if self.holds_records() {
+            self.write_held(record.into_iter().collect()).await?;
+        } else {
+            self.write_record_now(record).await?;
+        }
+        self.flush_if_due().await
+    }
+
+    /// Write a single record with the quoting style `style`, bypassing the
+    /// sort window.
+    pub async fn write_record_with_style<I, T>(&mut self, record: I, style: QuoteStyle) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        if self.state.fields_written > 0 {
+            // The core writer is in the middle of a record.
+            self.write_record_now(record).await?;
+        } else {
+            let core = core_with_style(&self.core, self.state.comment, style);
+            let core = std::mem::replace(&mut self.core, core);
+            let result = self.write_record_now(record).await;
+            self.core = core;
+            result?;
+        }
+        self.flush_if_due().await
+    }
+
+    /// Write a single `ByteRecord`.
     ///
-    #[inline(never)]
     pub async fn write_byte_record(&mut self, record: &ByteRecord) -> Result<()> {
-        if record.as_slice().is_empty() {
-            return self.write_record(record).await;
+        if self.holds_records() {
+            let mut held = record.clone();
+            held.set_quoted(None);
+            self.write_held(held).await?;
+        } else {
+            self.write_byte_record_now(record).await?;
+        }
+        self.flush_if_due().await
+    }
+
+    /// Write a single `ByteRecord`, quoting the fields it records as quoted
+    /// in the source data.
+    ///
+    pub async fn write_byte_record_preserving(&mut self, record: &ByteRecord) -> Result<()> {
+        if self.holds_records() {
+            self.write_held(record.clone()).await?;
+        } else {
+            self.write_byte_record_preserving_now(record).await?;
+        }
+        self.flush_if_due().await
+    }
+
+    /// Write a single `ByteRecord` preserving its quoted fields, bypassing
+    /// the sort window.
+    async fn write_byte_record_preserving_now(&mut self, record: &ByteRecord) -> Result<()> {
+        let quoted = |i: usize| record.was_quoted(i) == Some(true);
+        if !(0..record.len()).any(quoted) {
+            return self.write_byte_record_now(record).await;
+        }
+        let fields: Vec<&[u8]> = record.iter().collect();
+        self.check_record(&fields)?;
+        for (i, field) in fields.into_iter().enumerate() {
+            if let Err(err) = self.write_numbered_field(field, quoted(i)).await {
+                self.abandon_record();
+                return Err(err);
+            }
+        }
+        self.write_terminator().await
+    }
+
+    /// Whether the next record is to be held back by the sort window.
+    fn holds_records(&self) -> bool {
+        self.state.sort_window.is_some()
+            && !self.state.header_pending
+            && self.state.fields_written == 0
+    }
+
+    /// Hold `record` in the sort window, writing the record it pushes out.
+    /// Held records keep their quoted fields only if written with
+    /// `write_byte_record_preserving`.
+    async fn write_held(&mut self, record: ByteRecord) -> Result<()> {
+        let first = self.state.sort_window.as_mut().and_then(|w| w.push(record));
+        match first {
+            Some(first) => self.write_byte_record_preserving_now(&first).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Write all records held in the sort window.
+    async fn write_all_held(&mut self) -> Result<()> {
+        loop {
+            let record = match self.state.sort_window {
+                Some(ref mut window) => window.records.pop_front(),
+                None => None,
+            };
+            match record {
+                Some(record) => self.write_byte_record_preserving_now(&record).await?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Write a single record, bypassing the sort window.
+    async fn write_record_now<I, T>(&mut self, record: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        if !self.checks_records() {
+            for field in record.into_iter() {
+                self.write_field_impl(field).await?;
+            }
+            return self.write_terminator().await;
+        }
+        let fields: Vec<T> = record.into_iter().collect();
+        self.check_record(&fields)?;
+        for field in fields {
+            if let Err(err) = self.write_field_impl(field).await {
+                self.abandon_record();
+                return Err(err);
+            }
+        }
+        self.write_terminator().await
+    }
+
+    /// Whether records are checked in full by `check_record` before any of
+    /// their bytes is written.
+    fn checks_records(&self) -> bool {
+        self.state.fixed_width.is_some() || self.state.newline_in_field == NewlineMode::Error
+    }
+
+    /// Check that `fields`, the rest of the current record, can be written
+    /// in full, so a rejected record does not leave a partial record behind.
+    fn check_record<T: AsRef<[u8]>>(&self, fields: &[T]) -> Result<()> {
+        if !self.checks_records() {
+            return Ok(());
+        }
+        let number = match self.state.row_numbers {
+            Some(ref numbers) if self.state.fields_written == 0 => {
+                Some(numbers.peek(self.state.header_pending))
+            }
+            _ => None,
+        };
+        let start = self.state.fields_written;
+        let len = start + number.is_some() as u64 + fields.len() as u64;
+        let fw = self.state.fixed_width.as_ref();
+        if let Some(fw) = fw {
+            if len != fw.widths.len() as u64 {
+                return Err(Error::new(ErrorKind::UnequalLengths {
+                    pos: None,
+                    expected_len: fw.widths.len() as u64,
+                    len,
+                }));
+            }
+        }
+        let fields = number
+            .as_ref()
+            .map(String::as_bytes)
+            .into_iter()
+            .chain(fields.iter().map(AsRef::as_ref));
+        for (idx, field) in (start..).zip(fields) {
+            // Line breaks are reported here, according to `newline_in_field`.
+            let field = self.encode_field(idx, field)?;
+            let fw = match fw {
+                Some(fw) => fw,
+                None => continue,
+            };
+            let width = fw.widths[idx as usize];
+            if field.len() > width && !fw.truncate {
+                return Err(Error::new(ErrorKind::FieldTooWide {
+                    field: idx,
+                    width: width as u64,
+                    len: field.len() as u64,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Give up on the record being written after an error.
+    fn abandon_record(&mut self) {
+        self.state.fields_written = 0;
+        self.state.quoted_first = false;
+    }
+
+    /// Write a single `ByteRecord`, bypassing the sort window.
+    #[inline(never)]
+    async fn write_byte_record_now(&mut self, record: &ByteRecord) -> Result<()> {
+        if record.as_slice().is_empty() || self.state.fixed_width.is_some() {
+            return self.write_record_now(record).await;
+        }
+        #[cfg(feature = "base64")]
+        if !self.state.base64_columns.is_empty() {
+            return self.write_record_now(record).await;
+        }
+        if self.state.newline_in_field != NewlineMode::Keep
+            || self.state.quote_if.is_some()
+            || self.state.row_numbers.is_some()
+        {
+            return self.write_record_now(record).await;
         }
         // The idea here is to find a fast path for shuffling our record into
         // our buffer as quickly as possible. We do this because the underlying
@@ -621,7 +1751,7 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
             // The maximum number of bytes for the terminator.
             + 2;
         if self.buf.writable().len() < upper_bound {
-            return self.write_record(record).await;
+            return self.write_record_now(record).await;
         }
         let mut first = true;
         for field in record.iter() {
@@ -661,16 +1791,52 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
         self.write_field_impl(field).await
     }
 
+    /// End the record whose fields were written with `write_field`.
+    pub async fn end_record(&mut self) -> Result<()> {
+        self.write_terminator().await?;
+        self.flush_if_due().await
+    }
+
     /// Implementation of write_field.
     ///
     /// This is a separate method so we can force the compiler to inline it
     /// into write_record.
     #[inline(always)]
     async fn write_field_impl<T: AsRef<[u8]>>(&mut self, field: T) -> Result<()> {
+        self.write_numbered_field(field.as_ref(), false).await
+    }
+
+    /// Write a single field, starting the record with its row number, and
+    /// in quotes regardless of the quoting style if `force_quote` is set.
+    async fn write_numbered_field(&mut self, field: &[u8], force_quote: bool) -> Result<()> {
+        if self.state.fields_written == 0 {
+            let header = self.state.header_pending;
+            if let Some(number) = self.state.row_numbers.as_mut().map(|n| n.field(header)) {
+                self.write_unnumbered_field(number.as_bytes(), false).await?;
+            }
+        }
+        self.write_unnumbered_field(field, force_quote).await
+    }
+
+    /// Write a single field, without starting the record with its row
+    /// number.
+    async fn write_unnumbered_field(&mut self, field: &[u8], force_quote: bool) -> Result<()> {
+        let field = self.encode_field(self.state.fields_written, field)?;
+        if self.state.fixed_width.is_some() {
+            return self.write_fixed_width_field(&field).await;
+        }
+        if force_quote {
+            return self.write_quoted_field(&field).await;
+        }
+        if let Some(ref quote_if) = self.state.quote_if {
+            if (quote_if.0)(self.state.fields_written as usize, &field) {
+                return self.write_quoted_field(&field).await;
+            }
+        }
         if self.state.fields_written > 0 {
             self.write_delimiter().await?;
         }
-        let mut field = field.as_ref();
+        let mut field = &field[..];
         loop {
             let (res, nin, nout) = self.core.field(field, self.buf.writable());
             field = &field[nin..];
@@ -685,25 +1851,307 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
         }
     }
 
+    /// The bytes written for the field with the given index, base64 encoded
+    /// or with its line breaks handled.
+    fn encode_field<'a>(&self, idx: u64, field: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        #[cfg(feature = "base64")]
+        {
+            if !self.state.header_pending && self.state.base64_columns.contains(&(idx as usize)) {
+                return Ok(Cow::Owned(base64::encode(field).into_bytes()));
+            }
+        }
+        handle_newlines(self.state.newline_in_field, idx, field)
+    }
+
+    /// Write a single field in quotes, bypassing the core writer.
+    async fn write_quoted_field(&mut self, field: &[u8]) -> Result<()> {
+        if self.state.fields_written > 0 {
+            self.write_delimiter().await?;
+        } else {
+            self.state.quoted_first = true;
+        }
+        self.write_raw(&quote_field(&self.core, field)).await?;
+        self.state.fields_written += 1;
+        Ok(())
+    }
+
+    /// Write a single field padded (or truncated) to the width of its column.
+    async fn write_fixed_width_field(&mut self, field: &[u8]) -> Result<()> {
+        let fw = self.state.fixed_width.as_ref().unwrap();
+        let idx = self.state.fields_written;
+        let width = match fw.widths.get(idx as usize) {
+            Some(&width) => width,
+            None => {
+                return Err(Error::new(ErrorKind::UnequalLengths {
+                    pos: None,
+                    expected_len: fw.widths.len() as u64,
+                    len: idx + 1,
+                }))
+            }
+        };
+        let field = if field.len() <= width {
+            field
+        } else if fw.truncate {
+            &field[..width]
+        } else {
+            return Err(Error::new(ErrorKind::FieldTooWide {
+                field: idx,
+                width: width as u64,
+                len: field.len() as u64,
+            }));
+        };
+        let (padding, alignment) = (fw.padding, fw.alignment);
+        let padding_len = width - field.len();
+        match alignment {
+            Alignment::Left => {
+                self.write_raw(field).await?;
+                self.write_repeated(padding, padding_len).await?;
+            }
+            Alignment::Right => {
+                self.write_repeated(padding, padding_len).await?;
+                self.write_raw(field).await?;
+            }
+        }
+        self.state.fields_written += 1;
+        Ok(())
+    }
+
+    /// Write `byte` `n` times into the internal buffer, flushing it as
+    /// needed.
+    async fn write_repeated(&mut self, byte: u8, mut n: usize) -> Result<()> {
+        while n > 0 {
+            if self.buf.writable().is_empty() {
+                self.flush_buf().await?;
+            }
+            let len = std::cmp::min(n, self.buf.writable().len());
+            for b in &mut self.buf.writable()[..len] {
+                *b = byte;
+            }
+            self.buf.written(len);
+            n -= len;
+        }
+        Ok(())
+    }
+
+    /// Copy bytes verbatim into the internal buffer, flushing it as needed.
+    async fn write_raw(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            if self.buf.writable().is_empty() {
+                self.flush_buf().await?;
+            }
+            let n = std::cmp::min(data.len(), self.buf.writable().len());
+            self.buf.writable()[..n].copy_from_slice(&data[..n]);
+            self.buf.written(n);
+            data = &data[n..];
+        }
+        Ok(())
+    }
+
+    /// End the current table with a blank line, so the records written next
+    /// start a new table.
+    pub async fn end_table(&mut self) -> Result<()> {
+        self.write_all_held().await?;
+        if self.state.fields_written > 0 {
+            self.write_terminator().await?;
+        }
+        self.write_raw_terminator().await?;
+        self.state.first_field_count = None;
+        self.state.header_pending = self.state.has_headers;
+        Ok(())
+    }
+
+    /// Write `text` as comment lines, each started with the comment
+    /// character.
+    pub async fn write_comment(&mut self, text: &str) -> Result<()> {
+        let comment = match self.state.comment {
+            Some(comment) => comment,
+            None => {
+                return Err(Error::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "no comment character was set on the writer builder",
+                )))
+            }
+        };
+        self.write_all_held().await?;
+        if self.state.fields_written > 0 {
+            self.write_terminator().await?;
+        }
+        for line in text.split('\n') {
+            self.write_raw(&[comment]).await?;
+            self.write_raw(line.trim_end_matches('\r').as_bytes()).await?;
+            self.write_raw_terminator().await?;
+        }
+        Ok(())
+    }
+
+    /// Write the footer row configured with `AsyncWriterBuilder::footer`.
+    pub async fn write_footer(&mut self) -> Result<()> {
+        self.write_all_held().await?;
+        if self.state.fields_written > 0 {
+            self.write_terminator().await?;
+        }
+        // All bytes so far have to be part of the checksum.
+        self.flush_buf().await?;
+        let fields = self.state.footer.fields(self.state.records, self.state.checksum.value());
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                self.write_delimiter().await?;
+            }
+            let mut field = field.as_bytes();
+            loop {
+                let (res, nin, nout) = self.core.field(field, self.buf.writable());
+                field = &field[nin..];
+                self.buf.written(nout);
+                match res {
+                    WriteResult::InputEmpty => break,
+                    WriteResult::OutputFull => self.flush_buf().await?,
+                }
+            }
+        }
+        self.write_raw_terminator().await?;
+        self.state.fields_written = 0;
+        Ok(())
+    }
+
+    /// Read JSON objects, one per line, from `input` and write each of them
+    /// as a record.
+    #[cfg(feature = "serde_json")]
+    pub async fn write_ndjson<R: io::AsyncRead + Unpin>(
+        &mut self,
+        input: R,
+        encode_nested: bool,
+    ) -> Result<()> {
+        use serde_json::Value;
+
+        let json_error = |line, msg: String| Error::new(ErrorKind::Json { line, msg });
+        let mut input = io::BufReader::new(input);
+        let mut headers: Option<Vec<String>> = None;
+        let mut buf = String::new();
+        let mut line = 0;
+        loop {
+            buf.clear();
+            if input.read_line(&mut buf).await? == 0 {
+                return Ok(());
+            }
+            line += 1;
+            if buf.trim().is_empty() {
+                continue;
+            }
+            let object: JsonObject = serde_json::from_str(&buf)
+                .map_err(|err| json_error(line, err.to_string()))?;
+            let first = headers.is_none();
+            let keys = headers.get_or_insert_with(|| object.keys().cloned().collect());
+            if !self.state.flexible
+                && (keys.len() != object.len() || keys.iter().any(|k| object.get(k).is_none()))
+            {
+                return Err(json_error(
+                    line,
+                    "object keys differ from the keys of the first object".to_string(),
+                ));
+            }
+            if first && self.state.has_headers {
+                self.write_record(keys.iter()).await?;
+            }
+            let extra = object.0.iter().filter(|(k, _)| !keys.contains(k));
+            let values = keys.iter().map(|k| object.get(k)).chain(extra.map(|(_, v)| Some(v)));
+            let mut record = Vec::with_capacity(object.len());
+            for value in values {
+                record.push(match value {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(v @ Value::Array(_)) | Some(v @ Value::Object(_)) => {
+                        if !encode_nested {
+                            return Err(json_error(
+                                line,
+                                "nested arrays and objects can not be written as fields".to_string(),
+                            ));
+                        }
+                        v.to_string()
+                    }
+                    Some(v) => v.to_string(),
+                });
+            }
+            self.write_record(&record).await?;
+        }
+    }
+
     /// Flush the contents of the internal buffer to the underlying writer.
     ///
     /// If there was a problem writing to the underlying writer, then an error
     /// is returned.
     ///
-    /// Note that this also flushes the underlying writer.
+    /// Note that this also flushes the underlying writer, after writing the
+    /// records held by the sort window.
     pub async fn flush(&mut self) -> io::Result<()> {
+        self.write_all_held().await?;
         self.flush_buf().await?;
         self.wtr.as_mut().unwrap().flush().await?;
+        self.flushed();
+        Ok(())
+    }
+
+    /// Flush everything like `flush`, then close the underlying writer.
+    pub async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        let wtr = self.wtr.as_mut().unwrap();
+        cfg_if::cfg_if! {
+        if #[cfg(feature = "tokio")] {
+            wtr.shutdown().await?;
+        } else {
+            wtr.close().await?;
+        }}
+        self.state.closed = true;
+        Ok(())
+    }
+
+    /// Whether an automatic flush is due.
+    fn flush_due(&self) -> bool {
+        let unflushed_bytes = self.state.unflushed_bytes + self.buf.len;
+        matches!(self.state.flush_after_bytes, Some(n) if unflushed_bytes >= n)
+            || matches!(self.state.flush_after_records, Some(n) if self.state.unflushed_records >= n)
+    }
+
+    /// Flush the buffer and the underlying writer if an automatic flush is
+    /// due, without writing the records held by the sort window.
+    ///
+    /// While the buffer grows, the flush is left to `poll_ready`.
+    async fn flush_if_due(&mut self) -> Result<()> {
+        if !self.state.grow_buf && self.flush_due() {
+            self.flush_buf().await?;
+            self.wtr.as_mut().unwrap().flush().await?;
+            self.flushed();
+        }
         Ok(())
     }
 
+    /// Start counting towards the next automatic flush.
+    fn flushed(&mut self) {
+        self.state.unflushed_bytes = 0;
+        self.state.unflushed_records = 0;
+    }
+
     /// Flush the contents of the internal buffer to the underlying writer,
     /// without flushing the underlying writer.
     async fn flush_buf(&mut self) -> io::Result<()> {
+        if self.state.grow_buf {
+            self.buf.grow();
+            return Ok(());
+        }
+        if self.state.footer.checksum {
+            self.state.checksum.update(self.buf.readable());
+        }
         self.state.panicked = true;
-        let result = self.wtr.as_mut().unwrap().write_all(self.buf.readable()).await;
+        let mut result = Ok(());
+        if !self.state.sep_directive.is_empty() && !self.buf.readable().is_empty() {
+            let line = std::mem::take(&mut self.state.sep_directive);
+            result = self.wtr.as_mut().unwrap().write_all(&line).await;
+        }
+        if result.is_ok() {
+            result = self.wtr.as_mut().unwrap().write_all(self.buf.readable()).await;
+        }
         self.state.panicked = false;
         result?;
+        self.state.unflushed_bytes += self.buf.len;
         self.buf.clear();
         Ok(())
     }
@@ -719,6 +2167,123 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
         }
     }
 
+    /// Flush the contents of the internal buffer to the underlying writer
+    /// without blocking, keeping what was not written yet.
+    fn poll_flush_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.state.panicked = true;
+        let result = self.poll_write_buf(cx);
+        self.state.panicked = false;
+        result
+    }
+
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let wtr = self.wtr.as_mut().unwrap();
+        while !self.state.sep_directive.is_empty() && !self.buf.readable().is_empty() {
+            let n = ready!(Pin::new(&mut *wtr).poll_write(cx, &self.state.sep_directive))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.state.sep_directive.drain(..n);
+        }
+        while !self.buf.readable().is_empty() {
+            let n = ready!(Pin::new(&mut *wtr).poll_write(cx, self.buf.readable()))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            if self.state.footer.checksum {
+                self.state.checksum.update(&self.buf.readable()[..n]);
+            }
+            self.state.unflushed_bytes += n;
+            self.buf.consume(n);
+        }
+        self.buf.clear();
+        Poll::Ready(Ok(()))
+    }
+
+    /// Wait until the buffer is no longer full, so that `start_send` can
+    /// write the next record.
+    pub(crate) fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.flush_due() {
+            ready!(self.poll_flush_if_due(cx))?;
+        } else if self.buf.len >= self.buf.capacity {
+            ready!(self.poll_flush_buf(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Flush the buffer and the underlying writer without blocking, if an
+    /// automatic flush is due.
+    fn poll_flush_if_due(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.flush_due() {
+            ready!(self.poll_flush_buf(cx))?;
+            ready!(Pin::new(self.wtr.as_mut().unwrap()).poll_flush(cx))?;
+            self.flushed();
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Write `record` to the buffer, growing it as needed, so that writing
+    /// never waits for the underlying writer.
+    pub(crate) fn start_send(&mut self, record: &ByteRecord) -> Result<()> {
+        self.state.grow_buf = true;
+        let result = self.write_byte_record(record).now_or_never();
+        self.state.grow_buf = false;
+        result.expect("writing to a growing buffer does not wait")
+    }
+
+    /// Write all records of `records`, stopping at the first error.
+    pub async fn write_records<S>(&mut self, mut records: S) -> Result<()>
+    where
+        S: Stream<Item = Result<ByteRecord>> + Unpin,
+    {
+        future::poll_fn(|cx| loop {
+            ready!(self.poll_ready(cx))?;
+            match Pin::new(&mut records).poll_next(cx) {
+                Poll::Ready(Some(record)) => self.start_send(&record?)?,
+                Poll::Ready(None) => {
+                    ready!(self.poll_flush_if_due(cx))?;
+                    return Poll::Ready(Ok(()));
+                }
+                Poll::Pending => {
+                    // Write out what is buffered while waiting for records.
+                    if let Poll::Ready(Err(err)) = self.poll_flush_buf(cx) {
+                        return Poll::Ready(Err(err.into()));
+                    }
+                    return Poll::Pending;
+                }
+            }
+        })
+        .await
+    }
+
+    /// Write the records held by the sort window and the buffer, and flush
+    /// the underlying writer, without blocking.
+    pub(crate) fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.state.grow_buf = true;
+        let result = self.write_all_held().now_or_never();
+        self.state.grow_buf = false;
+        result.expect("writing to a growing buffer does not wait")?;
+        ready!(self.poll_flush_buf(cx))?;
+        ready!(Pin::new(self.wtr.as_mut().unwrap()).poll_flush(cx))?;
+        self.flushed();
+        Poll::Ready(Ok(()))
+    }
+
+    /// Flush everything like `poll_flush`, then close the underlying
+    /// writer.
+    pub(crate) fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.poll_flush(cx))?;
+        let wtr = Pin::new(self.wtr.as_mut().unwrap());
+        cfg_if::cfg_if! {
+        if #[cfg(feature = "tokio")] {
+            ready!(wtr.poll_shutdown(cx))?;
+        } else {
+            ready!(wtr.poll_close(cx))?;
+        }}
+        self.state.closed = true;
+        Poll::Ready(Ok(()))
+    }
+
     /// Write a CSV delimiter.
     async fn write_delimiter(&mut self) -> Result<()> {
         loop {
@@ -733,12 +2298,42 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
 
     /// Write a CSV terminator.
     async fn write_terminator(&mut self) -> Result<()> {
+        let fixed_len = self.state.fixed_width.as_ref().map(|fw| fw.widths.len() as u64);
+        if let Some(expected_len) = fixed_len {
+            // The core writer would quote an empty record, so the terminator
+            // is written directly.
+            if self.state.fields_written != expected_len {
+                let len = self.state.fields_written;
+                self.abandon_record();
+                return Err(Error::new(ErrorKind::UnequalLengths {
+                    pos: None,
+                    expected_len,
+                    len,
+                }));
+            }
+            self.check_field_count()?;
+            self.write_raw_terminator().await?;
+            self.record_written();
+            self.state.fields_written = 0;
+            return Ok(());
+        }
         self.check_field_count()?;
+        if std::mem::replace(&mut self.state.quoted_first, false)
+            && self.state.fields_written == 1
+        {
+            // The core writer saw no bytes of this record, so it would quote
+            // it as an empty record.
+            self.write_raw_terminator().await?;
+            self.record_written();
+            self.state.fields_written = 0;
+            return Ok(());
+        }
         loop {
             let (res, nout) = self.core.terminator(self.buf.writable());
             self.buf.written(nout);
             match res {
                 WriteResult::InputEmpty => {
+                    self.record_written();
                     self.state.fields_written = 0;
                     return Ok(());
                 }
@@ -747,6 +2342,15 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
         }
     }
 
+    /// Write the terminator bytes, bypassing the core writer.
+    async fn write_raw_terminator(&mut self) -> Result<()> {
+        match self.core.get_terminator() {
+            csv_core::Terminator::CRLF => self.write_raw(b"\r\n").await,
+            csv_core::Terminator::Any(b) => self.write_raw(&[b]).await,
+            _ => unreachable!(),
+        }
+    }
+
     /// Write a CSV terminator that is guaranteed to fit into the current buffer.
     /// 
     #[inline(never)]
@@ -764,10 +2368,20 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
             }
             _ => unreachable!(),
         }
+        self.record_written();
         self.state.fields_written = 0;
         Ok(())
     }
 
+    /// Account for a record that has just been terminated.
+    fn record_written(&mut self) {
+        self.state.unflushed_records += 1;
+        if !self.state.header_pending {
+            self.state.records += 1;
+        }
+        self.state.header_pending = false;
+    }
+
     fn check_field_count(&mut self) -> Result<()> {
         if !self.state.flexible {
             match self.state.first_field_count {
@@ -788,3 +2402,73 @@ impl<W: AsyncWrite + Unpin> AsyncWriterImpl<W> {
         Ok(())
     }
 }
+
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+//-//////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A sink writing each `ByteRecord` sent to it as a record, like
+/// `write_byte_record`.
+///
+/// It is made by `AsyncWriter::into_byte_sink`, so that streams of byte
+/// records can be written with `forward` or `send_all`, without converting
+/// them to `StringRecord`s. Sending a record writes it to the internal
+/// buffer, which is written out once it is full, when the sink is flushed,
+/// and when it is closed. Closing the sink also closes the underlying
+/// writer.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use futures::stream::StreamExt;
+/// use csv_async::{AsyncReaderBuilder, AsyncWriter};
+///
+/// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+/// async fn example() -> Result<(), Box<dyn Error>> {
+///     let data = "city,pop\nBoston,4628910\n";
+///     let rdr = AsyncReaderBuilder::new()
+///         .has_headers(false)
+///         .create_reader(data.as_bytes());
+///     let mut sink = AsyncWriter::from_writer(vec![]).into_byte_sink();
+///     rdr.into_byte_records().forward(&mut sink).await?;
+///
+///     assert_eq!(sink.into_inner().await?, data.as_bytes());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ByteRecordSink<W: AsyncWrite + Unpin>(AsyncWriterImpl<W>);
+
+impl<W: AsyncWrite + Unpin> ByteRecordSink<W> {
+    pub(crate) fn new(wtr: AsyncWriterImpl<W>) -> Self {
+        ByteRecordSink(wtr)
+    }
+
+    /// Flush the contents of the internal buffer and return the underlying
+    /// writer.
+    pub async fn into_inner(
+        self,
+    ) -> result::Result<W, IntoInnerError<ByteRecordSink<W>>> {
+        self.0.into_inner().await.map_err(|err| err.map_writer(ByteRecordSink))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<ByteRecord> for ByteRecordSink<W> {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, record: ByteRecord) -> Result<()> {
+        self.0.start_send(&record)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.0.poll_close(cx)
+    }
+}
@@ -8,7 +8,9 @@ use serde::Serialize;
 
 use crate::error::{Error, ErrorKind, Result};
 use crate::serializer::{serialize, serialize_header};
-use crate::AsyncWriterBuilder;
+use crate::string_record::StringRecord;
+use crate::{AsyncWriterBuilder, NewlineMode};
+use super::{handle_newlines, quote_field, sep_directive, QuotePredicate, RowNumbers};
 
 /// A helper struct to synchronously perform serialization of structures to bytes stored in memory
 /// according to interface provided by serde::Serialize.
@@ -30,6 +32,19 @@ struct WriterState {
     header: HeaderState,
     /// Whether inconsistent record lengths are allowed.
     flexible: bool,
+    /// Whether enum variants with data are written with their variant name.
+    tagged_enums: bool,
+    /// The field written for `None` values.
+    none_as: Vec<u8>,
+    /// How fields containing line breaks are written.
+    newline_in_field: NewlineMode,
+    /// Decides which fields are quoted regardless of the quoting style.
+    quote_if: Option<QuotePredicate>,
+    /// Whether the first field of this record was quoted by `quote_if`,
+    /// bypassing the core writer.
+    quoted_first: bool,
+    /// The column of row numbers prepended to records.
+    row_numbers: Option<RowNumbers>,
     /// The number of fields writtein in the first record. This is compared
     /// with `fields_written` on all subsequent records to check for
     /// inconsistent record lengths.
@@ -87,13 +102,24 @@ impl MemWriter {
         } else {
             HeaderState::None
         };
+        let core = builder.builder.build();
+        // The `sep=` line, if any, starts the data.
+        let directive = sep_directive(builder, &core);
+        let mut wtr = io::Cursor::new(directive);
+        wtr.set_position(wtr.get_ref().len() as u64);
         MemWriter {
-            core: builder.builder.build(),
-            wtr: io::Cursor::new(Vec::new()),
+            core,
+            wtr,
             buf: Buffer { buf: vec![0; builder.capacity], len: 0 },
             state: WriterState {
                 header: header_state,
                 flexible: builder.flexible,
+                tagged_enums: builder.tagged_enums,
+                none_as: builder.none_as.clone(),
+                newline_in_field: builder.newline_in_field,
+                quote_if: builder.quote_if.clone(),
+                quoted_first: false,
+                row_numbers: RowNumbers::new(builder),
                 first_field_count: None,
                 fields_written: 0,
                 panicked: false,
@@ -118,6 +144,66 @@ impl MemWriter {
         Ok(())
     }
 
+    /// Serialize a single record using Serde, writing `headers` as the
+    /// header row if it has not been written yet.
+    pub fn serialize_with_headers<S: Serialize>(
+        &mut self,
+        headers: &StringRecord,
+        record: S,
+    ) -> Result<()> {
+        if let HeaderState::Write = self.state.header {
+            if headers.is_empty() {
+                self.state.header = HeaderState::DidNotWrite;
+            } else {
+                for field in headers.iter() {
+                    self.write_field(field)?;
+                }
+                self.write_terminator()?;
+                self.state.header = HeaderState::DidWrite;
+            }
+        }
+        serialize(self, &record)?;
+        self.write_terminator()?;
+        Ok(())
+    }
+
+    /// End the current table with a blank line. The next serialized record
+    /// starts a new table, with its own header row.
+    pub fn end_table(&mut self) -> Result<()> {
+        if self.state.fields_written > 0 {
+            self.write_terminator()?;
+        }
+        let (term, len) = match self.core.get_terminator() {
+            csv_core::Terminator::CRLF => ([b'\r', b'\n'], 2),
+            csv_core::Terminator::Any(b) => ([b, 0], 1),
+            _ => unreachable!(),
+        };
+        if self.buf.writable().len() < len {
+            self.flush_buf()?;
+        }
+        self.buf.writable()[..len].copy_from_slice(&term[..len]);
+        self.buf.written(len);
+        self.state.first_field_count = None;
+        if let HeaderState::DidWrite | HeaderState::DidNotWrite = self.state.header {
+            self.state.header = HeaderState::Write;
+        }
+        Ok(())
+    }
+
+    /// Whether enum variants with data are written as a variant name column
+    /// followed by the payload.
+    pub fn tagged_enums(&self) -> bool {
+        self.state.tagged_enums
+    }
+
+    /// Write the field standing for a `None` value.
+    pub fn write_none(&mut self) -> Result<()> {
+        let token = std::mem::take(&mut self.state.none_as);
+        let res = self.write_field_impl(&token);
+        self.state.none_as = token;
+        res
+    }
+
     /// Write a single field.
     pub fn write_field<T: AsRef<[u8]>>(&mut self, field: T) -> Result<()> {
         self.write_field_impl(field)
@@ -129,10 +215,33 @@ impl MemWriter {
     /// into write_record.
     #[inline(always)]
     fn write_field_impl<T: AsRef<[u8]>>(&mut self, field: T) -> Result<()> {
+        if self.state.fields_written == 0 {
+            // The header row is written while the header state is `Write`.
+            let header = matches!(self.state.header, HeaderState::Write);
+            if let Some(number) = self.state.row_numbers.as_mut().map(|n| n.field(header)) {
+                self.write_unnumbered_field(number.as_bytes())?;
+            }
+        }
+        self.write_unnumbered_field(field.as_ref())
+    }
+
+    /// Write a single field, without starting the record with its row
+    /// number.
+    fn write_unnumbered_field(&mut self, field: &[u8]) -> Result<()> {
+        let field = handle_newlines(
+            self.state.newline_in_field,
+            self.state.fields_written,
+            field,
+        )?;
+        if let Some(ref quote_if) = self.state.quote_if {
+            if (quote_if.0)(self.state.fields_written as usize, &field) {
+                return self.write_quoted_field(&field);
+            }
+        }
         if self.state.fields_written > 0 {
             self.write_delimiter()?;
         }
-        let mut field = field.as_ref();
+        let mut field = &field[..];
         loop {
             let (res, nin, nout) = self.core.field(field, self.buf.writable());
             field = &field[nin..];
@@ -147,6 +256,25 @@ impl MemWriter {
         }
     }
 
+    /// Write a single field in quotes, bypassing the core writer.
+    fn write_quoted_field(&mut self, field: &[u8]) -> Result<()> {
+        if self.state.fields_written > 0 {
+            self.write_delimiter()?;
+        } else {
+            self.state.quoted_first = true;
+        }
+        self.write_raw(&quote_field(&self.core, field))?;
+        self.state.fields_written += 1;
+        Ok(())
+    }
+
+    /// Write bytes, bypassing the core writer.
+    fn write_raw(&mut self, data: &[u8]) -> Result<()> {
+        self.flush_buf()?;
+        self.wtr.write_all(data)?;
+        Ok(())
+    }
+
     /// Flush the contents of the internal buffer to the underlying writer.
     ///
     /// If there was a problem writing to the underlying writer, then an error
@@ -199,6 +327,19 @@ impl MemWriter {
     /// Write a CSV terminator.
     fn write_terminator(&mut self) -> Result<()> {
         self.check_field_count()?;
+        if std::mem::replace(&mut self.state.quoted_first, false)
+            && self.state.fields_written == 1
+        {
+            // The core writer saw no bytes of this record, so it would quote
+            // it as an empty record.
+            match self.core.get_terminator() {
+                csv_core::Terminator::CRLF => self.write_raw(b"\r\n")?,
+                csv_core::Terminator::Any(b) => self.write_raw(&[b])?,
+                _ => unreachable!(),
+            }
+            self.state.fields_written = 0;
+            return Ok(());
+        }
         loop {
             let (res, nout) = self.core.terminator(self.buf.writable());
             self.buf.written(nout);
@@ -267,7 +408,7 @@ impl Buffer {
 mod tests {
     use std::error::Error;
 
-    use serde::{serde_if_integer128, Serialize};
+    use serde::{serde_if_integer128, Deserialize, Serialize};
 
     use crate::byte_record::ByteRecord;
     use crate::error::{ErrorKind, IntoInnerError};
@@ -417,6 +558,85 @@ mod tests {
         assert_eq!(wtr_as_string(wtr), "42,42.5,true\n");
     }
 
+    #[test]
+    fn serialize_declaration_order() {
+        #[derive(Serialize)]
+        struct Row {
+            zulu: Option<u32>,
+            alpha: &'static str,
+            mike: f64,
+            #[serde(rename = "charlie")]
+            bravo: bool,
+            yankee: char,
+            echo: (),
+            delta: i8,
+        }
+
+        let row = Row {
+            zulu: Some(3),
+            alpha: "a",
+            mike: 1.5,
+            bravo: false,
+            yankee: 'y',
+            echo: (),
+            delta: -1,
+        };
+        let mut wtr = MemWriter::default();
+        wtr.serialize(&row).unwrap();
+        wtr.serialize(Row { zulu: None, ..row }).unwrap();
+        assert_eq!(
+            wtr_as_string(wtr),
+            "zulu,alpha,mike,charlie,yankee,echo,delta\n\
+             3,a,1.5,false,y,,-1\n\
+             ,a,1.5,false,y,,-1\n"
+        );
+    }
+
+    #[test]
+    fn serialize_with_custom_formatter() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        fn iso8601<S: serde::Serializer>(
+            time: &SystemTime,
+            ser: S,
+        ) -> Result<S::Ok, S::Error> {
+            let secs = time.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            // Civil date from days since epoch (proleptic Gregorian calendar).
+            let z = (secs / 86_400) as i64 + 719_468;
+            let era = z.div_euclid(146_097);
+            let doe = z - era * 146_097;
+            let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+            let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+            let mp = (5 * doy + 2) / 153;
+            let day = doy - (153 * mp + 2) / 5 + 1;
+            let month = if mp < 10 { mp + 3 } else { mp - 9 };
+            let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+            let tod = secs % 86_400;
+            ser.serialize_str(&format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                year, month, day, tod / 3600, tod % 3600 / 60, tod % 60
+            ))
+        }
+
+        #[derive(Serialize)]
+        struct Row {
+            id: u32,
+            #[serde(serialize_with = "iso8601")]
+            at: SystemTime,
+        }
+
+        let mut wtr = MemWriter::default();
+        wtr.serialize(Row { id: 1, at: UNIX_EPOCH }).unwrap();
+        wtr.serialize(Row {
+            id: 2,
+            at: UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+        }).unwrap();
+        assert_eq!(
+            wtr_as_string(wtr),
+            "id,at\n1,1970-01-01T00:00:00Z\n2,2020-09-13T12:26:40Z\n"
+        );
+    }
+
     serde_if_integer128! {
         #[test]
         fn serialize_no_headers_128() {
@@ -530,4 +750,142 @@ bar,3.14
         assert_eq!(data, "foo,1.1234,2.5678,3.14\n");
         Ok(())
     }
+
+    #[test]
+    fn end_table_writes_new_header() {
+        #[derive(Serialize)]
+        struct Row1 {
+            a: i32,
+        }
+        #[derive(Serialize)]
+        struct Row2 {
+            x: &'static str,
+            y: bool,
+        }
+
+        let mut wtr = MemWriter::default();
+        wtr.serialize(Row1 { a: 1 }).unwrap();
+        wtr.serialize(Row1 { a: 2 }).unwrap();
+        wtr.end_table().unwrap();
+        wtr.serialize(Row2 { x: "foo", y: true }).unwrap();
+        assert_eq!(wtr_as_string(wtr), "a\n1\n2\n\nx,y\nfoo,true\n");
+    }
+
+    #[test]
+    fn tagged_enum_round_trip() {
+        let mut builder = AsyncWriterBuilder::new();
+        builder.tagged_enums(true);
+        let mut wtr = MemWriter::new(&builder);
+        wtr.serialize(Ok::<i32, String>(5)).unwrap();
+        wtr.serialize(Err::<i32, String>("boom".to_string())).unwrap();
+        let data = wtr_as_string(wtr);
+        assert_eq!(data, "Ok,5\nErr,boom\n");
+
+        let got: Vec<Result<i32, String>> = data
+            .lines()
+            .map(|line| {
+                let rec = StringRecord::from(line.split(',').collect::<Vec<_>>());
+                rec.deserialize(None).unwrap()
+            })
+            .collect();
+        assert_eq!(got, vec![Ok(5), Err("boom".to_string())]);
+    }
+
+    #[test]
+    fn tagged_struct_variant_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Event {
+            Move { x: i32, y: i32 },
+            Click { x: i32, y: i32 },
+            Key(char),
+        }
+
+        let mut builder = AsyncWriterBuilder::new();
+        builder.tagged_enums(true).flexible(true);
+        let mut wtr = MemWriter::new(&builder);
+        wtr.serialize(Event::Move { x: 1, y: 2 }).unwrap();
+        wtr.serialize(Event::Click { x: 3, y: 4 }).unwrap();
+        wtr.serialize(Event::Key('q')).unwrap();
+        let data = wtr_as_string(wtr);
+        assert_eq!(data, "Event,x,y\nMove,1,2\nClick,3,4\nKey,q\n");
+
+        let mut lines = data
+            .lines()
+            .map(|line| StringRecord::from(line.split(',').collect::<Vec<_>>()));
+        let headers = lines.next().unwrap();
+        let got: Vec<Event> =
+            lines.map(|rec| rec.deserialize(Some(&headers)).unwrap()).collect();
+        assert_eq!(got, vec![
+            Event::Move { x: 1, y: 2 },
+            Event::Click { x: 3, y: 4 },
+            Event::Key('q'),
+        ]);
+    }
+
+    #[test]
+    fn row_numbers() {
+        #[derive(Serialize)]
+        struct Row {
+            city: &'static str,
+            pop: u64,
+        }
+
+        let mut builder = AsyncWriterBuilder::new();
+        builder.row_numbers(Some("row"));
+        let mut wtr = MemWriter::new(&builder);
+        wtr.serialize(Row { city: "Boston", pop: 4628910 }).unwrap();
+        wtr.serialize(Row { city: "Concord", pop: 42695 }).unwrap();
+        wtr.serialize(Row { city: "Dover", pop: 32741 }).unwrap();
+        assert_eq!(
+            wtr_as_string(wtr),
+            "row,city,pop\n1,Boston,4628910\n2,Concord,42695\n3,Dover,32741\n"
+        );
+
+        let mut builder = AsyncWriterBuilder::new();
+        builder.row_numbers(Some("row")).row_numbers_start(0);
+        let mut wtr = MemWriter::new(&builder);
+        wtr.serialize(("a", 1)).unwrap();
+        wtr.serialize(("b", 2)).unwrap();
+        assert_eq!(wtr_as_string(wtr), "0,a,1\n1,b,2\n");
+    }
+
+    #[test]
+    fn write_sep_directive() {
+        let mut builder = AsyncWriterBuilder::new();
+        builder.delimiter(b'|').write_sep_directive(true);
+        let mut wtr = MemWriter::new(&builder);
+        wtr.serialize(("a", 1)).unwrap();
+        wtr.serialize(("b", 2)).unwrap();
+        assert_eq!(wtr_as_string(wtr), "sep=|\r\na|1\nb|2\n");
+    }
+
+    #[test]
+    fn none_as() {
+        #[derive(Serialize)]
+        struct Row {
+            city: &'static str,
+            pop: Option<u64>,
+            note: Option<&'static str>,
+        }
+
+        let mut wtr = MemWriter::new(AsyncWriterBuilder::new().none_as("NULL"));
+        wtr.serialize(Row { city: "Boston", pop: Some(4628910), note: None }).unwrap();
+        wtr.serialize(Row { city: "Concord", pop: None, note: Some("") }).unwrap();
+        assert_eq!(
+            wtr_as_string(wtr),
+            "city,pop,note\nBoston,4628910,NULL\nConcord,NULL,\n"
+        );
+
+        // The token is quoted like any other field.
+        let mut wtr = MemWriter::new(
+            AsyncWriterBuilder::new().has_headers(false).none_as("n/a, none"),
+        );
+        wtr.serialize(("Dover", None::<u64>)).unwrap();
+        assert_eq!(wtr_as_string(wtr), "Dover,\"n/a, none\"\n");
+
+        // By default, None is an empty field.
+        let mut wtr = MemWriter::new(AsyncWriterBuilder::new().has_headers(false));
+        wtr.serialize(("Dover", None::<u64>)).unwrap();
+        assert_eq!(wtr_as_string(wtr), "Dover,\n");
+    }
 }
@@ -0,0 +1,181 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::ready;
+cfg_if::cfg_if! {
+if #[cfg(feature = "tokio")] {
+    use tokio::io::{self, AsyncWrite};
+} else {
+    use futures::io::{self, AsyncWrite};
+}}
+
+/// A writer that writes the same data to several underlying writers.
+///
+/// This is the writer used by CSV writers created with
+/// [`AsyncWriter::tee`](struct.AsyncWriter.html#method.tee), e.g. to write
+/// the same CSV data to a file and a socket. Data written is held until all
+/// writers have taken all of it, so a slow writer holds back the others.
+/// Flushing flushes all writers.
+///
+/// Writing stops at the first error. The error returned wraps a
+/// [`TeeError`](struct.TeeError.html) telling which writer failed, which can
+/// be retrieved with `std::io::Error::get_ref`.
+#[derive(Debug)]
+pub struct TeeWriter<W> {
+    sinks: Vec<W>,
+    buf: Vec<u8>,
+    written: Vec<usize>,
+}
+
+impl<W: AsyncWrite + Unpin> TeeWriter<W> {
+    /// Create a writer writing to all of `sinks`.
+    pub fn new(sinks: Vec<W>) -> TeeWriter<W> {
+        let written = vec![0; sinks.len()];
+        TeeWriter { sinks, buf: Vec::new(), written }
+    }
+
+    /// Return references to the underlying writers.
+    pub fn get_ref(&self) -> &[W] {
+        &self.sinks
+    }
+
+    /// Unwrap this writer, returning the underlying writers.
+    ///
+    /// Data not yet written to all of them is lost, so this should only be
+    /// called after a flush.
+    pub fn into_inner(self) -> Vec<W> {
+        self.sinks
+    }
+
+    /// Write the held data to all writers that have not taken all of it.
+    fn poll_drain(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        let mut done = true;
+        for (i, sink) in self.sinks.iter_mut().enumerate() {
+            while self.written[i] < self.buf.len() {
+                match Pin::new(&mut *sink).poll_write(cx, &self.buf[self.written[i]..]) {
+                    Poll::Ready(Ok(0)) => {
+                        let err = io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        );
+                        return Poll::Ready(Err(TeeError::wrap(i, err)));
+                    }
+                    Poll::Ready(Ok(n)) => self.written[i] += n,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(TeeError::wrap(i, err))),
+                    Poll::Pending => {
+                        done = false;
+                        break;
+                    }
+                }
+            }
+        }
+        if !done {
+            return Poll::Pending;
+        }
+        self.buf.clear();
+        for written in &mut self.written {
+            *written = 0;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Flush all writers, after writing the held data to them.
+    fn poll_flush_all(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
+        let mut done = true;
+        for (i, sink) in self.sinks.iter_mut().enumerate() {
+            match Pin::new(sink).poll_flush(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(TeeError::wrap(i, err))),
+                Poll::Pending => done = false,
+            }
+        }
+        if done { Poll::Ready(Ok(())) } else { Poll::Pending }
+    }
+
+    /// Close all writers, after flushing them.
+    fn poll_close_all(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        ready!(self.poll_flush_all(cx))?;
+        let mut done = true;
+        for (i, sink) in self.sinks.iter_mut().enumerate() {
+            cfg_if::cfg_if! {
+            if #[cfg(feature = "tokio")] {
+                let poll = Pin::new(sink).poll_shutdown(cx);
+            } else {
+                let poll = Pin::new(sink).poll_close(cx);
+            }}
+            match poll {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(TeeError::wrap(i, err))),
+                Poll::Pending => done = false,
+            }
+        }
+        if done { Poll::Ready(Ok(())) } else { Poll::Pending }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for TeeWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        this.buf.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_all(cx)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_close_all(cx)
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_close_all(cx)
+    }
+}
+
+/// An I/O error from one of the writers of a
+/// [`TeeWriter`](struct.TeeWriter.html).
+#[derive(Debug)]
+pub struct TeeError {
+    sink: usize,
+    err: io::Error,
+}
+
+impl TeeError {
+    /// Wrap the error of writer `sink` into an I/O error of the same kind.
+    fn wrap(sink: usize, err: io::Error) -> io::Error {
+        io::Error::new(err.kind(), TeeError { sink, err })
+    }
+
+    /// The index of the writer that failed.
+    pub fn sink(&self) -> usize {
+        self.sink
+    }
+
+    /// The error of the writer that failed.
+    pub fn error(&self) -> &io::Error {
+        &self.err
+    }
+}
+
+impl StdError for TeeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.err)
+    }
+}
+
+impl fmt::Display for TeeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tee writer {}: {}", self.sink, self.err)
+    }
+}
@@ -95,6 +95,8 @@ struct ByteRecordInner {
     fields: Vec<u8>,
     /// The number of and location of each field in this record.
     bounds: Bounds,
+    /// Whether each field was quoted in the source data, if tracked.
+    quoted: Option<Vec<bool>>,
 }
 
 impl Default for ByteRecord {
@@ -143,6 +145,7 @@ impl ByteRecord {
             pos: None,
             fields: vec![0; buffer],
             bounds: Bounds::with_capacity(fields),
+            quoted: None,
         }))
     }
 
@@ -283,12 +286,96 @@ impl ByteRecord {
         let mut trimmed =
             ByteRecord::with_capacity(self.as_slice().len(), self.len());
         trimmed.set_position(self.position().cloned());
+        trimmed.set_quoted(self.take_quoted());
         for field in &*self {
             trimmed.push_field(field.trim());
         }
         *self = trimmed;
     }
 
+    /// Replace every run of whitespace within the fields of this record with
+    /// a single space.
+    ///
+    /// Unlike `trim`, this keeps whitespace at the start and end of a field
+    /// (collapsed to a single space). This method uses the ASCII definition
+    /// of whitespace, i.e. bytes in the class `[\t\n\v\f\r ]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csv_async::ByteRecord;
+    ///
+    /// let mut record = ByteRecord::from(vec![
+    ///     "a   b\tc", "  foo", "bar",
+    /// ]);
+    /// record.collapse_whitespace();
+    /// assert_eq!(record, vec!["a b c", " foo", "bar"]);
+    /// ```
+    pub fn collapse_whitespace(&mut self) {
+        let mut collapsed =
+            ByteRecord::with_capacity(self.as_slice().len(), self.len());
+        collapsed.set_position(self.position().cloned());
+        collapsed.set_quoted(self.take_quoted());
+        let mut field_buf = Vec::new();
+        for field in &*self {
+            field_buf.clear();
+            let mut in_space = false;
+            for &b in field {
+                // Same class as `trim`, which includes vertical tab.
+                if b.is_ascii_whitespace() || b == b'\x0B' {
+                    if !in_space {
+                        field_buf.push(b' ');
+                    }
+                    in_space = true;
+                } else {
+                    field_buf.push(b);
+                    in_space = false;
+                }
+            }
+            collapsed.push_field(&field_buf);
+        }
+        *self = collapsed;
+    }
+
+    /// Replace every `\r\n` and lone `\r` within the fields of this record
+    /// with `\n`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csv_async::ByteRecord;
+    ///
+    /// let mut record = ByteRecord::from(vec!["a\r\nb\rc", "d\ne"]);
+    /// record.normalize_newlines();
+    /// assert_eq!(record, vec!["a\nb\nc", "d\ne"]);
+    /// ```
+    pub fn normalize_newlines(&mut self) {
+        if !self.as_slice().contains(&b'\r') {
+            return;
+        }
+        let mut normalized =
+            ByteRecord::with_capacity(self.as_slice().len(), self.len());
+        normalized.set_position(self.position().cloned());
+        normalized.set_quoted(self.take_quoted());
+        let mut field_buf = Vec::new();
+        for field in &*self {
+            field_buf.clear();
+            let mut bytes = field.iter().peekable();
+            while let Some(&b) = bytes.next() {
+                if b == b'\r' {
+                    if bytes.peek() == Some(&&b'\n') {
+                        bytes.next();
+                    }
+                    field_buf.push(b'\n');
+                } else {
+                    field_buf.push(b);
+                }
+            }
+            normalized.push_field(&field_buf);
+        }
+        *self = normalized;
+    }
+
     /// Add a new field to this record.
     ///
     /// # Example
@@ -373,6 +460,57 @@ impl ByteRecord {
         self.0.pos = pos;
     }
 
+    /// Whether the field at index `i` was quoted in the source data.
+    ///
+    /// This is only known for records read from delimited data by a reader
+    /// with `AsyncReaderBuilder::track_quoted` enabled. It returns `None`
+    /// otherwise, or if no field exists at the given index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use csv_async::{ByteRecord, AsyncReaderBuilder};
+    ///
+    /// # fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut record = ByteRecord::new();
+    ///     let mut rdr = AsyncReaderBuilder::new()
+    ///         .has_headers(false)
+    ///         .track_quoted(true)
+    ///         .create_reader("\"\",,\"x\"".as_bytes());
+    ///
+    ///     assert!(rdr.read_byte_record(&mut record).await?);
+    ///     assert_eq!(record, vec!["", "", "x"]);
+    ///     assert_eq!(record.was_quoted(0), Some(true));
+    ///     assert_eq!(record.was_quoted(1), Some(false));
+    ///     assert_eq!(record.was_quoted(2), Some(true));
+    ///     assert_eq!(record.was_quoted(3), None);
+    ///     assert_eq!(ByteRecord::from(vec!["x"]).was_quoted(0), None);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn was_quoted(&self, i: usize) -> Option<bool> {
+        if i >= self.len() {
+            return None;
+        }
+        self.0.quoted.as_ref().and_then(|quoted| quoted.get(i).cloned())
+    }
+
+    /// Set whether each field was quoted in the source data.
+    #[inline]
+    pub(crate) fn set_quoted(&mut self, quoted: Option<Vec<bool>>) {
+        self.0.quoted = quoted;
+    }
+
+    /// Take whether each field was quoted in the source data, leaving it
+    /// unknown.
+    #[inline]
+    pub(crate) fn take_quoted(&mut self) -> Option<Vec<bool>> {
+        self.0.quoted.take()
+    }
+
     /// Return the start and end position of a field in this record.
     ///
     /// If no such field exists at the given index, then return `None`.
@@ -436,6 +574,63 @@ impl ByteRecord {
         self.0.bounds.expand();
     }
 
+    /// Remove the empty fields of this record.
+    pub(crate) fn remove_empty_fields(&mut self) {
+        if self.iter().all(|field| !field.is_empty()) {
+            return;
+        }
+        let mut kept = ByteRecord::with_capacity(self.as_slice().len(), self.len());
+        kept.set_position(self.position().cloned());
+        for field in self.iter().filter(|field| !field.is_empty()) {
+            kept.push_field(field);
+        }
+        *self = kept;
+    }
+
+    /// Remove `open` and `close` from the fields starting with `open` and
+    /// ending with `close`.
+    pub(crate) fn strip_wrappers(&mut self, open: u8, close: u8) {
+        let wrapped = |field: &[u8]| {
+            field.len() >= 2 && field[0] == open && field[field.len() - 1] == close
+        };
+        if !self.iter().any(wrapped) {
+            return;
+        }
+        let mut stripped = ByteRecord::with_capacity(self.as_slice().len(), self.len());
+        stripped.set_position(self.position().cloned());
+        stripped.set_quoted(self.take_quoted());
+        for field in self.iter() {
+            if wrapped(field) {
+                stripped.push_field(&field[1..field.len() - 1]);
+            } else {
+                stripped.push_field(field);
+            }
+        }
+        *self = stripped;
+    }
+
+    /// A 64-bit FNV-1a hash of the fields, which only depends on their
+    /// contents, so it is the same across runs and platforms.
+    ///
+    /// Each field is hashed after its length, so records whose fields only
+    /// differ in where they are split hash differently.
+    pub(crate) fn content_hash(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = OFFSET;
+        let mut feed = |bytes: &[u8]| {
+            for &b in bytes {
+                hash ^= u64::from(b);
+                hash = hash.wrapping_mul(PRIME);
+            }
+        };
+        for field in self.iter() {
+            feed(&(field.len() as u64).to_le_bytes());
+            feed(field);
+        }
+        hash
+    }
+
     /// Validate the given record as UTF-8.
     ///
     /// If it's not UTF-8, return an error.
@@ -1152,4 +1347,29 @@ mod tests {
         let test2 = ByteRecord::from(vec!["12", "34"]);
         assert_ne!(test1, test2);
     }
+
+    #[cfg(feature = "with_serde")]
+    #[test]
+    fn deserialize() {
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row<'a> {
+            city: String,
+            pop: u64,
+            #[serde(borrow)]
+            code: &'a [u8],
+        }
+
+        let headers = ByteRecord::from(vec!["code", "pop", "city"]);
+        let record = ByteRecord::from(vec![&b"B\xff"[..], b"4628910", b"Boston"]);
+        let row: Row = record.deserialize(Some(&headers)).unwrap();
+        assert_eq!(row, Row { city: "Boston".to_string(), pop: 4628910, code: b"B\xff" });
+
+        // Without headers, fields are deserialized by position.
+        let record = ByteRecord::from(vec!["Boston", "4628910"]);
+        let row: (String, u64) = record.deserialize(None).unwrap();
+        assert_eq!(row, ("Boston".to_string(), 4628910));
+        assert!(record.deserialize::<(String, String, String)>(None).is_err());
+    }
 }
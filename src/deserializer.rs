@@ -1,13 +1,14 @@
 use std::error::Error as StdError;
 use std::fmt;
 use std::iter;
+use std::mem;
 use std::num;
 use std::str;
 
 use serde::de::value::BorrowedBytesDeserializer;
 use serde::de::{
     Deserialize, DeserializeSeed, Deserializer, EnumAccess,
-    Error as SerdeError, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+    Error as SerdeError, IntoDeserializer, MapAccess, SeqAccess,
     VariantAccess, Visitor,
 };
 use serde::serde_if_integer128;
@@ -22,12 +23,21 @@ pub fn deserialize_string_record<'de, D: Deserialize<'de>>(
     record: &'de StringRecord,
     headers: Option<&'de StringRecord>,
 ) -> Result<D, Error> {
-    let mut deser = DeRecordWrap(DeStringRecord {
-        it: record.iter().peekable(),
-        headers: headers.map(|r| r.iter()),
-        field: 0,
-    });
-    D::deserialize(&mut deser).map_err(|err| {
+    let mut deser = DeRecordWrap {
+        rec: DeStringRecord {
+            it: record.iter().peekable(),
+            headers: headers.map(|r| r.iter()),
+            field: 0,
+        },
+        in_field: false,
+    };
+    D::deserialize(&mut deser).map_err(|mut err| {
+        err.header = match (err.field, headers) {
+            (Some(field), Some(headers)) => {
+                headers.get(field as usize).map(|name| name.to_string())
+            }
+            _ => None,
+        };
         Error::new(ErrorKind::Deserialize {
             pos: record.position().map(Clone::clone),
             err: err,
@@ -39,12 +49,21 @@ pub fn deserialize_byte_record<'de, D: Deserialize<'de>>(
     record: &'de ByteRecord,
     headers: Option<&'de ByteRecord>,
 ) -> Result<D, Error> {
-    let mut deser = DeRecordWrap(DeByteRecord {
-        it: record.iter().peekable(),
-        headers: headers.map(|r| r.iter()),
-        field: 0,
-    });
-    D::deserialize(&mut deser).map_err(|err| {
+    let mut deser = DeRecordWrap {
+        rec: DeByteRecord {
+            it: record.iter().peekable(),
+            headers: headers.map(|r| r.iter()),
+            field: 0,
+        },
+        in_field: false,
+    };
+    D::deserialize(&mut deser).map_err(|mut err| {
+        err.header = match (err.field, headers) {
+            (Some(field), Some(headers)) => headers
+                .get(field as usize)
+                .map(|name| String::from_utf8_lossy(name).into_owned()),
+            _ => None,
+        };
         Error::new(ErrorKind::Deserialize {
             pos: record.position().map(Clone::clone),
             err: err,
@@ -99,44 +118,49 @@ trait DeRecord<'r> {
     ) -> Result<V::Value, DeserializeError>;
 }
 
-struct DeRecordWrap<T>(T);
+struct DeRecordWrap<T> {
+    rec: T,
+    /// Set while deserializing the value of a struct field, whose header
+    /// was already consumed as the key.
+    in_field: bool,
+}
 
 impl<'r, T: DeRecord<'r>> DeRecord<'r> for DeRecordWrap<T> {
     #[inline]
     fn has_headers(&self) -> bool {
-        self.0.has_headers()
+        self.rec.has_headers()
     }
 
     #[inline]
     fn next_header(&mut self) -> Result<Option<&'r str>, DeserializeError> {
-        self.0.next_header()
+        self.rec.next_header()
     }
 
     #[inline]
     fn next_header_bytes(
         &mut self,
     ) -> Result<Option<&'r [u8]>, DeserializeError> {
-        self.0.next_header_bytes()
+        self.rec.next_header_bytes()
     }
 
     #[inline]
     fn next_field(&mut self) -> Result<&'r str, DeserializeError> {
-        self.0.next_field()
+        self.rec.next_field()
     }
 
     #[inline]
     fn next_field_bytes(&mut self) -> Result<&'r [u8], DeserializeError> {
-        self.0.next_field_bytes()
+        self.rec.next_field_bytes()
     }
 
     #[inline]
     fn peek_field(&mut self) -> Option<&'r [u8]> {
-        self.0.peek_field()
+        self.rec.peek_field()
     }
 
     #[inline]
     fn error(&self, kind: DeserializeErrorKind) -> DeserializeError {
-        self.0.error(kind)
+        self.rec.error(kind)
     }
 
     #[inline]
@@ -144,7 +168,7 @@ impl<'r, T: DeRecord<'r>> DeRecord<'r> for DeRecordWrap<T> {
         &mut self,
         visitor: V,
     ) -> Result<V::Value, DeserializeError> {
-        self.0.infer_deserialize(visitor)
+        self.rec.infer_deserialize(visitor)
     }
 }
 
@@ -181,6 +205,7 @@ impl<'r> DeRecord<'r> for DeStringRecord<'r> {
             }
             None => Err(DeserializeError {
                 field: None,
+                header: None,
                 kind: DEK::UnexpectedEndOfRow,
             }),
         }
@@ -199,6 +224,7 @@ impl<'r> DeRecord<'r> for DeStringRecord<'r> {
     fn error(&self, kind: DeserializeErrorKind) -> DeserializeError {
         DeserializeError {
             field: Some(self.field.saturating_sub(1)),
+            header: None,
             kind: kind,
         }
     }
@@ -280,6 +306,7 @@ impl<'r> DeRecord<'r> for DeByteRecord<'r> {
             }
             None => Err(DeserializeError {
                 field: None,
+                header: None,
                 kind: DEK::UnexpectedEndOfRow,
             }),
         }
@@ -293,6 +320,7 @@ impl<'r> DeRecord<'r> for DeByteRecord<'r> {
     fn error(&self, kind: DeserializeErrorKind) -> DeserializeError {
         DeserializeError {
             field: Some(self.field.saturating_sub(1)),
+            header: None,
             kind: kind,
         }
     }
@@ -573,6 +601,11 @@ impl<'a, 'de: 'a, T: DeRecord<'de>> EnumAccess<'de>
         seed: V,
     ) -> Result<(V::Value, Self::Variant), Self::Error> {
         let variant_name = self.next_field()?;
+        if self.has_headers() && !self.in_field {
+            // A tagged enum spanning the whole record: keep the headers in
+            // step with the fields so a struct payload is keyed correctly.
+            self.next_header_bytes()?;
+        }
         seed.deserialize(variant_name.into_deserializer()).map(|v| (v, self))
     }
 }
@@ -588,28 +621,29 @@ impl<'a, 'de: 'a, T: DeRecord<'de>> VariantAccess<'de>
 
     fn newtype_variant_seed<U: DeserializeSeed<'de>>(
         self,
-        _seed: U,
+        seed: U,
     ) -> Result<U::Value, Self::Error> {
-        let unexp = Unexpected::UnitVariant;
-        Err(DeserializeError::invalid_type(unexp, &"newtype variant"))
+        seed.deserialize(self)
     }
 
     fn tuple_variant<V: Visitor<'de>>(
         self,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        let unexp = Unexpected::UnitVariant;
-        Err(DeserializeError::invalid_type(unexp, &"tuple variant"))
+        visitor.visit_seq(self)
     }
 
     fn struct_variant<V: Visitor<'de>>(
         self,
         _fields: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        let unexp = Unexpected::UnitVariant;
-        Err(DeserializeError::invalid_type(unexp, &"struct variant"))
+        if !self.has_headers() || self.in_field {
+            visitor.visit_seq(self)
+        } else {
+            visitor.visit_map(self)
+        }
     }
 }
 
@@ -651,7 +685,10 @@ impl<'a, 'de: 'a, T: DeRecord<'de>> MapAccess<'de>
         &mut self,
         seed: K,
     ) -> Result<K::Value, Self::Error> {
-        seed.deserialize(&mut **self)
+        let in_field = mem::replace(&mut self.in_field, true);
+        let value = seed.deserialize(&mut **self);
+        self.in_field = in_field;
+        value
     }
 }
 
@@ -659,6 +696,7 @@ impl<'a, 'de: 'a, T: DeRecord<'de>> MapAccess<'de>
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DeserializeError {
     field: Option<u64>,
+    header: Option<String>,
     kind: DeserializeErrorKind,
 }
 
@@ -686,7 +724,11 @@ pub enum DeserializeErrorKind {
 
 impl SerdeError for DeserializeError {
     fn custom<T: fmt::Display>(msg: T) -> DeserializeError {
-        DeserializeError { field: None, kind: DEK::Message(msg.to_string()) }
+        DeserializeError {
+            field: None,
+            header: None,
+            kind: DEK::Message(msg.to_string()),
+        }
     }
 }
 
@@ -698,10 +740,12 @@ impl StdError for DeserializeError {
 
 impl fmt::Display for DeserializeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(field) = self.field {
-            write!(f, "field {}: {}", field, self.kind)
-        } else {
-            write!(f, "{}", self.kind)
+        match (self.field, self.header.as_ref()) {
+            (Some(field), Some(header)) => {
+                write!(f, "field {} ({:?}): {}", field, header, self.kind)
+            }
+            (Some(field), None) => write!(f, "field {}: {}", field, self.kind),
+            (None, _) => write!(f, "{}", self.kind),
         }
     }
 }
@@ -730,6 +774,12 @@ impl DeserializeError {
         self.field
     }
 
+    /// Return the name of the header of the field of this error, if the
+    /// field and the headers are known.
+    pub fn header(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+
     /// Return the underlying error kind.
     pub fn kind(&self) -> &DeserializeErrorKind {
         &self.kind
@@ -806,7 +856,7 @@ mod tests {
 
     use super::{deserialize_byte_record, deserialize_string_record};
     use crate::byte_record::ByteRecord;
-    use crate::error::Error;
+    use crate::error::{Error, ErrorKind};
     use crate::string_record::StringRecord;
 
     fn de<D: DeserializeOwned>(fields: &[&str]) -> Result<D, Error> {
@@ -1132,22 +1182,22 @@ mod tests {
         assert_eq!(got, Foo { a: None, b: "foo".into(), c: Some(5) });
     }
 
-    // #[test]
-    // fn option_invalid_field() {
-    //     #[derive(Deserialize, Debug, PartialEq)]
-    //     struct Foo {
-    //         #[serde(deserialize_with = "crate::invalid_option")]
-    //         a: Option<i32>,
-    //         #[serde(deserialize_with = "crate::invalid_option")]
-    //         b: Option<i32>,
-    //         #[serde(deserialize_with = "crate::invalid_option")]
-    //         c: Option<i32>,
-    //     }
+    #[test]
+    fn option_invalid_field() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::invalid_option")]
+            a: Option<i32>,
+            #[serde(deserialize_with = "crate::invalid_option")]
+            b: Option<i32>,
+            #[serde(deserialize_with = "crate::invalid_option")]
+            c: Option<i32>,
+        }
 
-    //     let got: Foo =
-    //         de_headers(&["a", "b", "c"], &["xyz", "", "5"]).unwrap();
-    //     assert_eq!(got, Foo { a: None, b: None, c: Some(5) });
-    // }
+        let got: Foo =
+            de_headers(&["a", "b", "c"], &["xyz", "", "5"]).unwrap();
+        assert_eq!(got, Foo { a: None, b: None, c: Some(5) });
+    }
 
     #[test]
     fn borrowed() {
@@ -1250,4 +1300,45 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn error_field_and_header() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            city: String,
+            pop: u64,
+        }
+
+        let err = de_headers::<Row>(&["city", "pop"], &["Boston", "many"]).unwrap_err();
+        match *err.kind() {
+            ErrorKind::Deserialize { ref err, .. } => {
+                assert_eq!(err.field(), Some(1));
+                assert_eq!(err.header(), Some("pop"));
+                assert_eq!(err.to_string(), "field 1 (\"pop\"): invalid digit found in string");
+            }
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+
+        let headers = ByteRecord::from(vec!["pop", "city"]);
+        let record = ByteRecord::from(vec!["many", "Boston"]);
+        let err = deserialize_byte_record::<Row>(&record, Some(&headers)).unwrap_err();
+        match *err.kind() {
+            ErrorKind::Deserialize { ref err, .. } => {
+                assert_eq!(err.field(), Some(0));
+                assert_eq!(err.header(), Some("pop"));
+            }
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+
+        // Without headers, only the field index is known.
+        let err = de::<(String, u64)>(&["Boston", "many"]).unwrap_err();
+        match *err.kind() {
+            ErrorKind::Deserialize { ref err, .. } => {
+                assert_eq!(err.field(), Some(1));
+                assert_eq!(err.header(), None);
+                assert_eq!(err.to_string(), "field 1: invalid digit found in string");
+            }
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+    }
 }
@@ -85,10 +85,173 @@ pub enum ErrorKind {
         /// The number of fields in the bad record.
         len: u64,
     },
+    /// This error occurs when the first record following the header row has
+    /// a different number of fields than the header row. This error only
+    /// occurs when reading with `has_headers` enabled and the `flexible`
+    /// option disabled.
+    ///
+    /// Mismatches found in later records are reported as `UnequalLengths`.
+    HeaderLengthMismatch {
+        /// The position of the first record after the header row, if
+        /// available.
+        pos: Option<Position>,
+        /// The number of fields in the header row.
+        header_len: u64,
+        /// The number of fields in the record.
+        len: u64,
+    },
+    /// This error occurs when the header row contains the same name more
+    /// than once. This error only occurs when the
+    /// `error_on_duplicate_headers` option of a CSV reader is enabled.
+    DuplicateHeader {
+        /// The position of the header row, if available.
+        pos: Option<Position>,
+        /// The repeated header name.
+        name: String,
+        /// The indices of all fields with this name.
+        fields: Vec<u64>,
+    },
+    /// This error occurs when a reader is asked to select a column by a
+    /// header name that is not in the header row.
+    UnknownHeader {
+        /// The position of the header row, if available.
+        pos: Option<Position>,
+        /// The missing header name.
+        name: String,
+    },
     /// This error occurs when either the `byte_headers` or `headers` methods
     /// are called on a CSV reader that was asked to `seek` before it parsed
     /// the first record.
     Seek,
+    /// This error occurs when a writer in fixed-width mode is given a field
+    /// that does not fit into its column and truncation is disabled.
+    FieldTooWide {
+        /// The index of the offending field within its record.
+        field: u64,
+        /// The width of the column, in bytes.
+        width: u64,
+        /// The length of the field, in bytes.
+        len: u64,
+    },
+    /// This error occurs when a reader reads a field longer than its
+    /// `max_field_size` option allows. The error is reported as soon as
+    /// the limit is exceeded, before the rest of the field is read.
+    FieldTooLarge {
+        /// The position of the record containing the field, if available.
+        pos: Option<Position>,
+        /// The index of the offending field within its record.
+        field_index: u64,
+        /// The number of bytes of the field read so far.
+        size: u64,
+        /// The largest allowed field size, in bytes.
+        limit: u64,
+    },
+    /// This error occurs when a reader reads a record with more field bytes
+    /// than its `max_record_size` option allows. The error is reported as
+    /// soon as the limit is exceeded, before the rest of the record is read.
+    RecordTooLarge {
+        /// The position of the record, if available.
+        pos: Option<Position>,
+        /// The number of field bytes of the record read so far.
+        size: u64,
+        /// The largest allowed record size, in bytes.
+        limit: u64,
+    },
+    /// This error occurs when a reader reads more bytes in total than its
+    /// `max_total_bytes` option allows.
+    InputTooLarge {
+        /// The largest allowed input size, in bytes.
+        limit: u64,
+    },
+    /// This error occurs when a writer is given a field containing a line
+    /// break while its `newline_in_field` option is `NewlineMode::Error`.
+    NewlineInField {
+        /// The index of the offending field within its record.
+        field: u64,
+    },
+    /// This error occurs when a reader expecting a footer row (see
+    /// `AsyncReaderBuilder::expect_footer`) finds that it is missing or does
+    /// not match the data read before it.
+    FooterMismatch {
+        /// The position of the footer row, if available.
+        pos: Option<Position>,
+        /// A description of the mismatch.
+        msg: String,
+    },
+    /// This error occurs when `AsyncReader::summarize` finds a value that is
+    /// not a number in one of the summarized columns, while the
+    /// `error_on_non_numeric` option is enabled.
+    NonNumeric {
+        /// The position of the record in which this error occurred, if
+        /// available.
+        pos: Option<Position>,
+        /// The index of the offending field within its record.
+        field: u64,
+        /// The offending field, lossily converted to UTF-8.
+        value: String,
+    },
+    /// This error occurs when a reader in `strict` mode reads data that does
+    /// not follow RFC 4180.
+    Strict {
+        /// The position of the record containing the violation, if
+        /// available.
+        pos: Option<Position>,
+        /// A description of the violation.
+        reason: String,
+    },
+    /// This error occurs when a field does not match the pattern given for
+    /// its column with `AsyncReaderBuilder::column_patterns`.
+    #[cfg(feature = "regex")]
+    PatternMismatch {
+        /// The position of the record in which this error occurred, if
+        /// available.
+        pos: Option<Position>,
+        /// The index of the offending field within its record.
+        field_index: u64,
+        /// The offending field, lossily converted to UTF-8.
+        value: String,
+    },
+    /// This error occurs when a field of a column that is decoded from
+    /// base64 is not valid base64.
+    #[cfg(feature = "base64")]
+    Base64 {
+        /// The position of the record in which this error occurred, if
+        /// available.
+        pos: Option<Position>,
+        /// The index of the offending field within its record.
+        field: u64,
+        /// The corresponding base64 decoding error.
+        err: base64::DecodeError,
+    },
+    /// This error occurs when records can not be converted into an Arrow
+    /// record batch, e.g. because a field can not be cast to the type of its
+    /// column.
+    #[cfg(feature = "arrow")]
+    Arrow {
+        /// The position of the record in which this error occurred, if
+        /// available.
+        pos: Option<Position>,
+        /// The index of the offending field within its record, if the error
+        /// concerns a single column.
+        field: Option<u64>,
+        /// A description of the error.
+        msg: String,
+    },
+    /// This error occurs when the underlying reader has no data ready for
+    /// longer than the `idle_timeout` of a CSV reader. A record that was
+    /// being read when the timeout hit is kept, and reading it resumes on
+    /// the next call.
+    #[cfg(feature = "timeout")]
+    IdleTimeout,
+    /// An error of this kind occurs only when converting NDJSON input into
+    /// CSV records.
+    #[cfg(feature = "serde_json")]
+    Json {
+        /// The line of the NDJSON input on which the error occurred.
+        line: u64,
+        /// A description of the error.
+        msg: String,
+    },
     /// An error of this kind occurs only when using the Serde serializer.
     #[cfg(feature = "with_serde")]
     Serialize(String),
@@ -119,6 +282,22 @@ impl ErrorKind {
         match *self {
             ErrorKind::Utf8 { ref pos, .. } => pos.as_ref(),
             ErrorKind::UnequalLengths { ref pos, .. } => pos.as_ref(),
+            ErrorKind::HeaderLengthMismatch { ref pos, .. } => pos.as_ref(),
+            ErrorKind::DuplicateHeader { ref pos, .. } => pos.as_ref(),
+            ErrorKind::UnknownHeader { ref pos, .. } => pos.as_ref(),
+            ErrorKind::FieldTooLarge { ref pos, .. } => pos.as_ref(),
+            ErrorKind::RecordTooLarge { ref pos, .. } => pos.as_ref(),
+            ErrorKind::FooterMismatch { ref pos, .. } => pos.as_ref(),
+            ErrorKind::NonNumeric { ref pos, .. } => pos.as_ref(),
+            ErrorKind::Strict { ref pos, .. } => pos.as_ref(),
+            #[cfg(feature = "regex")]
+            ErrorKind::PatternMismatch { ref pos, .. } => pos.as_ref(),
+            #[cfg(feature = "base64")]
+            ErrorKind::Base64 { ref pos, .. } => pos.as_ref(),
+            #[cfg(feature = "arrow")]
+            ErrorKind::Arrow { ref pos, .. } => pos.as_ref(),
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Deserialize { ref pos, .. } => pos.as_ref(),
             _ => None,
         }
     }
@@ -142,7 +321,32 @@ impl StdError for Error {
             ErrorKind::Io(ref err) => Some(err),
             ErrorKind::Utf8 { ref err, .. } => Some(err),
             ErrorKind::UnequalLengths { .. } => None,
+            ErrorKind::HeaderLengthMismatch { .. } => None,
+            ErrorKind::DuplicateHeader { .. } => None,
+            ErrorKind::UnknownHeader { .. } => None,
             ErrorKind::Seek => None,
+            ErrorKind::FieldTooWide { .. } => None,
+            ErrorKind::FieldTooLarge { .. } => None,
+            ErrorKind::RecordTooLarge { .. } => None,
+            ErrorKind::InputTooLarge { .. } => None,
+            ErrorKind::NewlineInField { .. } => None,
+            ErrorKind::FooterMismatch { .. } => None,
+            ErrorKind::NonNumeric { .. } => None,
+            ErrorKind::Strict { .. } => None,
+            #[cfg(feature = "regex")]
+            ErrorKind::PatternMismatch { .. } => None,
+            #[cfg(feature = "base64")]
+            ErrorKind::Base64 { ref err, .. } => Some(err),
+            #[cfg(feature = "arrow")]
+            ErrorKind::Arrow { .. } => None,
+            #[cfg(feature = "timeout")]
+            ErrorKind::IdleTimeout => None,
+            #[cfg(feature = "serde_json")]
+            ErrorKind::Json { .. } => None,
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Serialize(_) => None,
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Deserialize { ref err, .. } => Some(err),
             _ => unreachable!(),
         }
     }
@@ -189,12 +393,219 @@ impl fmt::Display for Error {
                 len,
                 expected_len
             ),
+            ErrorKind::HeaderLengthMismatch { pos: None, header_len, len } => {
+                write!(
+                    f,
+                    "CSV error: \
+                     found record with {} fields, but the header row \
+                     has {} fields",
+                    len, header_len
+                )
+            }
+            ErrorKind::HeaderLengthMismatch {
+                pos: Some(ref pos),
+                header_len,
+                len,
+            } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 found record with {} fields, but the header row \
+                 has {} fields",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                len,
+                header_len
+            ),
+            ErrorKind::DuplicateHeader { ref pos, ref name, ref fields } => {
+                let fields: Vec<String> =
+                    fields.iter().map(|i| i.to_string()).collect();
+                write!(f, "CSV error: ")?;
+                if let Some(ref pos) = *pos {
+                    write!(f, "line {}: ", pos.line())?;
+                }
+                write!(
+                    f,
+                    "header {:?} appears more than once, in fields {}",
+                    name,
+                    fields.join(", ")
+                )
+            }
+            ErrorKind::UnknownHeader { ref pos, ref name } => {
+                write!(f, "CSV error: ")?;
+                if let Some(ref pos) = *pos {
+                    write!(f, "line {}: ", pos.line())?;
+                }
+                write!(f, "no header named {:?}", name)
+            }
             ErrorKind::Seek => write!(
                 f,
                 "CSV error: cannot access headers of CSV data \
                  when the parser was seeked before the first record \
                  could be read"
             ),
+            ErrorKind::FieldTooWide { field, width, len } => write!(
+                f,
+                "CSV error: field {} has {} bytes, which does not fit \
+                 into its fixed width of {} bytes",
+                field, len, width
+            ),
+            ErrorKind::FieldTooLarge { pos: None, field_index, size, limit } => write!(
+                f,
+                "CSV error: field {} has at least {} bytes, which exceeds \
+                 the limit of {} bytes",
+                field_index, size, limit
+            ),
+            ErrorKind::FieldTooLarge {
+                pos: Some(ref pos),
+                field_index,
+                size,
+                limit,
+            } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 field {} has at least {} bytes, which exceeds \
+                 the limit of {} bytes",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                field_index,
+                size,
+                limit
+            ),
+            ErrorKind::RecordTooLarge { pos: None, size, limit } => write!(
+                f,
+                "CSV error: record has at least {} bytes, which exceeds \
+                 the limit of {} bytes",
+                size, limit
+            ),
+            ErrorKind::RecordTooLarge { pos: Some(ref pos), size, limit } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 record has at least {} bytes, which exceeds \
+                 the limit of {} bytes",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                size,
+                limit
+            ),
+            ErrorKind::InputTooLarge { limit } => write!(
+                f,
+                "CSV error: input exceeds the limit of {} bytes",
+                limit
+            ),
+            ErrorKind::NewlineInField { field } => write!(
+                f,
+                "CSV error: field {} contains a line break",
+                field
+            ),
+            ErrorKind::FooterMismatch { ref pos, ref msg } => {
+                write!(f, "CSV error: ")?;
+                if let Some(ref pos) = *pos {
+                    write!(f, "line {}: ", pos.line())?;
+                }
+                write!(f, "footer {}", msg)
+            }
+            ErrorKind::NonNumeric { pos: None, field, ref value } => write!(
+                f,
+                "CSV error: field {}: {:?} is not a number",
+                field, value
+            ),
+            ErrorKind::NonNumeric { pos: Some(ref pos), field, ref value } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 field {}: {:?} is not a number",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                field,
+                value
+            ),
+            ErrorKind::Strict { pos: None, ref reason } => {
+                write!(f, "CSV strict mode error: {}", reason)
+            }
+            ErrorKind::Strict { pos: Some(ref pos), ref reason } => write!(
+                f,
+                "CSV strict mode error: record {} (line: {}, byte: {}): {}",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                reason
+            ),
+            #[cfg(feature = "regex")]
+            ErrorKind::PatternMismatch { pos: None, field_index, ref value } => write!(
+                f,
+                "CSV error: field {}: {:?} does not match the column pattern",
+                field_index, value
+            ),
+            #[cfg(feature = "regex")]
+            ErrorKind::PatternMismatch { pos: Some(ref pos), field_index, ref value } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 field {}: {:?} does not match the column pattern",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                field_index,
+                value
+            ),
+            #[cfg(feature = "base64")]
+            ErrorKind::Base64 { pos: None, field, ref err } => {
+                write!(f, "CSV error: field {}: invalid base64: {}", field, err)
+            }
+            #[cfg(feature = "base64")]
+            ErrorKind::Base64 { pos: Some(ref pos), field, ref err } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): \
+                 field {}: invalid base64: {}",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                field,
+                err
+            ),
+            #[cfg(feature = "arrow")]
+            ErrorKind::Arrow { ref pos, field, ref msg } => {
+                write!(f, "Arrow error: ")?;
+                if let Some(ref pos) = *pos {
+                    write!(
+                        f,
+                        "record {} (line: {}, byte: {}): ",
+                        pos.record(),
+                        pos.line(),
+                        pos.byte()
+                    )?;
+                }
+                if let Some(field) = field {
+                    write!(f, "field {}: ", field)?;
+                }
+                write!(f, "{}", msg)
+            }
+            #[cfg(feature = "timeout")]
+            ErrorKind::IdleTimeout => write!(
+                f,
+                "CSV error: no data received within the idle timeout"
+            ),
+            #[cfg(feature = "serde_json")]
+            ErrorKind::Json { line, ref msg } => {
+                write!(f, "NDJSON error: line {}: {}", line, msg)
+            }
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Serialize(ref err) => write!(f, "CSV write error: {}", err),
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Deserialize { pos: None, ref err } => {
+                write!(f, "CSV deserialize error: {}", err)
+            }
+            #[cfg(feature = "with_serde")]
+            ErrorKind::Deserialize { pos: Some(ref pos), ref err } => write!(
+                f,
+                "CSV deserialize error: record {} (line: {}, byte: {}): {}",
+                pos.record(),
+                pos.line(),
+                pos.byte(),
+                err
+            ),
             _ => unreachable!(),
         }
     }
@@ -325,6 +736,11 @@ impl<W> IntoInnerError<W> {
     pub fn into_writer(self) -> W {
         self.wtr
     }
+
+    /// Replace the writer with the one `f` makes of it.
+    pub(crate) fn map_writer<U, F: FnOnce(W) -> U>(self, f: F) -> IntoInnerError<U> {
+        IntoInnerError { wtr: f(self.wtr), err: self.err }
+    }
 }
 
 impl<W: std::any::Any> StdError for IntoInnerError<W> {
@@ -0,0 +1,120 @@
+use crate::byte_record::ByteRecord;
+
+/// The layout of a footer row, holding the number of data records and
+/// optionally a checksum of the data.
+///
+/// The footer is the last row of the CSV data. Its fields are, in order:
+///
+/// 1. `label`, if set,
+/// 2. the number of records before the footer, not counting the header row,
+/// 3. the checksum, if `checksum` is set: the Adler-32 checksum of all bytes
+///    before the footer row (including the header row), as eight lowercase
+///    hexadecimal digits.
+///
+/// A footer is written by `AsyncWriter::write_footer`, and verified when
+/// reading with `AsyncReaderBuilder::expect_footer`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FooterSpec {
+    /// A fixed first field, marking the row as a footer.
+    pub label: Option<String>,
+    /// Whether a checksum of the data is included.
+    pub checksum: bool,
+}
+
+impl FooterSpec {
+    /// Build the fields of a footer row.
+    pub(crate) fn fields(&self, records: u64, checksum: u32) -> Vec<String> {
+        let mut fields = Vec::with_capacity(3);
+        if let Some(ref label) = self.label {
+            fields.push(label.clone());
+        }
+        fields.push(records.to_string());
+        if self.checksum {
+            fields.push(format!("{:08x}", checksum));
+        }
+        fields
+    }
+
+    /// Check a footer row against the data read before it. On a mismatch,
+    /// this describes the first difference found.
+    pub(crate) fn verify(&self, footer: &ByteRecord, records: u64, checksum: u32) -> Option<String> {
+        let expected = self.fields(records, checksum);
+        if footer.len() != expected.len() {
+            return Some(format!(
+                "has {} fields, but {} were expected",
+                footer.len(),
+                expected.len()
+            ));
+        }
+        let mut found = footer.iter().map(String::from_utf8_lossy);
+        if let Some(ref label) = self.label {
+            let field = found.next().unwrap();
+            if field != label.as_str() {
+                return Some(format!("has label {:?} instead of {:?}", field, label));
+            }
+        }
+        let field = found.next().unwrap();
+        if field.parse::<u64>().ok() != Some(records) {
+            return Some(format!("declares {} records, but {} were read", field, records));
+        }
+        if self.checksum {
+            let field = found.next().unwrap();
+            if u32::from_str_radix(&field, 16).ok() != Some(checksum) {
+                return Some(format!(
+                    "declares checksum {}, but the data read has checksum {:08x}",
+                    field, checksum
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// A running Adler-32 checksum.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+impl Adler32 {
+    const MOD: u32 = 65521;
+
+    /// Add bytes to the checksum.
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        // The sums can not overflow within 5552 bytes.
+        for chunk in data.chunks(5552) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= Adler32::MOD;
+            self.b %= Adler32::MOD;
+        }
+    }
+
+    /// The checksum of all bytes added so far.
+    pub(crate) fn value(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Adler32;
+
+    #[test]
+    fn adler32() {
+        let mut sum = Adler32::default();
+        assert_eq!(sum.value(), 1);
+        sum.update(b"Wiki");
+        sum.update(b"pedia");
+        assert_eq!(sum.value(), 0x11e6_0398);
+    }
+}
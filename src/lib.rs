@@ -46,6 +46,9 @@ type.
 The rest of the types in this crate mostly correspond to more detailed errors,
 position information, configuration knobs or iterator types.
 
+The most commonly used of these types, along with the `StreamExt` trait, can
+be imported at once from the [`prelude`](prelude/index.html) module.
+
 # Setup
 
 Add this to your `Cargo.toml`:
@@ -315,6 +318,15 @@ mod tests {
         std::fs::remove_file(file_in).unwrap();
         std::fs::remove_file(file_out).unwrap();
     }
+
+    #[test]
+    fn alphabetic_header_scheme() {
+        let names: Vec<String> = [0, 1, 25, 26, 27, 701, 702]
+            .iter()
+            .map(|&i| crate::HeaderScheme::Alphabetic.header(i))
+            .collect();
+        assert_eq!(names, vec!["a", "b", "z", "aa", "ab", "zz", "aaa"]);
+    }
  
     cfg_if::cfg_if! {
         if #[cfg(feature = "with_serde")] {
@@ -393,6 +405,8 @@ mod tests {
 
 mod byte_record;
 mod error;
+mod footer;
+mod record_pool;
 mod string_record;
 
 cfg_if::cfg_if! {
@@ -404,6 +418,8 @@ if #[cfg(feature = "with_serde")] {
 mod async_readers;
 mod async_writers;
 
+pub mod prelude;
+
 // pub mod cookbook;
 // pub mod tutorial;
 
@@ -413,23 +429,37 @@ pub use crate::error::{
     Error, ErrorKind, FromUtf8Error, IntoInnerError, Result, Utf8Error,
 };
 pub use crate::string_record::{StringRecord, StringRecordIter};
+pub use crate::footer::FooterSpec;
+pub use crate::record_pool::RecordPool;
 
-pub use crate::async_readers::AsyncReaderBuilder;
-pub use crate::async_writers::AsyncWriterBuilder;
+pub use crate::async_readers::{
+    AsyncReaderBuilder, ColumnSummary, ColumnType, InferredType, ParseTiming, ParserState, Warning,
+    WarningKind,
+};
+pub use crate::async_readers::diff::{diff_records, RecordDiff};
+pub use crate::async_writers::{AsyncWriterBuilder, ByteRecordSink};
+pub use crate::async_writers::tee::{TeeError, TeeWriter};
+#[cfg(feature = "tokio-codec")]
+pub use crate::async_readers::codec::CsvDecoder;
+#[cfg(feature = "tokio-codec")]
+pub use crate::async_writers::codec::CsvEncoder;
 
 cfg_if::cfg_if! {
 if #[cfg(feature = "tokio")] {
     pub use crate::async_readers::{
         ardr_tokio::AsyncReader, 
         ByteRecordsIntoStream, ByteRecordsStream, 
-        StringRecordsIntoStream, StringRecordsStream,
+        RecordOrHeader, SectionEvent, StringRecordsIntoStream, StringRecordsStream,
+        StringRecordsWithHeaderStream, StringRecordsWithRawStream, TableReader,
     };
+    pub use crate::async_writers::atomic::AtomicFile;
     pub use crate::async_writers::awtr_tokio::AsyncWriter;
 } else {
     pub use crate::async_readers::{
         ardr_futures::AsyncReader, 
         ByteRecordsIntoStream, ByteRecordsStream, 
-        StringRecordsIntoStream, StringRecordsStream,
+        RecordOrHeader, SectionEvent, StringRecordsIntoStream, StringRecordsStream,
+        StringRecordsWithHeaderStream, StringRecordsWithRawStream, TableReader,
     };
     pub use crate::async_writers::awtr_futures::AsyncWriter;
 }}
@@ -535,6 +565,90 @@ impl Default for Terminator {
     }
 }
 
+/// The alignment of field data within a fixed-width column.
+///
+/// This is used when writing fixed-width data to decide on which side of
+/// a field the padding goes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    /// Field data starts at the beginning of the column and padding is
+    /// appended after it. This is the default.
+    Left,
+    /// Field data ends at the end of the column and padding is inserted
+    /// before it.
+    Right,
+}
+
+impl Default for Alignment {
+    fn default() -> Alignment {
+        Alignment::Left
+    }
+}
+
+/// How a CSV writer handles fields containing line breaks.
+///
+/// A line break is any of `\r\n`, `\r` or `\n`. See
+/// `AsyncWriterBuilder::newline_in_field`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NewlineMode {
+    /// Write the field as is, quoting it as needed. This is the default.
+    Keep,
+    /// Replace every line break with the given byte.
+    Replace(u8),
+    /// Refuse to write the field, returning a `NewlineInField` error.
+    Error,
+}
+
+impl Default for NewlineMode {
+    fn default() -> NewlineMode {
+        NewlineMode::Keep
+    }
+}
+
+/// The naming scheme of headers generated for CSV data without a header row.
+///
+/// Generated names are derived from the column number. See
+/// `AsyncReaderBuilder::generate_headers`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeaderScheme {
+    /// `col1`, `col2`, ... This is the default.
+    Numbered,
+    /// `a`, `b`, ..., `z`, `aa`, `ab`, ... like spreadsheet columns, but in
+    /// lower case.
+    Alphabetic,
+    /// The given prefix followed by the column number, starting at 1.
+    Prefixed(String),
+}
+
+impl HeaderScheme {
+    /// Returns the name of the column with the given zero based index.
+    fn header(&self, index: usize) -> String {
+        match *self {
+            HeaderScheme::Numbered => format!("col{}", index + 1),
+            HeaderScheme::Prefixed(ref prefix) => {
+                format!("{}{}", prefix, index + 1)
+            }
+            HeaderScheme::Alphabetic => {
+                let mut name = Vec::new();
+                let mut n = index + 1;
+                while n > 0 {
+                    n -= 1;
+                    name.push(b'a' + (n % 26) as u8);
+                    n /= 26;
+                }
+                name.reverse();
+                String::from_utf8(name).unwrap()
+            }
+        }
+    }
+}
+
+impl Default for HeaderScheme {
+    fn default() -> HeaderScheme {
+        HeaderScheme::Numbered
+    }
+}
+
 /// The whitespace preservation behavior when reading CSV data.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Trim {
@@ -571,3 +685,80 @@ impl Default for Trim {
     }
 }
 
+/// How a CSV reader handles records that are not valid UTF-8 when reading
+/// them as strings.
+///
+/// See `AsyncReaderBuilder::invalid_utf8`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InvalidUtf8Policy {
+    /// Report a `Utf8` error for the record. This is the default.
+    Error,
+    /// Replace every invalid sequence with U+FFFD, the Unicode replacement
+    /// character.
+    ReplaceLossy,
+    /// Drop the record and read the next one instead.
+    Skip,
+}
+
+impl Default for InvalidUtf8Policy {
+    fn default() -> InvalidUtf8Policy {
+        InvalidUtf8Policy::Error
+    }
+}
+
+
+/// A custom Serde deserializer for possibly invalid `Option<T>` fields.
+///
+/// When deserializing CSV data, it is sometimes desirable to simply ignore
+/// fields with invalid data. For example, there might be a field that is
+/// usually a number, but will occasionally contain garbage data that causes
+/// number parsing to fail.
+///
+/// You might be inclined to use, say, `Option<i32>` for fields such at
+/// this. By default, however, `Option<i32>` will either capture *empty*
+/// fields with `None` or valid numeric fields with `Some(the_number)`. If
+/// the field is non-empty and not a valid number, then deserialization will
+/// return an error instead of using `None`.
+///
+/// This function allows you to override this default behavior. Namely, if
+/// `Option<T>` is deserialized with non-empty but invalid data, then the
+/// error will be ignored and `None` will be used.
+///
+/// This function is used with the `#[serde(deserialize_with = "...")]`
+/// attribute, and is only available with the `with_serde` feature.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+///
+/// use csv_async::StringRecord;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Eq, PartialEq)]
+/// struct Row {
+///     #[serde(deserialize_with = "csv_async::invalid_option")]
+///     a: Option<i32>,
+///     #[serde(deserialize_with = "csv_async::invalid_option")]
+///     b: Option<i32>,
+///     #[serde(deserialize_with = "csv_async::invalid_option")]
+///     c: Option<i32>,
+/// }
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<(), Box<dyn Error>> {
+///     let headers = StringRecord::from(vec!["a", "b", "c"]);
+///     let record = StringRecord::from(vec!["1", "", "xyz"]);
+///     let row: Row = record.deserialize(Some(&headers))?;
+///     assert_eq!(row, Row { a: Some(1), b: None, c: None });
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "with_serde")]
+pub fn invalid_option<'de, D, T>(de: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Option<T>: serde::Deserialize<'de>,
+{
+    serde::Deserialize::deserialize(de).or_else(|_| Ok(None))
+}
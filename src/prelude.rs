@@ -0,0 +1,49 @@
+/*!
+Re-exports of the most commonly used types and traits.
+
+Glob-importing this module brings the reader and writer types, their
+builders, the record types and the configuration enums into scope, along
+with the `StreamExt` trait needed to iterate over records. It is
+`futures::stream::StreamExt`, or `tokio_stream::StreamExt` with the `tokio`
+feature.
+
+The crate's `Result` and `Error` types are left out, so they do not shadow
+the standard ones.
+
+# Example
+
+```
+use csv_async::prelude::*;
+
+# fn main() { async_std::task::block_on(async {example().await.unwrap()}); }
+async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    let data = "city,pop\nBoston,4628910\n";
+    let mut rdr = AsyncReaderBuilder::new()
+        .trim(Trim::All)
+        .create_reader(data.as_bytes());
+    let mut records = rdr.records();
+    let record: StringRecord = records.next().await.unwrap()?;
+    assert_eq!(record, vec!["Boston", "4628910"]);
+
+    let mut wtr = AsyncWriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .terminator(Terminator::CRLF)
+        .create_writer(vec![]);
+    wtr.write_record(&record).await?;
+    assert_eq!(wtr.into_inner().await?, b"\"Boston\",\"4628910\"\r\n");
+    Ok(())
+}
+```
+*/
+
+pub use crate::{
+    AsyncReader, AsyncReaderBuilder, AsyncWriter, AsyncWriterBuilder,
+    ByteRecord, ErrorKind, Position, QuoteStyle, StringRecord, Terminator, Trim,
+};
+#[cfg(feature = "with_serde")]
+pub use crate::{AsyncDeserializer, AsyncSerializer};
+
+#[cfg(not(feature = "tokio"))]
+pub use futures::stream::StreamExt;
+#[cfg(feature = "tokio")]
+pub use tokio_stream::StreamExt;
@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::byte_record::ByteRecord;
+
+/// A pool of byte records, whose buffers are reused instead of allocating
+/// fresh records.
+///
+/// A pool is shared by the readers created with
+/// `AsyncReaderBuilder::record_pool`. Their record streams take the records
+/// they yield from the pool, and records handed back with `put` are cleared
+/// and kept for the next reader that needs one. This saves allocations when
+/// parsing many small CSV files, e.g. in a server.
+///
+/// A record is only worth reusing if its buffer is large enough, so records
+/// whose buffer can hold the last record read are preferred.
+#[derive(Debug)]
+pub struct RecordPool {
+    idle: Mutex<Vec<ByteRecord>>,
+    max_idle: usize,
+    reused: AtomicU64,
+}
+
+impl RecordPool {
+    /// Create an empty pool keeping at most `max_idle` records.
+    ///
+    /// Records handed back while the pool is full are dropped.
+    pub fn new(max_idle: usize) -> RecordPool {
+        RecordPool {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            reused: AtomicU64::new(0),
+        }
+    }
+
+    /// Take an empty record from the pool, or allocate a new one if the
+    /// pool is empty.
+    pub fn get(&self) -> ByteRecord {
+        self.get_with_capacity(0)
+    }
+
+    /// Hand a record back to the pool, to reuse its buffer.
+    pub fn put(&self, mut record: ByteRecord) {
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < self.max_idle {
+            record.clear();
+            record.set_position(None);
+            idle.push(record);
+        }
+    }
+
+    /// The number of records in the pool, waiting to be reused.
+    pub fn idle(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+
+    /// The number of records taken from the pool instead of allocating
+    /// them.
+    pub fn reused(&self) -> u64 {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    /// Take an empty record, preferring the most recently returned one
+    /// with a buffer of at least `capacity` bytes.
+    pub(crate) fn get_with_capacity(&self, capacity: usize) -> ByteRecord {
+        let mut idle = self.idle.lock().unwrap();
+        let found = idle.iter_mut().rposition(|record| record.as_parts().0.len() >= capacity);
+        let record = match found {
+            Some(i) => Some(idle.swap_remove(i)),
+            None => idle.pop(),
+        };
+        match record {
+            Some(record) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                record
+            }
+            None => ByteRecord::new(),
+        }
+    }
+}
@@ -120,7 +120,7 @@ impl<'a, 'w> Serializer for &'a mut SeRecord<'w> {
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        self.wtr.write_field(&[])
+        self.wtr.write_none()
     }
 
     fn serialize_some<T: ?Sized + Serialize>(
@@ -162,9 +162,12 @@ impl<'a, 'w> Serializer for &'a mut SeRecord<'w> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
+        if self.wtr.tagged_enums() {
+            self.wtr.write_field(variant)?;
+        }
         value.serialize(self)
     }
 
@@ -194,10 +197,16 @@ impl<'a, 'w> Serializer for &'a mut SeRecord<'w> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Err(Error::custom("serializing enum tuple variants is not supported"))
+        if !self.wtr.tagged_enums() {
+            return Err(Error::custom(
+                "serializing enum tuple variants is not supported",
+            ));
+        }
+        self.wtr.write_field(variant)?;
+        Ok(self)
     }
 
     fn serialize_map(
@@ -224,10 +233,16 @@ impl<'a, 'w> Serializer for &'a mut SeRecord<'w> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Err(Error::custom("serializing enum struct variants is not supported"))
+        if !self.wtr.tagged_enums() {
+            return Err(Error::custom(
+                "serializing enum struct variants is not supported",
+            ));
+        }
+        self.wtr.write_field(variant)?;
+        Ok(self)
     }
 }
 
@@ -285,13 +300,13 @@ impl<'a, 'w> SerializeTupleVariant for &'a mut SeRecord<'w> {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        unreachable!()
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unreachable!()
+        Ok(())
     }
 }
 
@@ -342,13 +357,13 @@ impl<'a, 'w> SerializeStructVariant for &'a mut SeRecord<'w> {
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
         _key: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        unreachable!()
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unreachable!()
+        Ok(())
     }
 }
 
@@ -447,11 +462,14 @@ enum HeaderState {
 struct SeHeader<'w> {
     wtr: &'w mut MemWriter,
     state: HeaderState,
+    /// The name of a tagged enum, written as the header of its tag column
+    /// once a struct field of the payload is encountered.
+    tag: Option<&'static str>,
 }
 
 impl<'w> SeHeader<'w> {
     fn new(wtr: &'w mut MemWriter) -> Self {
-        SeHeader { wtr: wtr, state: HeaderState::Write }
+        SeHeader { wtr: wtr, state: HeaderState::Write, tag: None }
     }
 
     fn wrote_header(&self) -> bool {
@@ -488,6 +506,41 @@ impl<'w> SeHeader<'w> {
             Ok(self)
         }
     }
+
+    fn handle_tag(&mut self, name: &'static str) -> Result<&mut Self, Error> {
+        match self.state {
+            HeaderState::InStructField => {
+                return Err(error_container_inside_struct(name))
+            }
+            HeaderState::Write => self.tag = Some(name),
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    fn handle_struct_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        // Grab old state and update state to `EncounteredStructField`.
+        let old_state =
+            mem::replace(&mut self.state, HeaderState::EncounteredStructField);
+        if let HeaderState::ErrorIfWrite(err) = old_state {
+            return Err(err);
+        }
+        if let Some(tag) = self.tag.take() {
+            self.wtr.write_field(tag)?;
+        }
+        self.wtr.write_field(key)?;
+
+        // Check that there aren't any containers in the value.
+        self.state = HeaderState::InStructField;
+        value.serialize(&mut *self)?;
+        self.state = HeaderState::EncounteredStructField;
+
+        Ok(())
+    }
 }
 
 impl<'a, 'w> Serializer for &'a mut SeHeader<'w> {
@@ -613,9 +666,13 @@ impl<'a, 'w> Serializer for &'a mut SeHeader<'w> {
         name: &'static str,
         _variant_index: u32,
         variant: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        self.handle_scalar(format!("{}::{}(_)", name, variant))
+        if self.wtr.tagged_enums() {
+            value.serialize(self.handle_tag(name)?)
+        } else {
+            self.handle_scalar(format!("{}::{}(_)", name, variant))
+        }
     }
 
     fn serialize_seq(
@@ -642,12 +699,17 @@ impl<'a, 'w> Serializer for &'a mut SeHeader<'w> {
 
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Err(Error::custom("serializing enum tuple variants is not supported"))
+        if !self.wtr.tagged_enums() {
+            return Err(Error::custom(
+                "serializing enum tuple variants is not supported",
+            ));
+        }
+        self.handle_tag(name)
     }
 
     fn serialize_map(
@@ -672,12 +734,17 @@ impl<'a, 'w> Serializer for &'a mut SeHeader<'w> {
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Err(Error::custom("serializing enum struct variants is not supported"))
+        if !self.wtr.tagged_enums() {
+            return Err(Error::custom(
+                "serializing enum struct variants is not supported",
+            ));
+        }
+        self.handle_tag(name)
     }
 }
 
@@ -735,13 +802,13 @@ impl<'a, 'w> SerializeTupleVariant for &'a mut SeHeader<'w> {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        unreachable!()
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unreachable!()
+        Ok(())
     }
 }
 
@@ -777,20 +844,7 @@ impl<'a, 'w> SerializeStruct for &'a mut SeHeader<'w> {
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        // Grab old state and update state to `EncounteredStructField`.
-        let old_state =
-            mem::replace(&mut self.state, HeaderState::EncounteredStructField);
-        if let HeaderState::ErrorIfWrite(err) = old_state {
-            return Err(err);
-        }
-        self.wtr.write_field(key)?;
-
-        // Check that there aren't any containers in the value.
-        self.state = HeaderState::InStructField;
-        value.serialize(&mut **self)?;
-        self.state = HeaderState::EncounteredStructField;
-
-        Ok(())
+        self.handle_struct_field(key, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -804,14 +858,14 @@ impl<'a, 'w> SerializeStructVariant for &'a mut SeHeader<'w> {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _key: &'static str,
-        _value: &T,
+        key: &'static str,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        unreachable!()
+        self.handle_struct_field(key, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unreachable!()
+        Ok(())
     }
 }
 
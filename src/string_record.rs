@@ -3,6 +3,7 @@ use std::iter::FromIterator;
 use std::ops::{self, Range};
 use std::result;
 use std::str;
+use std::task::{Context, Poll};
 
 #[cfg(not(feature = "tokio"))]
 use futures::io;
@@ -13,11 +14,14 @@ use tokio::io;
 #[cfg(feature = "with_serde")]
 use serde::de::Deserialize;
 
-use crate::async_readers::AsyncReaderImpl;
+use futures::ready;
+
+use crate::async_readers::{AsyncReaderImpl, WarningKind};
 use crate::byte_record::{ByteRecord, ByteRecordIter, Position};
 #[cfg(feature = "with_serde")]
 use crate::deserializer::deserialize_string_record;
 use crate::error::{Error, ErrorKind, FromUtf8Error, Result};
+use crate::{AsyncWriter, InvalidUtf8Policy};
 
 /// A single CSV record stored as valid UTF-8 bytes.
 ///
@@ -349,6 +353,7 @@ impl StringRecord {
         let mut trimmed =
             StringRecord::with_capacity(self.as_slice().len(), self.len());
         trimmed.set_position(self.position().cloned());
+        trimmed.0.set_quoted(self.0.take_quoted());
         for field in &*self {
             trimmed.push_field(field.trim());
         }
@@ -618,15 +623,47 @@ impl StringRecord {
     ) -> Result<D> {
         deserialize_string_record(self, headers)
     }
+
+    /// Write this record to the given CSV writer, using the writer's
+    /// configuration.
+    ///
+    /// This is the same as calling `write_record` on the writer with this
+    /// record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::error::Error;
+    ///
+    /// use csv_async::{AsyncWriter, StringRecord};
+    ///
+    /// # fn main() { futures::executor::block_on(example()).unwrap() }
+    /// async fn example() -> Result<(), Box<dyn Error>> {
+    ///     let mut wtr = AsyncWriter::from_writer(vec![]);
+    ///     let record = StringRecord::from(vec!["Boston", "United States"]);
+    ///     record.write_to(&mut wtr).await?;
+    ///
+    ///     let data = String::from_utf8(wtr.into_inner().await?)?;
+    ///     assert_eq!(data, "Boston,United States\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write_to<W: io::AsyncWrite + std::marker::Unpin>(
+        &self,
+        wtr: &mut AsyncWriter<W>,
+    ) -> Result<()> {
+        wtr.write_record(self).await
+    }
     
     /// A safe function for reading CSV data into a `StringRecord`.
     ///
     /// This relies on the internal representation of `StringRecord`.
     #[inline(always)]
-    pub(crate) async fn read<R: io::AsyncRead + std::marker::Unpin>(
+    pub(crate) fn poll_read<R: io::AsyncRead + std::marker::Unpin>(
         &mut self,
+        cx: &mut Context,
         rdr: &mut AsyncReaderImpl<R>,
-    ) -> Result<bool> {
+    ) -> Poll<Result<bool>> {
         // SAFETY: This code is critical to upholding the safety of other code
         // blocks in this module. Namely, after calling `read_byte_record`,
         // it is possible for `record` to contain invalid UTF-8. We check for
@@ -634,23 +671,43 @@ impl StringRecord {
         // clear the record. (It is bad for `record` to contain invalid UTF-8
         // because other accessor methods, like `get`, assume that every field
         // is valid UTF-8.)
-        let pos = rdr.position().clone();
-        let read_res = rdr.read_byte_record(&mut self.0).await;
-        let utf8_res = match self.0.validate() {
-            Ok(()) => Ok(()),
-            Err(err) => {
-                // If this record isn't valid UTF-8, then completely wipe it.
-                self.0.clear();
-                Err(err)
-            }
+        let (read_res, pos, utf8_res) = loop {
+            let read_res = ready!(rdr.poll_read_byte_record(cx, &mut self.0));
+            let pos = self.0.position().cloned();
+            let utf8_res = match self.0.validate() {
+                Ok(()) => Ok(()),
+                Err(err) if read_res.is_ok() && rdr.invalid_utf8() == InvalidUtf8Policy::Skip => {
+                    let field = err.field() as u64;
+                    rdr.warn(pos.as_ref(), WarningKind::SkippedInvalidUtf8 { field });
+                    continue;
+                }
+                Err(err) if read_res.is_ok()
+                    && rdr.invalid_utf8() == InvalidUtf8Policy::ReplaceLossy =>
+                {
+                    let field = err.field() as u64;
+                    rdr.warn(pos.as_ref(), WarningKind::ReplacedInvalidUtf8 { field });
+                    let mut bytes = std::mem::take(&mut self.0);
+                    let quoted = bytes.take_quoted();
+                    *self = StringRecord::from_byte_record_lossy(bytes);
+                    self.0.set_position(pos.clone());
+                    self.0.set_quoted(quoted);
+                    Ok(())
+                }
+                Err(err) => {
+                    // If this record isn't valid UTF-8, then completely wipe it.
+                    self.0.clear();
+                    Err(err)
+                }
+            };
+            break (read_res, pos, utf8_res);
         };
-        match (read_res, utf8_res) {
+        Poll::Ready(match (read_res, utf8_res) {
             (Err(err), _) => Err(err),
             (Ok(_), Err(err)) => {
-                Err(Error::new(ErrorKind::Utf8 { pos: Some(pos), err: err }))
+                Err(Error::new(ErrorKind::Utf8 { pos, err: err }))
             }
             (Ok(eof), Ok(())) => Ok(eof),
-        }
+        })
     }
 }
 
@@ -747,7 +804,10 @@ impl<'r> DoubleEndedIterator for StringRecordIter<'r> {
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
+
     use crate::string_record::StringRecord;
+    use crate::{AsyncReaderBuilder, AsyncWriterBuilder};
 
     #[test]
     fn trim_front() {
@@ -836,4 +896,51 @@ mod tests {
         let test2 = StringRecord::from(vec!["12", "34"]);
         assert_ne!(test1, test2);
     }
+
+    #[test]
+    fn write_to_writer() {
+        block_on(async {
+            let mut wtr = AsyncWriterBuilder::new().delimiter(b';').create_writer(vec![]);
+            let record = StringRecord::from(vec!["a", "b;c", ""]);
+            record.write_to(&mut wtr).await.unwrap();
+            StringRecord::from(vec!["1", "2", "3"]).write_to(&mut wtr).await.unwrap();
+            let data = wtr.into_inner().await.unwrap();
+            assert_eq!(data, b"a;\"b;c\";\n1;2;3\n");
+
+            let mut rdr = AsyncReaderBuilder::new()
+                .delimiter(b';')
+                .has_headers(false)
+                .create_reader(&data[..]);
+            let mut got = StringRecord::new();
+            assert!(rdr.read_record(&mut got).await.unwrap());
+            assert_eq!(got, record);
+        });
+    }
+
+    #[cfg(feature = "with_serde")]
+    #[test]
+    fn deserialize() {
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Row {
+            city: String,
+            pop: Option<u64>,
+        }
+
+        let headers = StringRecord::from(vec!["pop", "city"]);
+        let record = StringRecord::from(vec!["4628910", "Boston"]);
+        let row: Row = record.deserialize(Some(&headers)).unwrap();
+        assert_eq!(row, Row { city: "Boston".to_string(), pop: Some(4628910) });
+
+        let record = StringRecord::from(vec!["", "Concord"]);
+        let row: Row = record.deserialize(Some(&headers)).unwrap();
+        assert_eq!(row, Row { city: "Concord".to_string(), pop: None });
+
+        // Without headers, fields are deserialized by position.
+        let record = StringRecord::from(vec!["Boston", "4628910"]);
+        let row: (&str, u64) = record.deserialize(None).unwrap();
+        assert_eq!(row, ("Boston", 4628910));
+        assert!(record.deserialize::<(u64, u64)>(None).is_err());
+    }
 }